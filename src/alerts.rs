@@ -0,0 +1,382 @@
+//! Price alerts evaluated against quotes.
+//!
+//! [`Alerts`] holds declarative [`Rule`]s — a price above or below a level, a percent change
+//! over a number of minutes, a change of the market cap rank — and checks them against the data
+//! of `quotes_latest_by_id()`. Events go to callbacks and channels.
+//!
+//! Threshold rules fire when their condition starts to hold and are re-armed once it no longer
+//! does, so a price staying above a level is reported once. A rule that fired stays silent for
+//! its cooldown on top of that; rank changes during the cooldown are dropped, not held back.
+//!
+#![cfg_attr(feature = "blocking", doc = " ```rust,no_run")]
+#![cfg_attr(not(feature = "blocking"), doc = " ```rust,ignore")]
+//! use cmc::alerts::{Alerts, Condition, Rule};
+//! use cmc::api::Amount;
+//! use cmc::{Cmc, CryptoId};
+//! use std::time::Duration;
+//!
+//! let cmc = Cmc::new("<API KEY>");
+//!
+//! let mut alerts = Alerts::new()
+//!     .rule(Rule::new("btc-high", CryptoId(1), Condition::Above(Amount::from(30_000))))
+//!     .rule(
+//!         Rule::new("eth-move", CryptoId(1027), Condition::PercentChange {
+//!             percent: Amount::from(5),
+//!             minutes: 15,
+//!         })
+//!         .cooldown(Duration::from_secs(3600)),
+//!     )
+//!     .rule(Rule::new("eth-rank", CryptoId(1027), Condition::RankChange))
+//!     .on_alert(|event| println!("{}", event));
+//!
+//! loop {
+//!     match cmc.quotes_latest_by_id(alerts.ids()) {
+//!         Ok(quotes) => {
+//!             alerts.evaluate(&quotes);
+//!         }
+//!         Err(err) => println!("{}", err),
+//!     }
+//!     std::thread::sleep(Duration::from_secs(60));
+//! }
+//! ```
+
+use crate::api::cryptocurrency::quotes_latest_v2::{CryptoCurrency, QLv2Id};
+use crate::api::{Amount, Timestamp};
+use crate::ids::CryptoId;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::sync::mpsc::Sender;
+use std::time::{Duration, Instant};
+
+/// What a [`Rule`] watches for.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Condition {
+    /// The price is above the level.
+    Above(Amount),
+    /// The price is below the level.
+    Below(Amount),
+    /// The price moved by at least `percent` (up or down) within `minutes`, measured between
+    /// evaluations.
+    PercentChange { percent: Amount, minutes: u64 },
+    /// The market cap rank changed.
+    RankChange,
+}
+
+/// A named condition on one cryptocurrency.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rule {
+    name: String,
+    id: CryptoId,
+    condition: Condition,
+    cooldown: Duration,
+}
+
+impl Rule {
+    pub fn new<N: Into<String>>(name: N, id: CryptoId, condition: Condition) -> Self {
+        Self {
+            name: name.into(),
+            id,
+            condition,
+            cooldown: Duration::ZERO,
+        }
+    }
+
+    /// Minimum time between two events of the rule. None by default.
+    pub fn cooldown(mut self, cooldown: Duration) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+}
+
+/// What happened, with the values that triggered it.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AlertKind {
+    Above {
+        price: Amount,
+        level: Amount,
+    },
+    Below {
+        price: Amount,
+        level: Amount,
+    },
+    PercentChange {
+        from: Amount,
+        to: Amount,
+        percent: Amount,
+        minutes: u64,
+    },
+    RankChange {
+        from: u64,
+        to: u64,
+    },
+}
+
+/// A rule that fired.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AlertEvent {
+    /// Name of the rule.
+    pub rule: String,
+    pub id: CryptoId,
+    pub symbol: String,
+    pub kind: AlertKind,
+    /// `last_updated` of the quote that triggered the event.
+    pub last_updated: Timestamp,
+}
+
+impl fmt::Display for AlertEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {} ", self.rule, self.symbol)?;
+        match &self.kind {
+            AlertKind::Above { price, level } => write!(f, "above {}: {}", level, price),
+            AlertKind::Below { price, level } => write!(f, "below {}: {}", level, price),
+            AlertKind::PercentChange {
+                from,
+                to,
+                percent,
+                minutes,
+            } => write!(
+                f,
+                "moved {:.2}% in {} min: {} -> {}",
+                percent, minutes, from, to
+            ),
+            AlertKind::RankChange { from, to } => write!(f, "rank {} -> {}", from, to),
+        }
+    }
+}
+
+enum Sink {
+    Callback(Box<dyn FnMut(&AlertEvent) + Send>),
+    Channel(Sender<AlertEvent>),
+}
+
+/// Evaluation state of a rule.
+struct Armed {
+    rule: Rule,
+    armed: bool,
+    fired_at: Option<Instant>,
+    rank: Option<u64>,
+}
+
+/// Rules, their state and where events go.
+pub struct Alerts {
+    currency: String,
+    rules: Vec<Armed>,
+    history: HashMap<CryptoId, VecDeque<(Instant, Amount)>>,
+    sinks: Vec<Sink>,
+}
+
+impl Default for Alerts {
+    fn default() -> Self {
+        Self {
+            currency: "USD".into(),
+            rules: Vec::new(),
+            history: HashMap::new(),
+            sinks: Vec::new(),
+        }
+    }
+}
+
+impl Alerts {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Quote currency the levels are in, "USD" by default. It has to be among the `convert`
+    /// currencies of the client. Symbols are uppercased, like the keys of `quote`.
+    pub fn currency<T: Into<String>>(mut self, currency: T) -> Self {
+        self.currency = currency.into().to_uppercase();
+        self
+    }
+
+    /// Adds a rule.
+    pub fn rule(mut self, rule: Rule) -> Self {
+        self.rules.push(Armed {
+            rule,
+            armed: true,
+            fired_at: None,
+            rank: None,
+        });
+        self
+    }
+
+    /// Calls `f` with every event.
+    pub fn on_alert<F: FnMut(&AlertEvent) + Send + 'static>(mut self, f: F) -> Self {
+        self.sinks.push(Sink::Callback(Box::new(f)));
+        self
+    }
+
+    /// Sends every event to `sender`. Events are dropped once the receiver is gone.
+    pub fn channel(mut self, sender: Sender<AlertEvent>) -> Self {
+        self.sinks.push(Sink::Channel(sender));
+        self
+    }
+
+    /// Ids of all rules, to pass to `quotes_latest_by_id()`.
+    pub fn ids(&self) -> Vec<CryptoId> {
+        let mut ids: Vec<_> = self.rules.iter().map(|armed| armed.rule.id).collect();
+        ids.sort();
+        ids.dedup();
+        ids
+    }
+
+    /// Checks all rules against `quotes`, delivers the events and returns them.
+    pub fn evaluate(&mut self, quotes: &QLv2Id) -> Vec<AlertEvent> {
+        self.evaluate_at(quotes, Instant::now())
+    }
+
+    /// Like [`evaluate()`](Alerts::evaluate), with `at` as the current time, e.g. to replay
+    /// recorded quotes.
+    pub fn evaluate_at(&mut self, quotes: &QLv2Id, at: Instant) -> Vec<AlertEvent> {
+        for quote in quotes.data.values() {
+            if let Some(price) = self.price(quote) {
+                let id = CryptoId(quote.id as u64);
+                self.history.entry(id).or_default().push_back((at, price));
+            }
+        }
+
+        let mut events = Vec::new();
+        for index in 0..self.rules.len() {
+            let id = self.rules[index].rule.id;
+            let Some(quote) = quotes.data.get(&id.to_string()) else {
+                continue;
+            };
+            if let Some(kind) = self.check(index, quote, at) {
+                let armed = &mut self.rules[index];
+                armed.fired_at = Some(at);
+                events.push(AlertEvent {
+                    rule: armed.rule.name.clone(),
+                    id,
+                    symbol: quote.symbol.clone(),
+                    kind,
+                    last_updated: quote.last_updated.to_owned(),
+                });
+            }
+        }
+        self.prune(at);
+
+        for event in &events {
+            self.deliver(event);
+        }
+        events
+    }
+
+    /// Event of one rule, if it fires.
+    fn check(&mut self, index: usize, quote: &CryptoCurrency, at: Instant) -> Option<AlertKind> {
+        let price = self.price(quote);
+        let reference = match self.rules[index].rule.condition {
+            Condition::PercentChange { minutes, .. } => self.reference(quote, minutes, at),
+            _ => None,
+        };
+        let armed = &mut self.rules[index];
+        let cooling = armed
+            .fired_at
+            .is_some_and(|fired| at < fired + armed.rule.cooldown);
+
+        let kind = match armed.rule.condition {
+            Condition::Above(level) => price
+                .filter(|price| *price > level)
+                .map(|price| AlertKind::Above { price, level }),
+            Condition::Below(level) => price
+                .filter(|price| *price < level)
+                .map(|price| AlertKind::Below { price, level }),
+            Condition::PercentChange { percent, minutes } => {
+                price.zip(reference).and_then(|(to, from)| {
+                    if from == Amount::default() {
+                        return None;
+                    }
+                    let change = (to - from) * Amount::from(100) / from;
+                    (change.abs() >= percent).then_some(AlertKind::PercentChange {
+                        from,
+                        to,
+                        percent: change,
+                        minutes,
+                    })
+                })
+            }
+            Condition::RankChange => {
+                // The rank is tracked during the cooldown too, so the next event starts from
+                // the rank seen last rather than the one before the suppressed changes.
+                let rank = quote.cmc_rank?;
+                match armed.rank.replace(rank) {
+                    Some(from) if from != rank && !cooling => {
+                        return Some(AlertKind::RankChange { from, to: rank });
+                    }
+                    _ => None,
+                }
+            }
+        };
+
+        match kind {
+            Some(kind) if armed.armed && !cooling => {
+                armed.armed = false;
+                Some(kind)
+            }
+            Some(_) => None,
+            None => {
+                armed.armed = true;
+                None
+            }
+        }
+    }
+
+    fn price(&self, quote: &CryptoCurrency) -> Option<Amount> {
+        quote
+            .quote
+            .get(&self.currency)
+            .and_then(|quote| quote.price)
+    }
+
+    /// Latest price seen at least `minutes` before `at`.
+    fn reference(&self, quote: &CryptoCurrency, minutes: u64, at: Instant) -> Option<Amount> {
+        let cutoff = at.checked_sub(Duration::from_secs(minutes * 60))?;
+        self.history
+            .get(&CryptoId(quote.id as u64))?
+            .iter()
+            .take_while(|(seen, _)| *seen <= cutoff)
+            .last()
+            .map(|(_, price)| *price)
+    }
+
+    /// Drops prices older than any percent change rule needs.
+    fn prune(&mut self, at: Instant) {
+        let mut keep: HashMap<CryptoId, u64> = HashMap::new();
+        for armed in &self.rules {
+            if let Condition::PercentChange { minutes, .. } = armed.rule.condition {
+                let entry = keep.entry(armed.rule.id).or_default();
+                *entry = (*entry).max(minutes);
+            }
+        }
+        self.history.retain(|id, prices| {
+            let Some(minutes) = keep.get(id) else {
+                return false;
+            };
+            if let Some(cutoff) = at.checked_sub(Duration::from_secs(minutes * 60)) {
+                while prices.len() > 1 && prices[1].0 <= cutoff {
+                    prices.pop_front();
+                }
+            }
+            true
+        });
+    }
+
+    fn deliver(&mut self, event: &AlertEvent) {
+        self.sinks.retain_mut(|sink| match sink {
+            Sink::Callback(f) => {
+                f(event);
+                true
+            }
+            Sink::Channel(sender) => sender.send(event.clone()).is_ok(),
+        });
+    }
+}
+
+impl fmt::Debug for Alerts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rules: Vec<_> = self.rules.iter().map(|armed| &armed.rule).collect();
+        f.debug_struct("Alerts")
+            .field("currency", &self.currency)
+            .field("rules", &rules)
+            .field("sinks", &self.sinks.len())
+            .finish()
+    }
+}
@@ -0,0 +1,119 @@
+//! Blocking client, generated from the shared core in `crate::client`.
+
+use crate::api::response::Response;
+#[cfg(any(feature = "cryptocurrency", feature = "tools"))]
+use crate::api::Amount;
+use crate::api::{CmcResult, Config};
+use crate::cache::Lookup;
+use crate::client::client;
+use crate::errors::{CmcErrors, ConfigError};
+#[cfg(any(feature = "cryptocurrency", feature = "exchange"))]
+use crate::ids::CryptoId;
+#[cfg(feature = "exchange")]
+use crate::ids::ExchangeQuery;
+#[cfg(feature = "cryptocurrency")]
+use crate::ids::{AssetQuery, CategoryId, Slug};
+#[cfg(any(feature = "cryptocurrency", feature = "tools"))]
+use crate::ids::{AssetRef, Symbol};
+use crate::ids::{CurrencyId, Identifiers};
+#[cfg(feature = "cryptocurrency")]
+use crate::portfolio::{Portfolio, Quotes as PortfolioQuotes, Valuation};
+#[cfg(feature = "cryptocurrency")]
+use crate::rates::RateTable;
+use crate::request::*;
+#[cfg(feature = "cryptocurrency")]
+use crate::resolver::{Resolver, ID_MAP_PAGE};
+use crate::transport::{HttpRequest, HttpTransport as Transport};
+#[cfg(feature = "cryptocurrency")]
+use crate::watch::QuoteWatch;
+#[cfg(feature = "cryptocurrency")]
+use crate::Sort;
+#[cfg(feature = "fiat")]
+use crate::SortFiat;
+use crate::{Environment, KeyPool, Ledger, Pass, RefCache};
+#[cfg(feature = "exchange")]
+use crate::{ListingStatusExchange, SortExchange};
+use reqwest::blocking::Client;
+use reqwest::{Proxy, StatusCode};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+#[cfg(any(feature = "cryptocurrency", feature = "tools"))]
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+#[cfg(feature = "cryptocurrency")]
+use crate::api::cryptocurrency::*;
+#[cfg(feature = "exchange")]
+use crate::api::exchange::*;
+#[cfg(feature = "fiat")]
+use crate::api::fiat::*;
+#[cfg(feature = "global_metrics")]
+use crate::api::global_metrics::*;
+#[cfg(feature = "key")]
+use crate::api::key::*;
+#[cfg(feature = "tools")]
+use crate::api::tools::LotValue;
+
+client!(;);
+
+impl Cmc {
+    /// Sends a request.
+    fn fetch(&self, request: HttpRequest) -> CmcResult<Value> {
+        self.fetch_pooled(request)
+    }
+
+    /// Looks up the price of one asset.
+    #[cfg(feature = "cryptocurrency")]
+    fn lookup(&self, asset: AssetRef, currency: &str) -> CmcResult<Amount> {
+        self.price_of(asset, currency)
+    }
+
+    /// Polls the latest quotes of `ids` every `interval` and yields the ones whose
+    /// `last_updated` changed, see [`crate::watch`]. CoinMarketCap refreshes quotes every 60
+    /// seconds, so shorter intervals spend credits on unchanged data.
+    ///
+    /// # Example:
+    ///
+    /// ```rust,no_run
+    /// use cmc::{Cmc, CryptoId};
+    /// use std::time::Duration;
+    ///
+    /// let cmc = Cmc::new("<API KEY>");
+    ///
+    /// cmc.watch_quotes(CryptoId(1), Duration::from_secs(60)).each(|update| {
+    ///     if let Ok(update) = update {
+    ///         println!("{:?}", update.quote.quote["USD"].price);
+    ///     }
+    ///     true
+    /// });
+    /// ```
+    #[cfg(feature = "cryptocurrency")]
+    pub fn watch_quotes<T: Identifiers<CryptoId>>(&self, ids: T, interval: Duration) -> QuoteWatch {
+        QuoteWatch::new(self.clone(), ids.to_query(), interval)
+    }
+
+    /// Refreshes a stale cache entry on a background thread.
+    fn refresh(&self, cache: RefCache, key: String, request: HttpRequest) {
+        let cmc = self.clone();
+        thread::spawn(move || {
+            if let Ok(body) = cmc.fetch(request) {
+                let _ = cache.store(&key, &body);
+            }
+            cache.end_refresh(&key);
+        });
+    }
+
+    #[doc(hidden)]
+    #[deprecated(since = "0.3.0", note = "Use `fiat_id_map()` instead")]
+    #[cfg(feature = "fiat")]
+    pub fn id_map_fiat(
+        &self,
+        start: usize,
+        limit: usize,
+        sort: SortFiat,
+    ) -> CmcResult<CmcFiatIdMap> {
+        Cmc::fiat_id_map(self, start, limit, sort)
+    }
+}
@@ -1,22 +1,10 @@
+use crate::api::response::Response;
+pub use crate::api::response::Status;
+use crate::api::Timestamp;
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
 use std::fmt::{Display, Formatter};
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct CmcCategories {
-    pub status: Status,
-    pub data: Vec<Category>,
-}
-
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct Status {
-    pub timestamp: String,
-    pub error_code: i64,
-    pub error_message: Value,
-    pub elapsed: i64,
-    pub credit_count: i64,
-    pub notice: Value,
-}
+pub type CmcCategories = Response<Vec<Category>>;
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Category {
@@ -30,7 +18,7 @@ pub struct Category {
     pub market_cap_change: f64,
     pub volume: f64,
     pub volume_change: f64,
-    pub last_updated: String,
+    pub last_updated: Timestamp,
 }
 
 impl Display for CmcCategories {
@@ -1,23 +1,11 @@
+use crate::api::response::Response;
+pub use crate::api::response::Status;
+use crate::api::{de, Amount, Timestamp};
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
 use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct CmcCategory {
-    pub status: Status,
-    pub data: Category,
-}
-
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct Status {
-    pub timestamp: String,
-    pub error_code: i64,
-    pub error_message: Value,
-    pub elapsed: i64,
-    pub credit_count: i64,
-    pub notice: Value,
-}
+pub type CmcCategory = Response<Category>;
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Category {
@@ -26,7 +14,7 @@ pub struct Category {
     pub title: String,
     pub description: String,
     pub num_tokens: i64,
-    pub last_updated: String,
+    pub last_updated: Timestamp,
     pub avg_price_change: f64,
     pub market_cap: f64,
     pub market_cap_change: f64,
@@ -42,7 +30,7 @@ pub struct Coin {
     pub symbol: String,
     pub slug: String,
     pub num_market_pairs: i64,
-    pub date_added: String,
+    pub date_added: Timestamp,
     pub tags: Vec<String>,
     pub max_supply: Option<i64>,
     pub circulating_supply: f64,
@@ -51,10 +39,12 @@ pub struct Coin {
     pub platform: Option<Platform>,
     pub cmc_rank: i64,
     pub is_fiat: i64,
-    pub self_reported_circulating_supply: Value,
-    pub self_reported_market_cap: Value,
+    #[serde(default, deserialize_with = "de::opt_f64")]
+    pub self_reported_circulating_supply: Option<f64>,
+    #[serde(default, deserialize_with = "de::opt_f64")]
+    pub self_reported_market_cap: Option<f64>,
     pub tvl_ratio: Option<f64>,
-    pub last_updated: String,
+    pub last_updated: Timestamp,
     pub quote: HashMap<String, Currency>,
 }
 
@@ -69,20 +59,33 @@ pub struct Platform {
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Currency {
-    pub price: f64,
-    pub volume_24h: Value,
-    pub volume_change_24h: Value,
-    pub percent_change_1h: Value,
-    pub percent_change_24h: Value,
-    pub percent_change_7d: Value,
-    pub percent_change_30d: Value,
-    pub percent_change_60d: Value,
-    pub percent_change_90d: Value,
-    pub market_cap: Value,
-    pub market_cap_dominance: Value,
-    pub fully_diluted_market_cap: Value,
-    pub tvl: Value,
-    pub last_updated: String,
+    #[serde(deserialize_with = "de::amount")]
+    pub price: Amount,
+    #[serde(default, deserialize_with = "de::opt_amount")]
+    pub volume_24h: Option<Amount>,
+    #[serde(default, deserialize_with = "de::opt_f64")]
+    pub volume_change_24h: Option<f64>,
+    #[serde(default, deserialize_with = "de::opt_f64")]
+    pub percent_change_1h: Option<f64>,
+    #[serde(default, deserialize_with = "de::opt_f64")]
+    pub percent_change_24h: Option<f64>,
+    #[serde(default, deserialize_with = "de::opt_f64")]
+    pub percent_change_7d: Option<f64>,
+    #[serde(default, deserialize_with = "de::opt_f64")]
+    pub percent_change_30d: Option<f64>,
+    #[serde(default, deserialize_with = "de::opt_f64")]
+    pub percent_change_60d: Option<f64>,
+    #[serde(default, deserialize_with = "de::opt_f64")]
+    pub percent_change_90d: Option<f64>,
+    #[serde(default, deserialize_with = "de::opt_amount")]
+    pub market_cap: Option<Amount>,
+    #[serde(default, deserialize_with = "de::opt_f64")]
+    pub market_cap_dominance: Option<f64>,
+    #[serde(default, deserialize_with = "de::opt_amount")]
+    pub fully_diluted_market_cap: Option<Amount>,
+    #[serde(default, deserialize_with = "de::opt_amount")]
+    pub tvl: Option<Amount>,
+    pub last_updated: Timestamp,
 }
 
 impl Display for Category {
@@ -1,22 +1,10 @@
+use crate::api::response::Response;
+pub use crate::api::response::Status;
+use crate::api::{de, Timestamp};
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
 use std::fmt::{Display, Formatter};
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct CmcIdMap {
-    pub status: Status,
-    pub data: Vec<Cryptocurrency>,
-}
-
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct Status {
-    pub timestamp: String,
-    pub error_code: i64,
-    pub error_message: Value,
-    pub elapsed: i64,
-    pub credit_count: i64,
-    pub notice: Value,
-}
+pub type CmcIdMap = Response<Vec<Cryptocurrency>>;
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Cryptocurrency {
@@ -26,9 +14,21 @@ pub struct Cryptocurrency {
     pub slug: String,
     pub rank: i64,
     pub is_active: i64,
-    pub first_historical_data: String,
-    pub last_historical_data: String,
-    pub platform: Value,
+    pub first_historical_data: Timestamp,
+    pub last_historical_data: Timestamp,
+    #[serde(default, deserialize_with = "de::opt_object")]
+    pub platform: Option<Platform>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Platform {
+    #[serde(default, deserialize_with = "de::opt_u64")]
+    pub id: Option<u64>,
+    pub name: String,
+    pub symbol: String,
+    pub slug: String,
+    #[serde(default, deserialize_with = "de::opt_string")]
+    pub token_address: Option<String>,
 }
 
 impl Display for CmcIdMap {
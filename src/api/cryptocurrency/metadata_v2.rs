@@ -1,28 +1,11 @@
+use crate::api::response::Response;
+pub use crate::api::response::Status;
+use crate::api::{de, Timestamp};
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
 use std::collections::HashMap;
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct MDv2 {
-    pub status: Status,
-    pub data: HashMap<String, Metadata>,
-}
-
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct MDv2Symbol {
-    pub status: Status,
-    pub data: HashMap<String, Vec<Metadata>>,
-}
-
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct Status {
-    pub timestamp: String,
-    pub error_code: i64,
-    pub error_message: Value,
-    pub elapsed: i64,
-    pub credit_count: i64,
-    pub notice: Value,
-}
+pub type MDv2 = Response<HashMap<String, Metadata>>;
+pub type MDv2Symbol = Response<HashMap<String, Vec<Metadata>>>;
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Metadata {
@@ -42,28 +25,43 @@ pub struct Metadata {
     pub tag_groups: Option<Vec<String>>,
     pub urls: Urls,
     pub platform: Option<Platform>,
-    pub date_added: String,
+    pub date_added: Timestamp,
     pub twitter_username: String,
     pub is_hidden: i64,
-    pub date_launched: Value,
-    pub contract_address: Vec<Value>,
-    pub self_reported_circulating_supply: Value,
-    pub self_reported_tags: Value,
-    pub self_reported_market_cap: Value,
+    #[serde(default, deserialize_with = "de::opt_timestamp")]
+    pub date_launched: Option<Timestamp>,
+    #[serde(default, deserialize_with = "de::object_list")]
+    pub contract_address: Vec<Contract>,
+    #[serde(default, deserialize_with = "de::opt_f64")]
+    pub self_reported_circulating_supply: Option<f64>,
+    #[serde(default, deserialize_with = "de::string_list")]
+    pub self_reported_tags: Vec<String>,
+    #[serde(default, deserialize_with = "de::opt_f64")]
+    pub self_reported_market_cap: Option<f64>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Urls {
-    pub website: Vec<Value>,
-    pub twitter: Vec<Value>,
-    pub message_board: Vec<Value>,
-    pub chat: Vec<Value>,
-    pub facebook: Vec<Value>,
-    pub explorer: Vec<Value>,
-    pub reddit: Vec<Value>,
-    pub technical_doc: Vec<Value>,
-    pub source_code: Vec<Value>,
-    pub announcement: Vec<Value>,
+    #[serde(default, deserialize_with = "de::string_list")]
+    pub website: Vec<String>,
+    #[serde(default, deserialize_with = "de::string_list")]
+    pub twitter: Vec<String>,
+    #[serde(default, deserialize_with = "de::string_list")]
+    pub message_board: Vec<String>,
+    #[serde(default, deserialize_with = "de::string_list")]
+    pub chat: Vec<String>,
+    #[serde(default, deserialize_with = "de::string_list")]
+    pub facebook: Vec<String>,
+    #[serde(default, deserialize_with = "de::string_list")]
+    pub explorer: Vec<String>,
+    #[serde(default, deserialize_with = "de::string_list")]
+    pub reddit: Vec<String>,
+    #[serde(default, deserialize_with = "de::string_list")]
+    pub technical_doc: Vec<String>,
+    #[serde(default, deserialize_with = "de::string_list")]
+    pub source_code: Vec<String>,
+    #[serde(default, deserialize_with = "de::string_list")]
+    pub announcement: Vec<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -74,3 +72,25 @@ pub struct Platform {
     pub symbol: String,
     pub token_address: String,
 }
+
+/// A contract address of the cryptocurrency on one platform.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Contract {
+    pub contract_address: String,
+    pub platform: ContractPlatform,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContractPlatform {
+    pub name: String,
+    pub coin: ContractCoin,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContractCoin {
+    #[serde(default, deserialize_with = "de::opt_u64")]
+    pub id: Option<u64>,
+    pub name: String,
+    pub symbol: String,
+    pub slug: String,
+}
@@ -1,34 +1,19 @@
+use crate::api::response::Response;
+pub use crate::api::response::Status;
+use crate::api::{de, Amount, Timestamp};
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
 use std::collections::HashMap;
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct QLv2Slug {
-    pub status: Status,
-    pub data: HashMap<String, CryptoCurrency>,
-}
-
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct QLv2Id {
-    pub status: Status,
-    pub data: HashMap<String, CryptoCurrency>,
-}
+#[cfg(any(feature = "blocking", feature = "async"))]
+use crate::api::CmcResult;
+#[cfg(any(feature = "blocking", feature = "async"))]
+use crate::errors::CmcErrors;
+#[cfg(any(feature = "blocking", feature = "async"))]
+use crate::ids::CryptoId;
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct QLv2Symbol {
-    pub status: Status,
-    pub data: HashMap<String, Vec<CryptoCurrency>>,
-}
-
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct Status {
-    pub timestamp: String,
-    pub error_code: i64,
-    pub error_message: Value,
-    pub elapsed: i64,
-    pub credit_count: i64,
-    pub notice: Value,
-}
+pub type QLv2Slug = Response<HashMap<String, CryptoCurrency>>;
+pub type QLv2Id = Response<HashMap<String, CryptoCurrency>>;
+pub type QLv2Symbol = Response<HashMap<String, Vec<CryptoCurrency>>>;
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CryptoCurrency {
@@ -36,23 +21,62 @@ pub struct CryptoCurrency {
     pub name: String,
     pub symbol: String,
     pub slug: String,
-    pub num_market_pairs: Value,
-    pub date_added: Value,
+    #[serde(default, deserialize_with = "de::opt_u64")]
+    pub num_market_pairs: Option<u64>,
+    #[serde(default, deserialize_with = "de::opt_timestamp")]
+    pub date_added: Option<Timestamp>,
+    #[serde(default, deserialize_with = "de::object_list")]
     pub tags: Vec<Tag>,
-    pub max_supply: Value,
-    pub circulating_supply: Value,
-    pub total_supply: Value,
+    #[serde(default, deserialize_with = "de::opt_f64")]
+    pub max_supply: Option<f64>,
+    #[serde(default, deserialize_with = "de::opt_f64")]
+    pub circulating_supply: Option<f64>,
+    #[serde(default, deserialize_with = "de::opt_f64")]
+    pub total_supply: Option<f64>,
     pub is_active: i64,
-    pub platform: Value,
-    pub cmc_rank: Value,
+    #[serde(default, deserialize_with = "de::opt_object")]
+    pub platform: Option<Platform>,
+    #[serde(default, deserialize_with = "de::opt_u64")]
+    pub cmc_rank: Option<u64>,
     pub is_fiat: i64,
-    pub self_reported_circulating_supply: Value,
-    pub self_reported_market_cap: Value,
-    pub tvl_ratio: Value,
-    pub last_updated: String,
+    #[serde(default, deserialize_with = "de::opt_f64")]
+    pub self_reported_circulating_supply: Option<f64>,
+    #[serde(default, deserialize_with = "de::opt_f64")]
+    pub self_reported_market_cap: Option<f64>,
+    #[serde(default, deserialize_with = "de::opt_f64")]
+    pub tvl_ratio: Option<f64>,
+    pub last_updated: Timestamp,
     pub quote: HashMap<String, Currency>,
 }
 
+/// The cryptocurrency a symbol lookup found. Symbols are not unique, so several candidates
+/// fail with `CmcErrors::Ambiguous` instead of picking one.
+#[cfg(any(feature = "blocking", feature = "async"))]
+pub(crate) fn single<'a>(
+    symbol: &str,
+    candidates: Option<&'a Vec<CryptoCurrency>>,
+) -> CmcResult<&'a CryptoCurrency> {
+    match candidates.map(Vec::as_slice).unwrap_or_default() {
+        [] => Err(CmcErrors::NotFound(symbol.to_owned())),
+        [cc] => Ok(cc),
+        candidates => Err(CmcErrors::Ambiguous(
+            symbol.to_owned(),
+            candidates.iter().map(|cc| CryptoId(cc.id as u64)).collect(),
+        )),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Platform {
+    #[serde(default, deserialize_with = "de::opt_u64")]
+    pub id: Option<u64>,
+    pub name: String,
+    pub symbol: String,
+    pub slug: String,
+    #[serde(default, deserialize_with = "de::opt_string")]
+    pub token_address: Option<String>,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Tag {
     pub slug: String,
@@ -62,18 +86,31 @@ pub struct Tag {
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Currency {
-    pub price: Option<f64>,
-    pub volume_24h: Value,
-    pub volume_change_24h: Value,
-    pub percent_change_1h: Value,
-    pub percent_change_24h: Value,
-    pub percent_change_7d: Value,
-    pub percent_change_30d: Value,
-    pub percent_change_60d: Value,
-    pub percent_change_90d: Value,
-    pub market_cap: Value,
-    pub market_cap_dominance: Value,
-    pub fully_diluted_market_cap: Value,
-    pub tvl: Value,
-    pub last_updated: String,
+    #[serde(default, deserialize_with = "de::opt_amount")]
+    pub price: Option<Amount>,
+    #[serde(default, deserialize_with = "de::opt_amount")]
+    pub volume_24h: Option<Amount>,
+    #[serde(default, deserialize_with = "de::opt_f64")]
+    pub volume_change_24h: Option<f64>,
+    #[serde(default, deserialize_with = "de::opt_f64")]
+    pub percent_change_1h: Option<f64>,
+    #[serde(default, deserialize_with = "de::opt_f64")]
+    pub percent_change_24h: Option<f64>,
+    #[serde(default, deserialize_with = "de::opt_f64")]
+    pub percent_change_7d: Option<f64>,
+    #[serde(default, deserialize_with = "de::opt_f64")]
+    pub percent_change_30d: Option<f64>,
+    #[serde(default, deserialize_with = "de::opt_f64")]
+    pub percent_change_60d: Option<f64>,
+    #[serde(default, deserialize_with = "de::opt_f64")]
+    pub percent_change_90d: Option<f64>,
+    #[serde(default, deserialize_with = "de::opt_amount")]
+    pub market_cap: Option<Amount>,
+    #[serde(default, deserialize_with = "de::opt_f64")]
+    pub market_cap_dominance: Option<f64>,
+    #[serde(default, deserialize_with = "de::opt_amount")]
+    pub fully_diluted_market_cap: Option<Amount>,
+    #[serde(default, deserialize_with = "de::opt_amount")]
+    pub tvl: Option<Amount>,
+    pub last_updated: Timestamp,
 }
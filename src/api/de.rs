@@ -0,0 +1,239 @@
+//! Tolerant deserializers for fields the API encodes inconsistently.
+//!
+//! Depending on the endpoint and the asset, numbers arrive as JSON numbers, numeric strings or
+//! `null`, and lists may be `null` instead of empty. Use these with
+//! `#[serde(default, deserialize_with = "...")]`, so missing fields are accepted as well.
+
+#[cfg(any(
+    feature = "cryptocurrency",
+    feature = "global_metrics",
+    feature = "tools"
+))]
+use crate::api::Amount;
+#[cfg(any(feature = "cryptocurrency", feature = "exchange", feature = "tools"))]
+use crate::api::Timestamp;
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+
+/// Number, numeric string or `null`. Empty or non-numeric strings read as `None`.
+#[cfg(any(
+    feature = "cryptocurrency",
+    feature = "exchange",
+    all(
+        not(feature = "decimal"),
+        any(feature = "global_metrics", feature = "tools")
+    )
+))]
+pub(crate) fn opt_f64<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<f64>, D::Error> {
+    Ok(match Value::deserialize(deserializer)? {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    })
+}
+
+/// Monetary value as a number, numeric string or `null`.
+///
+/// The `decimal` feature turns on serde_json's `arbitrary_precision`, so a JSON number keeps the
+/// text the API sent and is read into a `Decimal` digit for digit. A number that does not fit a
+/// `Decimal` (beyond its range or its 28 fractional digits) is an error, not a rounded value.
+/// Non-numeric strings read as `None`.
+#[cfg(all(
+    feature = "decimal",
+    any(
+        feature = "cryptocurrency",
+        feature = "global_metrics",
+        feature = "tools"
+    )
+))]
+pub(crate) fn opt_amount<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Amount>, D::Error> {
+    match Value::deserialize(deserializer)? {
+        Value::Number(n) => decimal(&n.to_string()).map(Some),
+        Value::String(s) if s.trim().parse::<f64>().is_ok() => decimal(s.trim()).map(Some),
+        _ => Ok(None),
+    }
+    .map_err(serde::de::Error::custom)
+}
+
+/// Monetary value as a number, numeric string or `null`.
+#[cfg(all(
+    not(feature = "decimal"),
+    any(
+        feature = "cryptocurrency",
+        feature = "global_metrics",
+        feature = "tools"
+    )
+))]
+pub(crate) fn opt_amount<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Amount>, D::Error> {
+    opt_f64(deserializer)
+}
+
+/// Required monetary value, see [`opt_amount`].
+#[cfg(all(
+    feature = "decimal",
+    any(
+        feature = "cryptocurrency",
+        feature = "global_metrics",
+        feature = "tools"
+    )
+))]
+pub(crate) fn amount<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Amount, D::Error> {
+    opt_amount(deserializer)?.ok_or_else(|| serde::de::Error::custom("invalid decimal amount"))
+}
+
+/// Required monetary value.
+#[cfg(all(
+    not(feature = "decimal"),
+    any(
+        feature = "cryptocurrency",
+        feature = "global_metrics",
+        feature = "tools"
+    )
+))]
+pub(crate) fn amount<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Amount, D::Error> {
+    f64::deserialize(deserializer)
+}
+
+/// Non-negative integer, also accepted as a whole float (`21000000.0`) or a numeric string.
+#[cfg(any(feature = "cryptocurrency", feature = "exchange"))]
+pub(crate) fn opt_u64<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u64>, D::Error> {
+    Ok(match Value::deserialize(deserializer)? {
+        Value::Number(n) => n.as_u64().or_else(|| n.as_f64().and_then(whole)),
+        Value::String(s) => s
+            .trim()
+            .parse()
+            .ok()
+            .or_else(|| s.trim().parse().ok().and_then(whole)),
+        _ => None,
+    })
+}
+
+/// String, `null`, or a number or boolean kept in its JSON form. Empty strings read as `None`.
+pub(crate) fn opt_string<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<String>, D::Error> {
+    Ok(match Value::deserialize(deserializer)? {
+        Value::String(s) if s.is_empty() => None,
+        Value::String(s) => Some(s),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    })
+}
+
+/// ISO 8601 string, Unix timestamp in seconds or `null`.
+#[cfg(all(
+    feature = "chrono",
+    any(feature = "cryptocurrency", feature = "exchange", feature = "tools")
+))]
+pub(crate) fn opt_timestamp<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Timestamp>, D::Error> {
+    use chrono::{DateTime, Utc};
+
+    Ok(match Value::deserialize(deserializer)? {
+        Value::String(s) => DateTime::parse_from_rfc3339(s.trim())
+            .ok()
+            .map(|t| t.with_timezone(&Utc)),
+        Value::Number(n) => n.as_i64().and_then(|t| DateTime::from_timestamp(t, 0)),
+        _ => None,
+    })
+}
+
+/// ISO 8601 string or `null`. Empty strings read as `None`.
+#[cfg(all(
+    not(feature = "chrono"),
+    any(feature = "cryptocurrency", feature = "exchange", feature = "tools")
+))]
+pub(crate) fn opt_timestamp<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Timestamp>, D::Error> {
+    opt_string(deserializer)
+}
+
+/// Boolean, `0`/`1` or `"true"`/`"false"`.
+#[cfg(feature = "exchange")]
+pub(crate) fn opt_bool<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<bool>, D::Error> {
+    Ok(match Value::deserialize(deserializer)? {
+        Value::Bool(b) => Some(b),
+        Value::Number(n) => n.as_f64().map(|n| n != 0.0),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    })
+}
+
+/// List of strings, where `null` reads as an empty list and `null` items are skipped.
+#[cfg(any(feature = "cryptocurrency", feature = "exchange"))]
+pub(crate) fn string_list<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<String>, D::Error> {
+    Ok(match Value::deserialize(deserializer)? {
+        Value::Array(items) => items
+            .into_iter()
+            .filter_map(|item| match item {
+                Value::String(s) => Some(s),
+                Value::Number(n) => Some(n.to_string()),
+                _ => None,
+            })
+            .collect(),
+        Value::String(s) if !s.is_empty() => vec![s],
+        _ => Vec::new(),
+    })
+}
+
+/// A typed object, where `null`, an empty string or an unexpected shape reads as `None`.
+#[cfg(feature = "cryptocurrency")]
+pub(crate) fn opt_object<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: serde::de::DeserializeOwned,
+{
+    Ok(match Value::deserialize(deserializer)? {
+        value @ Value::Object(_) => serde_json::from_value(value).ok(),
+        _ => None,
+    })
+}
+
+/// A list of typed objects, where `null` reads as an empty list and malformed items are skipped.
+#[cfg(feature = "cryptocurrency")]
+pub(crate) fn object_list<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: serde::de::DeserializeOwned,
+{
+    Ok(match Value::deserialize(deserializer)? {
+        Value::Array(items) => items
+            .into_iter()
+            .filter_map(|item| serde_json::from_value(item).ok())
+            .collect(),
+        _ => Vec::new(),
+    })
+}
+
+#[cfg(all(
+    feature = "decimal",
+    any(
+        feature = "cryptocurrency",
+        feature = "global_metrics",
+        feature = "tools"
+    )
+))]
+fn decimal(s: &str) -> Result<Amount, String> {
+    let amount = if s.contains(['e', 'E']) {
+        Amount::from_scientific(s)
+    } else {
+        Amount::from_str_exact(s)
+    };
+    amount.map_err(|err| format!("amount `{}` does not fit a decimal: {}", s, err))
+}
+
+#[cfg(any(feature = "cryptocurrency", feature = "exchange"))]
+fn whole(n: f64) -> Option<u64> {
+    (n >= 0.0 && n.fract() == 0.0 && n <= u64::MAX as f64).then_some(n as u64)
+}
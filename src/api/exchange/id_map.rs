@@ -1,22 +1,10 @@
+use crate::api::response::Response;
+pub use crate::api::response::Status;
+use crate::api::Timestamp;
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
 use std::fmt::{Display, Formatter};
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct CmcExchangeIdMap {
-    pub status: Status,
-    pub data: Vec<Exchange>,
-}
-
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct Status {
-    pub timestamp: String,
-    pub error_code: i64,
-    pub error_message: Value,
-    pub elapsed: i64,
-    pub credit_count: i64,
-    pub notice: Value,
-}
+pub type CmcExchangeIdMap = Response<Vec<Exchange>>;
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Exchange {
@@ -24,8 +12,8 @@ pub struct Exchange {
     pub name: String,
     pub slug: String,
     pub is_active: i64,
-    pub first_historical_data: String,
-    pub last_historical_data: String,
+    pub first_historical_data: Timestamp,
+    pub last_historical_data: Timestamp,
 }
 
 impl Display for CmcExchangeIdMap {
@@ -1,52 +1,53 @@
+use crate::api::response::Response;
+pub use crate::api::response::Status;
+use crate::api::{de, Timestamp};
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
 use std::collections::HashMap;
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct ExchangeMetadata {
-    pub status: Status,
-    pub data: HashMap<String, Exchange>,
-}
-
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct Status {
-    pub timestamp: String,
-    pub error_code: i64,
-    pub error_message: Value,
-    pub elapsed: i64,
-    pub credit_count: i64,
-    pub notice: Value,
-}
+pub type ExchangeMetadata = Response<HashMap<String, Exchange>>;
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Exchange {
     pub id: i64,
     pub name: String,
     pub slug: String,
-    pub description: Value,
+    #[serde(default, deserialize_with = "de::opt_string")]
+    pub description: Option<String>,
     pub notice: String,
     pub logo: String,
-    pub countries: Vec<Value>,
+    #[serde(default, deserialize_with = "de::string_list")]
+    pub countries: Vec<String>,
     pub fiats: Vec<String>,
     pub urls: Urls,
-    pub tags: Value,
+    #[serde(default, deserialize_with = "de::string_list")]
+    pub tags: Vec<String>,
     #[serde(rename = "type")]
     pub type_field: String,
-    pub date_launched: Value,
+    #[serde(default, deserialize_with = "de::opt_timestamp")]
+    pub date_launched: Option<Timestamp>,
     pub is_hidden: i64,
-    pub is_redistributable: Value,
+    #[serde(default, deserialize_with = "de::opt_bool")]
+    pub is_redistributable: Option<bool>,
     pub maker_fee: f64,
     pub taker_fee: f64,
-    pub spot_volume_usd: Value,
-    pub spot_volume_last_updated: Value,
-    pub weekly_visits: Value,
+    #[serde(default, deserialize_with = "de::opt_f64")]
+    pub spot_volume_usd: Option<f64>,
+    #[serde(default, deserialize_with = "de::opt_timestamp")]
+    pub spot_volume_last_updated: Option<Timestamp>,
+    #[serde(default, deserialize_with = "de::opt_u64")]
+    pub weekly_visits: Option<u64>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Urls {
-    pub chat: Vec<Value>,
-    pub twitter: Vec<Value>,
-    pub blog: Vec<Value>,
-    pub fee: Vec<Value>,
-    pub website: Vec<Value>,
+    #[serde(default, deserialize_with = "de::string_list")]
+    pub chat: Vec<String>,
+    #[serde(default, deserialize_with = "de::string_list")]
+    pub twitter: Vec<String>,
+    #[serde(default, deserialize_with = "de::string_list")]
+    pub blog: Vec<String>,
+    #[serde(default, deserialize_with = "de::string_list")]
+    pub fee: Vec<String>,
+    #[serde(default, deserialize_with = "de::string_list")]
+    pub website: Vec<String>,
 }
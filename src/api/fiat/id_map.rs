@@ -1,22 +1,9 @@
+use crate::api::response::Response;
+pub use crate::api::response::Status;
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
 use std::fmt::{Display, Formatter};
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct CmcFiatIdMap {
-    pub status: Status,
-    pub data: Vec<Currency>,
-}
-
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct Status {
-    pub timestamp: String,
-    pub error_code: i64,
-    pub error_message: Value,
-    pub elapsed: i64,
-    pub credit_count: i64,
-    pub notice: Value,
-}
+pub type CmcFiatIdMap = Response<Vec<Currency>>;
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Currency {
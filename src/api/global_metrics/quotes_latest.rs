@@ -1,22 +1,10 @@
+use crate::api::response::Response;
+pub use crate::api::response::Status;
+use crate::api::{de, Amount, Timestamp};
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
 use std::collections::HashMap;
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct CmcGlobalMetrics {
-    pub status: Status,
-    pub data: GlobalMetrics,
-}
-
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct Status {
-    pub timestamp: String,
-    pub error_code: i64,
-    pub error_message: Value,
-    pub elapsed: i64,
-    pub credit_count: i64,
-    pub notice: Value,
-}
+pub type CmcGlobalMetrics = Response<GlobalMetrics>;
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct GlobalMetrics {
@@ -43,31 +31,47 @@ pub struct GlobalMetrics {
     pub derivatives_volume_24h_reported: f64,
     pub derivatives_24h_percentage_change: f64,
     pub quote: HashMap<String, Currency>,
-    pub last_updated: String,
+    pub last_updated: Timestamp,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Currency {
-    pub total_market_cap: f64,
-    pub total_volume_24h: f64,
-    pub total_volume_24h_reported: f64,
-    pub altcoin_volume_24h: f64,
-    pub altcoin_volume_24h_reported: f64,
-    pub altcoin_market_cap: f64,
-    pub defi_volume_24h: f64,
-    pub defi_volume_24h_reported: f64,
+    #[serde(deserialize_with = "de::amount")]
+    pub total_market_cap: Amount,
+    #[serde(deserialize_with = "de::amount")]
+    pub total_volume_24h: Amount,
+    #[serde(deserialize_with = "de::amount")]
+    pub total_volume_24h_reported: Amount,
+    #[serde(deserialize_with = "de::amount")]
+    pub altcoin_volume_24h: Amount,
+    #[serde(deserialize_with = "de::amount")]
+    pub altcoin_volume_24h_reported: Amount,
+    #[serde(deserialize_with = "de::amount")]
+    pub altcoin_market_cap: Amount,
+    #[serde(deserialize_with = "de::amount")]
+    pub defi_volume_24h: Amount,
+    #[serde(deserialize_with = "de::amount")]
+    pub defi_volume_24h_reported: Amount,
     pub defi_24h_percentage_change: f64,
-    pub defi_market_cap: f64,
-    pub stablecoin_volume_24h: f64,
-    pub stablecoin_volume_24h_reported: f64,
+    #[serde(deserialize_with = "de::amount")]
+    pub defi_market_cap: Amount,
+    #[serde(deserialize_with = "de::amount")]
+    pub stablecoin_volume_24h: Amount,
+    #[serde(deserialize_with = "de::amount")]
+    pub stablecoin_volume_24h_reported: Amount,
     pub stablecoin_24h_percentage_change: f64,
-    pub stablecoin_market_cap: f64,
-    pub derivatives_volume_24h: f64,
-    pub derivatives_volume_24h_reported: f64,
+    #[serde(deserialize_with = "de::amount")]
+    pub stablecoin_market_cap: Amount,
+    #[serde(deserialize_with = "de::amount")]
+    pub derivatives_volume_24h: Amount,
+    #[serde(deserialize_with = "de::amount")]
+    pub derivatives_volume_24h_reported: Amount,
     pub derivatives_24h_percentage_change: f64,
-    pub last_updated: String,
-    pub total_market_cap_yesterday: Option<f64>,
-    pub total_volume_24h_yesterday: Option<f64>,
+    pub last_updated: Timestamp,
+    #[serde(default, deserialize_with = "de::opt_amount")]
+    pub total_market_cap_yesterday: Option<Amount>,
+    #[serde(default, deserialize_with = "de::opt_amount")]
+    pub total_volume_24h_yesterday: Option<Amount>,
     pub total_market_cap_yesterday_percentage_change: Option<f64>,
     pub total_volume_24h_yesterday_percentage_change: Option<f64>,
 }
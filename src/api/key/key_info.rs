@@ -1,22 +1,10 @@
+use crate::api::response::Response;
+pub use crate::api::response::Status;
+use crate::api::Timestamp;
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
 use std::fmt::{Display, Formatter};
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct CmcKeyInfo {
-    pub status: Status,
-    pub data: KeyInfo,
-}
-
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct Status {
-    pub timestamp: String,
-    pub error_code: i64,
-    pub error_message: Value,
-    pub elapsed: i64,
-    pub credit_count: i64,
-    pub notice: Value,
-}
+pub type CmcKeyInfo = Response<KeyInfo>;
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct KeyInfo {
@@ -28,7 +16,7 @@ pub struct KeyInfo {
 pub struct Plan {
     pub credit_limit_monthly: i64,
     pub credit_limit_monthly_reset: String,
-    pub credit_limit_monthly_reset_timestamp: String,
+    pub credit_limit_monthly_reset_timestamp: Timestamp,
     pub rate_limit_minute: i64,
 }
 
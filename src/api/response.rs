@@ -0,0 +1,37 @@
+//! Envelope shared by all endpoint responses.
+
+use crate::api::{de, Timestamp};
+use serde::{Deserialize, Serialize};
+
+/// `status` object of a response: credits spent, request time and API notices.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Status {
+    pub timestamp: Timestamp,
+    pub error_code: i64,
+    #[serde(default, deserialize_with = "de::opt_string")]
+    pub error_message: Option<String>,
+    /// Milliseconds the API spent on the request.
+    pub elapsed: i64,
+    /// API credits the call was charged.
+    pub credit_count: i64,
+    /// Deprecation warnings and other notices from CoinMarketCap.
+    #[serde(default, deserialize_with = "de::opt_string")]
+    pub notice: Option<String>,
+}
+
+/// Response of any endpoint: `status` plus the endpoint specific `data`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Response<T> {
+    pub status: Status,
+    pub data: T,
+}
+
+impl<T> Response<T> {
+    /// Replaces the data, keeping the status.
+    pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> Response<U> {
+        Response {
+            status: self.status,
+            data: f(self.data),
+        }
+    }
+}
@@ -1,3 +1,3 @@
 pub mod price_conversion_v2;
 
-pub use crate::api::tools::price_conversion_v2::{PCv2Id, PCv2Symbol};
+pub use crate::api::tools::price_conversion_v2::{ConversionResult, LotValue, PCv2Id, PCv2Symbol};
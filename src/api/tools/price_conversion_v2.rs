@@ -1,39 +1,44 @@
+use crate::api::response::Response;
+pub use crate::api::response::Status;
+use crate::api::{de, Amount, Timestamp};
+use crate::ids::AssetRef;
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
 use std::collections::HashMap;
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct PCv2Symbol {
-    pub status: Status,
-    pub data: Vec<ConversionResult>,
-}
-
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct PCv2Id {
-    pub status: Status,
-    pub data: ConversionResult,
-}
-
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct Status {
-    pub timestamp: String,
-    pub error_code: i64,
-    pub error_message: Value,
-    pub elapsed: i64,
-    pub credit_count: i64,
-    pub notice: Value,
-}
+pub type PCv2Symbol = Response<Vec<ConversionResult>>;
+pub type PCv2Id = Response<ConversionResult>;
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ConversionResult {
     pub id: i64,
     pub symbol: String,
     pub name: String,
-    pub amount: f64,
+    #[serde(deserialize_with = "de::amount")]
+    pub amount: Amount,
+    #[serde(default, deserialize_with = "de::opt_timestamp")]
+    pub last_updated: Option<Timestamp>,
     pub quote: HashMap<String, Price>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Price {
-    pub price: Option<f64>,
+    #[serde(default, deserialize_with = "de::opt_amount")]
+    pub price: Option<Amount>,
+    #[serde(default, deserialize_with = "de::opt_timestamp")]
+    pub last_updated: Option<Timestamp>,
+}
+
+/// Value of one tax lot, see `value_lots()`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LotValue {
+    pub amount: Amount,
+    pub asset: AssetRef,
+    /// Point in time of the lot, as sent to the API.
+    pub time: String,
+    /// Currency of `price` and `value`.
+    pub currency: String,
+    /// Price of one unit at `time`.
+    pub price: Amount,
+    /// `price * amount`.
+    pub value: Amount,
 }
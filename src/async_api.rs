@@ -1,1058 +1,173 @@
-use crate::api::cryptocurrency::{
-    Category, CmcCategories, CmcCategory, CmcIdMap, MDv2, MDv2Symbol, Metadata, QLv2Id, QLv2Slug,
-    QLv2Symbol,
-};
-use crate::api::exchange::{CmcExchangeIdMap, ExchangeMetadata};
-use crate::api::fiat::CmcFiatIdMap;
-use crate::api::global_metrics::{CmcGlobalMetrics, GlobalMetrics};
-use crate::api::key::{CmcKeyInfo, KeyInfo};
-use crate::api::tools::{PCv2Id, PCv2Symbol};
+//! Async client. It is generated from the same core as [`crate::api`], so it offers the same
+//! builder and methods; calls return futures and have to be `.await`ed. The examples of the
+//! blocking client apply unchanged, apart from the import path and the `.await`.
+
+use crate::api::response::Response;
+#[cfg(any(feature = "cryptocurrency", feature = "tools"))]
+use crate::api::Amount;
 use crate::api::{CmcResult, Config};
-use crate::errors::{ApiError, CmcErrors};
-pub use crate::{ListingStatusExchange, Pass, Sort, SortExchange, SortFiat};
-use reqwest::StatusCode;
-use reqwest::{Client, RequestBuilder};
+use crate::cache::Lookup;
+use crate::client::client;
+use crate::errors::{CmcErrors, ConfigError};
+#[cfg(any(feature = "cryptocurrency", feature = "exchange"))]
+use crate::ids::CryptoId;
+#[cfg(feature = "exchange")]
+use crate::ids::ExchangeQuery;
+#[cfg(feature = "cryptocurrency")]
+use crate::ids::{AssetQuery, CategoryId, Slug};
+#[cfg(any(feature = "cryptocurrency", feature = "tools"))]
+use crate::ids::{AssetRef, Symbol};
+use crate::ids::{CurrencyId, Identifiers};
+#[cfg(feature = "cryptocurrency")]
+use crate::portfolio::{Portfolio, Quotes as PortfolioQuotes, Valuation};
+#[cfg(feature = "cryptocurrency")]
+use crate::rates::RateTable;
+use crate::request::*;
+#[cfg(feature = "cryptocurrency")]
+use crate::resolver::{Resolver, ID_MAP_PAGE};
+use crate::transport::{AsyncHttpTransport as Transport, HttpRequest};
+pub use crate::{
+    Environment, KeyPool, Ledger, ListingStatusExchange, Pass, RefCache, Sort, SortExchange,
+    SortFiat,
+};
+use reqwest::Client;
+use reqwest::{Proxy, StatusCode};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+#[cfg(any(feature = "cryptocurrency", feature = "tools"))]
 use std::collections::HashMap;
-
-/// A `CmcBuilder` can be used to create a `Cmc` with custom configuration.
-pub struct CmcBuilder {
-    api_key: String,
-    client: Client,
-    config: Config,
-}
+use std::sync::Arc;
+use std::time::Duration;
+
+#[cfg(feature = "cryptocurrency")]
+use crate::api::cryptocurrency::*;
+#[cfg(feature = "exchange")]
+use crate::api::exchange::*;
+#[cfg(feature = "fiat")]
+use crate::api::fiat::*;
+#[cfg(feature = "global_metrics")]
+use crate::api::global_metrics::*;
+#[cfg(feature = "key")]
+use crate::api::key::*;
+#[cfg(feature = "tools")]
+use crate::api::tools::LotValue;
+
+client!(async; .await);
 
 impl CmcBuilder {
-    pub fn new<T: Into<String>>(api_key: T) -> Self {
-        let client = Client::builder().pool_idle_timeout(None).build().unwrap();
-
-        Self {
-            api_key: api_key.into(),
-            client,
-            config: Config::default(),
-        }
-    }
-
-    /// # Set pass:
-    ///
-    /// - **Id**: Cryptocurrency coinmarketcap id. Example: "1027"
-    ///
-    /// - **Slug**: Alternatively pass one cryptocurrency slug. Example: "ethereum"
-    ///
-    /// - **Symbol**: Alternatively pass one cryptocurrency symbol. Example: "BTC"
-    ///
-    /// **NOTE**: `CoinMarketCap recommend utilizing CMC ID instead of cryptocurrency symbols to securely identify cryptocurrencies with other endpoints and in your own application logic`
-    /// (Can be obtained using the method [id_map()][id]).
-    /// # Example:
-    /// ```rust
-    /// use cmc::async_api::{CmcBuilder, Pass};
-    ///
-    /// let cmc = CmcBuilder::new("<API KEY>").pass(Pass::Id).build();
-    ///
-    /// match cmc.price("1027").await { // 1027 is Ethereum id.
-    ///     Ok(price) => println!("Price: {}", price),
-    ///     Err(err) => println!("Error: {}", err),
-    /// }
-    /// ```
-    /// [id]: ./struct.Cmc.html#method.id_map
-    pub fn pass(mut self, pass: Pass) -> CmcBuilder {
-        self.config.pass = pass;
-        self
-    }
-
-    /// Optionally calculate market quotes in up to 120 currencies by passing cryptocurrency or fiat.
+    /// Optionally let concurrent identical requests (same endpoint and query) share one HTTP
+    /// request and its response. Works below the [`RefCache`], so cache misses and refreshes
+    /// are coalesced as well.
     /// # Example:
     /// ```rust
     /// use cmc::async_api::CmcBuilder;
     ///
-    /// let cmc = CmcBuilder::new("<API KEY>").convert("EUR").build();
+    /// let cmc = CmcBuilder::new("<API KEY>").coalesce(true).build().unwrap();
     ///
-    /// match cmc.price("ETH").await {
-    ///     Ok(price) => println!("Price: {}", price), // In Euro
-    ///     Err(err) => println!("Error: {}", err),
-    /// }
+    /// // Clones of `cmc` asking for the BTC price at the same time now share one request.
+    /// let price = cmc.price("BTC");
     /// ```
-    pub fn convert<T: Into<String>>(mut self, currency: T) -> CmcBuilder {
-        self.config.currency = currency.into().to_uppercase();
+    pub fn coalesce(mut self, enabled: bool) -> CmcBuilder {
+        self.config.flights = enabled.then(Default::default);
         self
     }
 
-    /// Optionally calculate market quotes in up to 120 currencies by passing cryptocurrency or fiat.
+    /// Optionally merge `price()` calls into batches: lookups arriving within `window` of each
+    /// other are sent as one `v2/cryptocurrency/quotes/latest` request (e.g. `?id=1,1027,825`),
+    /// holding at most `max_size` distinct ids, slugs or symbols. Each caller still gets the
+    /// price of its own asset. Ids, slugs and symbols are batched separately, the way
+    /// `quotes_latest_by_id()`, `_by_slug()` and `_by_symbol()` send them.
+    ///
+    /// An invalid id, slug or symbol makes CoinMarketCap reject the whole request, so every
+    /// caller of that batch gets the error. Batches are sent from tasks spawned on the current
+    /// Tokio runtime, so `price()` fails with `CmcErrors::NoRuntime` outside one.
     /// # Example:
     /// ```rust
     /// use cmc::async_api::CmcBuilder;
+    /// use cmc::CryptoId;
+    /// use std::time::Duration;
     ///
-    /// let cmc = CmcBuilder::new("<API KEY>").convert_id("1027").build();
+    /// let cmc = CmcBuilder::new("<API KEY>")
+    ///     .batch(Duration::from_millis(50), 100)
+    ///     .build()
+    ///     .unwrap();
     ///
-    /// match cmc.price("BTC").await {
-    ///     Ok(price) => println!("Price: {}", price), // In ETH
-    ///     Err(err) => println!("Error: {}", err),
-    /// }
+    /// // Tasks asking for prices within 50ms of each other now share one request.
+    /// let price = cmc.price(CryptoId(1027));
     /// ```
-    pub fn convert_id<T: Into<String>>(mut self, currency_id: T) -> CmcBuilder {
-        self.config.currency_id = Some(currency_id.into());
-        self
-    }
-
-    /// Optionally set the coinmarketcap base url.
-    pub fn base_url<T: Into<String>>(mut self, base_url: T) -> CmcBuilder {
-        self.config.base_url = base_url.into();
+    #[cfg(feature = "cryptocurrency")]
+    pub fn batch(mut self, window: Duration, max_size: usize) -> CmcBuilder {
+        self.config.batcher = Some(Arc::new(crate::batch::Batcher::new(window, max_size)));
         self
     }
-
-    /// Returns a Cmc async client that uses this CmcBuilder configuration.
-    pub fn build(self) -> Cmc {
-        Cmc {
-            api_key: self.api_key,
-            client: self.client,
-            config: self.config,
-        }
-    }
-}
-
-/// A `Cmc` can be used to create a CoinMarketCap client with default configuration.
-#[derive(Clone, Debug)]
-pub struct Cmc {
-    api_key: String,
-    client: Client,
-    config: Config,
 }
 
 impl Cmc {
-    /// Constructs a new CoinMarketCap async Client.
-    pub fn new<T: Into<String>>(api_key: T) -> Self {
-        CmcBuilder::new(api_key).build()
-    }
-
-    fn add_endpoint(&self, endpoint: &str) -> RequestBuilder {
-        self.client
-            .get(format!("{}{}", self.config.base_url, endpoint))
-            .header("X-CMC_PRO_API_KEY", &self.api_key)
-            .header("Accepts", "application/json")
-    }
-
-    /// Returns a mapping of all cryptocurrencies to unique CoinMarketCap ids.
-    ///
-    /// # Example:
-    ///
-    /// Parameters:
-    /// - `start` Offset the start.
-    /// - `limit` Specify the number of results to return.
-    /// - `sort` What field to sort the list of cryptocurrencies by.
-    ///
-    /// ```rust
-    /// use cmc::async_api::{Cmc, Sort};
-    ///
-    /// let cmc = Cmc::new("<API KEY>");
-    ///
-    /// match cmc.id_map(1, 50, Sort::CmcRank).await {
-    ///     Ok(map) => println!("{}", map),
-    ///     Err(err) => println!("{}", err),
-    /// }
-    /// ```
-    #[cfg(feature = "cryptocurrency")]
-    pub async fn id_map(&self, start: usize, limit: usize, sort: Sort) -> CmcResult<CmcIdMap> {
-        let rb = self
-            .add_endpoint("v1/cryptocurrency/map")
-            .query(&[("start", start), ("limit", limit)]);
-
-        let resp = match sort {
-            Sort::Id => rb.query(&[("sort", "id")]).send().await?,
-            Sort::CmcRank => rb.query(&[("sort", "cmc_rank")]).send().await?,
-        };
-
-        match resp.status() {
-            StatusCode::OK => {
-                let root = resp.json::<CmcIdMap>().await?;
-                Ok(root)
-            }
-            code => {
-                let root = resp.json::<ApiError>().await?;
-                Err(CmcErrors::ApiError(format!(
-                    "Status Code: {}. Error message: {}",
-                    code, root.status.error_message
-                )))
-            }
-        }
-    }
-
-    /// Returns a mapping of all supported fiat currencies to unique CoinMarketCap ids.
-    ///
-    /// # Example:
-    ///
-    /// Parameters:
-    /// - `start` Offset the start.
-    /// - `limit` Specify the number of results to return.
-    /// - `sort` What field to sort the list of currencies by.
-    ///
-    /// Basic usage:
-    ///
-    /// ```rust
-    /// use cmc::async_api::{Cmc, SortFiat};
-    ///
-    /// let cmc = Cmc::new("<API KEY>");
-    ///
-    /// match cmc.fiat_id_map(1, 100, SortFiat::Name).await {
-    ///     Ok(map) => println!("{}", map),
-    ///     Err(err) => println!("{}", err),
-    /// }
-    /// ```
-    #[cfg(feature = "fiat")]
-    pub async fn fiat_id_map(
-        &self,
-        start: usize,
-        limit: usize,
-        sort: SortFiat,
-    ) -> CmcResult<CmcFiatIdMap> {
-        let rb = self
-            .add_endpoint("v1/fiat/map")
-            .query(&[("start", start), ("limit", limit)]);
-
-        let resp = match sort {
-            SortFiat::Id => rb.query(&[("sort", "id")]).send().await?,
-            SortFiat::Name => rb.query(&[("sort", "name")]).send().await?,
-        };
-
-        match resp.status() {
-            StatusCode::OK => {
-                let root = resp.json::<CmcFiatIdMap>().await?;
-                Ok(root)
-            }
-            code => {
-                let root = resp.json::<ApiError>().await?;
-                Err(CmcErrors::ApiError(format!(
-                    "Status Code: {}. Error message: {}",
-                    code, root.status.error_message
-                )))
-            }
-        }
-    }
-
-    /// Latest price for cryptocurrency in USD.
-    ///
-    /// # Example:
-    ///
-    /// ```rust
-    /// use cmc::async_api::Cmc;
-    ///
-    /// let cmc = Cmc::new("<API KEY>");
-    ///
-    /// match cmc.price("BTC").await {
-    ///     Ok(price) => println!("Price: {}", price),
-    ///     Err(err) => println!("Error: {}", err),
-    /// }
-    /// ```
-    #[cfg(feature = "cryptocurrency")]
-    pub async fn price<T: Into<String>>(&self, query: T) -> CmcResult<f64> {
-        let query = query.into();
-        if query.contains(',') {
-            return Err(CmcErrors::IncorrectQuery);
-        }
-
-        let currency = if let Some(currency_id) = &self.config.currency_id {
-            currency_id
-        } else {
-            &self.config.currency
-        };
-
-        match self.config.pass {
-            Pass::Symbol => Ok(self.price_by_symbol(&query, currency).await?),
-            Pass::Id => Ok(self.price_by_id(&query, currency).await?),
-            Pass::Slug => Ok(self.price_by_slug(&query, currency).await?),
-            Pass::Address => Err(CmcErrors::PassIncompatible),
-        }
-    }
-
-    #[cfg(feature = "cryptocurrency")]
-    async fn price_by_id(&self, id: &str, currency: &str) -> CmcResult<f64> {
-        let rb = self
-            .add_endpoint("v2/cryptocurrency/quotes/latest")
-            .query(&[("id", id)]);
-
-        let resp = if self.config.currency_id.is_some() {
-            rb.query(&[("convert_id", currency)]).send().await?
-        } else {
-            rb.query(&[("convert", currency)]).send().await?
-        };
-
-        match resp.status() {
-            StatusCode::OK => {
-                let root = resp.json::<QLv2Id>().await?;
-                if let Some(price) = root
-                    .data
-                    .get(id)
-                    .unwrap()
-                    .quote
-                    .get(currency)
-                    .unwrap()
-                    .price
-                {
-                    Ok(price)
-                } else {
-                    Err(CmcErrors::NullAnswer)
-                }
-            }
-            code => {
-                let root = resp.json::<ApiError>().await?;
-                Err(CmcErrors::ApiError(format!(
-                    "Status Code: {}. Error message: {}",
-                    code, root.status.error_message
-                )))
-            }
-        }
-    }
-
-    #[cfg(feature = "cryptocurrency")]
-    async fn price_by_slug(&self, slug: &str, currency: &str) -> CmcResult<f64> {
-        let rb = self
-            .add_endpoint("v2/cryptocurrency/quotes/latest")
-            .query(&[("slug", slug.to_lowercase())]);
-        let resp = if self.config.currency_id.is_some() {
-            rb.query(&[("convert_id", currency)]).send().await?
-        } else {
-            rb.query(&[("convert", currency)]).send().await?
-        };
-
-        match resp.status() {
-            StatusCode::OK => {
-                let root = resp.json::<QLv2Slug>().await?;
-                let slug_id = root.data.iter().next().unwrap().0;
-                if let Some(price) = root
-                    .data
-                    .get(slug_id)
-                    .unwrap()
-                    .quote
-                    .get(currency)
-                    .unwrap()
-                    .price
-                {
-                    Ok(price)
-                } else {
-                    Err(CmcErrors::NullAnswer)
-                }
-            }
-            code => {
-                let root = resp.json::<ApiError>().await?;
-                Err(CmcErrors::ApiError(format!(
-                    "Status Code: {}. Error message: {}",
-                    code, root.status.error_message
-                )))
-            }
-        }
-    }
-
-    #[cfg(feature = "cryptocurrency")]
-    async fn price_by_symbol(&self, symbol: &str, currency: &str) -> CmcResult<f64> {
-        let rb = self
-            .add_endpoint("v2/cryptocurrency/quotes/latest")
-            .query(&[("symbol", symbol)]);
-
-        let resp = if self.config.currency_id.is_some() {
-            rb.query(&[("convert_id", currency)]).send().await?
-        } else {
-            rb.query(&[("convert", currency)]).send().await?
-        };
-        match resp.status() {
-            StatusCode::OK => {
-                let root = resp.json::<QLv2Symbol>().await?;
-                if let Some(price) = root.data.get(&symbol.to_uppercase()).unwrap()[0]
-                    .quote
-                    .get(currency)
-                    .unwrap()
-                    .price
-                {
-                    Ok(price)
-                } else {
-                    Err(CmcErrors::NullAnswer)
-                }
-            }
-            code => {
-                let root = resp.json::<ApiError>().await?;
-                Err(CmcErrors::ApiError(format!(
-                    "Status Code: {}. Error message: {}",
-                    code, root.status.error_message
-                )))
-            }
-        }
-    }
-
-    /// Returns the latest market quote for 1 or more cryptocurrencies (using id's).
-    #[cfg(feature = "cryptocurrency")]
-    pub async fn quotes_latest_by_id<T: Into<String>>(&self, ids: T) -> CmcResult<QLv2Id> {
-        let ids = ids.into();
-
-        let rb = self
-            .add_endpoint("v2/cryptocurrency/quotes/latest")
-            .query(&[("id", ids)]);
-
-        let resp = if let Some(currency_id) = &self.config.currency_id {
-            rb.query(&[("convert_id", currency_id)]).send().await?
-        } else {
-            rb.query(&[("convert", &self.config.currency)])
-                .send()
-                .await?
-        };
-
-        match resp.status() {
-            StatusCode::OK => {
-                let root = resp.json::<QLv2Id>().await?;
-                Ok(root)
-            }
-            code => {
-                let root = resp.json::<ApiError>().await?;
-                Err(CmcErrors::ApiError(format!(
-                    "Status Code: {}. Error message: {}",
-                    code, root.status.error_message
-                )))
-            }
-        }
-    }
-
-    /// Returns the latest market quote for 1 or more cryptocurrencies (using slug's).
-    #[cfg(feature = "cryptocurrency")]
-    pub async fn quotes_latest_by_slug<T: Into<String>>(&self, slugs: T) -> CmcResult<QLv2Slug> {
-        let slugs = slugs.into();
-
-        let rb = self
-            .add_endpoint("v2/cryptocurrency/quotes/latest")
-            .query(&[("slug", slugs)]);
-
-        let resp = if let Some(currency_id) = &self.config.currency_id {
-            rb.query(&[("convert_id", currency_id)]).send().await?
-        } else {
-            rb.query(&[("convert", &self.config.currency)])
-                .send()
-                .await?
-        };
-
-        match resp.status() {
-            StatusCode::OK => {
-                let root = resp.json::<QLv2Slug>().await?;
-                Ok(root)
-            }
-            code => {
-                let root = resp.json::<ApiError>().await?;
-                Err(CmcErrors::ApiError(format!(
-                    "Status Code: {}. Error message: {}",
-                    code, root.status.error_message
-                )))
-            }
-        }
-    }
-
-    /// Returns the latest market quote for 1 or more cryptocurrencies (using symbol's).
-    #[cfg(feature = "cryptocurrency")]
-    pub async fn quotes_latest_by_symbol<T: Into<String>>(
-        &self,
-        symbols: T,
-    ) -> CmcResult<QLv2Symbol> {
-        let symbols = symbols.into();
-
-        let rb = self
-            .add_endpoint("v2/cryptocurrency/quotes/latest")
-            .query(&[("symbol", symbols)]);
-
-        let resp = if let Some(currency_id) = &self.config.currency_id {
-            rb.query(&[("convert_id", currency_id)]).send().await?
-        } else {
-            rb.query(&[("convert", &self.config.currency)])
-                .send()
-                .await?
-        };
-
-        match resp.status() {
-            StatusCode::OK => {
-                let root = resp.json::<QLv2Symbol>().await?;
-                Ok(root)
-            }
-            code => {
-                let root = resp.json::<ApiError>().await?;
-                Err(CmcErrors::ApiError(format!(
-                    "Status Code: {}. Error message: {}",
-                    code, root.status.error_message
-                )))
-            }
-        }
-    }
-
-    /// Returns API key details and usage stats.
-    #[cfg(feature = "key")]
-    pub async fn key_info(&self) -> CmcResult<KeyInfo> {
-        let resp = self.add_endpoint("v1/key/info").send().await?;
-        match resp.status() {
-            StatusCode::OK => {
-                let root = resp.json::<CmcKeyInfo>().await?;
-                Ok(root.data)
-            }
-            code => {
-                let root = resp.json::<ApiError>().await?;
-                Err(CmcErrors::ApiError(format!(
-                    "Status Code: {}. Error message: {}",
-                    code, root.status.error_message
-                )))
-            }
-        }
-    }
-
-    /// Convert an amount of one cryptocurrency or fiat currency into one or more different currencies
-    /// utilizing the latest market rate for each currency.
-    ///
-    /// # Example:
-    ///
-    /// Parameters:
-    /// - `amount` An amount of currency to convert.
-    /// - `symbol` Alternatively the currency symbol of the base cryptocurrency or fiat to convert from.
-    /// - `time` Optional timestamp (Unix or ISO 8601) to reference historical pricing during conversion. If not passed, the current time will be used.
-    /// - `convert` Pass  fiat or cryptocurrency symbols to convert the source amount to.
-    ///
-    /// Basic usage:
-    ///
-    /// ```rust
-    /// use cmc::async_api::Cmc;
-    ///
-    /// let cmc = Cmc::new("<API KEY>");
-    ///
-    /// // 2.5 BTC in EUR
-    /// match cmc.price_conversion(2.5, "BTC", None, "EUR").await {
-    ///     Ok(price) => println!("Total price: {}", price),
-    ///     Err(err) => println!("Error: {}", err),
-    /// }
-    /// ```
-    #[cfg(feature = "tools")]
-    pub async fn price_conversion(
-        &self,
-        amount: f64,
-        symbol: &str,
-        time: Option<&str>,
-        convert: &str,
-    ) -> CmcResult<f64> {
-        let rb = self
-            .add_endpoint("v2/tools/price-conversion")
-            .query(&[("amount", amount)])
-            .query(&[("symbol", symbol), ("convert", convert)]);
-
-        let resp = match time {
-            Some(t) => rb.query(&[("time", t)]).send().await?,
-            None => rb.send().await?,
-        };
-
-        match resp.status() {
-            StatusCode::OK => {
-                let root = resp.json::<PCv2Symbol>().await?;
-                let price = root.data[0]
-                    .quote
-                    .get(&convert.to_uppercase())
-                    .unwrap()
-                    .price;
-                if let Some(price) = price {
-                    Ok(price)
-                } else {
-                    Err(CmcErrors::NullAnswer)
-                }
-            }
-            code => {
-                let root = resp.json::<ApiError>().await?;
-                Err(CmcErrors::ApiError(format!(
-                    "Status Code: {}. Error message: {}",
-                    code, root.status.error_message
-                )))
-            }
-        }
-    }
-
-    /// Convert an amount of one cryptocurrency or fiat currency into one or more different currencies
-    /// utilizing the latest market rate for each currency.
-    ///
-    /// # Example:
-    ///
-    /// Parameters:
-    /// - `amount` An amount of currency to convert.
-    /// - `id` The CoinMarketCap currency ID of the base cryptocurrency or fiat to convert from.
-    /// - `time` Optional timestamp (Unix or ISO 8601) to reference historical pricing during conversion. If not passed, the current time will be used.
-    /// - `convert_id` Optionally calculate market quotes by CoinMarketCap ID instead of symbol. This option is identical to convert outside of ID format.
-    ///
-    /// Basic usage:
-    ///
-    /// ```rust
-    /// use cmc::async_api::Cmc;
-    ///
-    /// let cmc = Cmc::new("<API KEY>");
-    ///
-    /// // 1.6 ETH in Monero (XMR).
-    /// match cmc.price_conversion_id(1.6, "1027", None, "328").await {
-    ///     Ok(price) => println!("Total price: {}", price),
-    ///     Err(err) => println!("Error: {}", err),
-    /// }
-    /// ```
-    #[cfg(feature = "tools")]
-    pub async fn price_conversion_id(
-        &self,
-        amount: f64,
-        id: &str,
-        time: Option<&str>,
-        convert_id: &str,
-    ) -> CmcResult<f64> {
-        let rb = self
-            .add_endpoint("v2/tools/price-conversion")
-            .query(&[("amount", amount)])
-            .query(&[("id", id), ("convert_id", convert_id)]);
-
-        let resp = match time {
-            Some(t) => rb.query(&[("time", t)]).send().await?,
-            None => rb.send().await?,
-        };
-
-        match resp.status() {
-            StatusCode::OK => {
-                let root = resp.json::<PCv2Id>().await?;
-                let price = root
-                    .data
-                    .quote
-                    .get(&convert_id.to_uppercase())
-                    .unwrap()
-                    .price;
-                if let Some(price) = price {
-                    Ok(price)
-                } else {
-                    Err(CmcErrors::NullAnswer)
-                }
-            }
-            code => {
-                let root = resp.json::<ApiError>().await?;
-                Err(CmcErrors::ApiError(format!(
-                    "Status Code: {}. Error message: {}",
-                    code, root.status.error_message
-                )))
+    /// Sends a request, or waits for an identical one in flight if coalescing is enabled.
+    async fn fetch(&self, request: HttpRequest) -> CmcResult<Value> {
+        match &self.config.flights {
+            Some(flights) => {
+                flights
+                    .run(&request, self.fetch_pooled(request.clone()))
+                    .await
             }
+            None => self.fetch_pooled(request).await,
         }
     }
 
-    /// Returns information about all coin categories available on CoinMarketCap.
-    ///
-    /// # Example:
-    ///
-    /// Parameters:
-    /// - `start` Optionally offset the start (1-based index) of the paginated list of items to return.
-    /// - `limit` Optionally specify the number of results to return. Use this parameter and the "start" parameter to determine your own pagination size.
-    /// - `pass` Cryptocurrency pass (id, slug, symbol)
-    ///
-    /// Basic usage:
-    ///
-    /// ```rust
-    /// use cmc::async_api::{CmcBuilder, Pass};
-    ///
-    /// let cmc = CmcBuilder::new("<API KEY>")
-    ///     .pass(Pass::Id)
-    ///     .build();
-    ///
-    /// match cmc.categories(1, 10, "1027").await {
-    ///     Ok(categories) => println!("{categories}"),
-    ///     Err(err) => println!("{err}"),
-    /// }
-    /// ```
+    /// Looks up the price of one asset, in a batch if batching is enabled.
     #[cfg(feature = "cryptocurrency")]
-    pub async fn categories<T: Into<String>>(
-        &self,
-        start: usize,
-        limit: usize,
-        pass: T,
-    ) -> CmcResult<CmcCategories> {
-        let query = pass.into();
-        let rb = self
-            .add_endpoint("v1/cryptocurrency/categories")
-            .query(&[("start", start), ("limit", limit)]);
-
-        let resp = match self.config.pass {
-            Pass::Symbol => rb.query(&[("symbol", query)]).send().await?,
-            Pass::Id => rb.query(&[("id", query)]).send().await?,
-            Pass::Slug => rb.query(&[("slug", query)]).send().await?,
-            Pass::Address => return Err(CmcErrors::PassIncompatible),
-        };
-
-        match resp.status() {
-            StatusCode::OK => {
-                let root = resp.json::<CmcCategories>().await?;
-                Ok(root)
-            }
-            code => {
-                let root = resp.json::<ApiError>().await?;
-                Err(CmcErrors::ApiError(format!(
-                    "Status Code: {}. Error message: {}",
-                    code, root.status.error_message
-                )))
-            }
+    async fn lookup(&self, asset: AssetRef, currency: &str) -> CmcResult<Amount> {
+        match &self.config.batcher {
+            Some(batcher) => batcher.price(self, asset, currency).await,
+            None => self.price_of(asset, currency).await,
         }
     }
 
-    /// Returns information about a single coin category available on CoinMarketCap.
+    /// Polls the latest quotes of `ids` every `interval` and streams the ones whose
+    /// `last_updated` changed, see [`crate::watch`]. Polling runs on a task spawned on the
+    /// current Tokio runtime. Called outside one, the stream yields `CmcErrors::NoRuntime`.
     ///
     /// # Example:
     ///
-    /// Parameters:
-    /// - `id` The Category ID. This can be found using the [categories()].
-    /// - `start` Optionally offset the start (1-based index) of the paginated list of coins to return.
-    /// - `limit` Optionally specify the number of coins to return. Use this parameter and the "start" parameter to determine your own pagination size.
-    ///
-    /// Basic usage:
-    ///
-    /// ```rust
-    /// use cmc::async_api::CmcBuilder;
-    ///
-    /// let cmc = CmcBuilder::new("<API KEY>")
-    ///     .convert("EUR")
-    ///     .build();
-    ///
-    /// match cmc.category("605e2ce9d41eae1066535f7c", 1, 10).await {
-    ///     Ok(category) => println!("{category}"),
-    ///     Err(err) => println!("{err}"),
-    /// }
-    /// ```
-    /// [categories()]: ./struct.Cmc.html#method.categories
-    #[cfg(feature = "cryptocurrency")]
-    pub async fn category(&self, id: &str, start: usize, limit: usize) -> CmcResult<Category> {
-        let rb = self
-            .add_endpoint("v1/cryptocurrency/category")
-            .query(&[("id", id)])
-            .query(&[("start", start), ("limit", limit)]);
-
-        let resp = if let Some(currency_id) = &self.config.currency_id {
-            rb.query(&[("convert_id", currency_id)]).send().await?
-        } else {
-            rb.query(&[("convert", &self.config.currency)])
-                .send()
-                .await?
-        };
-
-        match resp.status() {
-            StatusCode::OK => {
-                let root = resp.json::<CmcCategory>().await?;
-                Ok(root.data)
-            }
-            code => {
-                let root = resp.json::<ApiError>().await?;
-                Err(CmcErrors::ApiError(format!(
-                    "Status Code: {}. Error message: {}",
-                    code, root.status.error_message
-                )))
-            }
-        }
-    }
-
-    /// Returns all static metadata available for one or more cryptocurrencies.
-    /// This information includes details like logo, description, official website URL, social links,
-    /// and links to a cryptocurrency's technical documentation.
-    ///
-    /// Parameters:
-    ///
-    /// - **Id**: Cryptocurrency coinmarketcap id. Example: "1027"
-    ///
-    /// - **Slug**: Alternatively pass one cryptocurrency slug. Example: "ethereum"
-    ///
-    /// - **Symbol**: Alternatively pass one cryptocurrency symbol. Example: "BTC"
-    ///
-    /// - **Address**: Alternatively pass in a contract address. Example: "0xc40af1e4fecfa05ce6bab79dcd8b373d2e436c4e"
-    ///
-    /// **NOTE**: `CoinMarketCap recommend utilizing CMC ID instead of cryptocurrency symbols to securely identify cryptocurrencies with other endpoints and in your own application logic`
-    /// (Can be obtained using the method [id_map()][id]).
-    /// ```rust
-    /// use cmc::async_api::{CmcBuilder, Pass};
-    ///
-    /// let cmc = CmcBuilder::new("<API KEY>")
-    ///     .pass(Pass::Id)
-    ///     .build();
-    /// // Cryptocurrency metadata.
-    /// match cmc.metadata("1027").await {
-    ///     Ok(metadata) => println!("{}", metadata.description),
-    ///     Err(err) => println!("{}", err),
-    /// }
-    ///
-    /// let cmc = CmcBuilder::new("<API KEY>")
-    ///     .pass(Pass::Address)
-    ///     .build();
-    /// // Contract address metadata.
-    /// match cmc.metadata("0xc40af1e4fecfa05ce6bab79dcd8b373d2e436c4e").await {
-    ///     Ok(metadata) => println!("{}", metadata.description),
-    ///     Err(err) => println!("{}", err),
-    /// }
-    ///```
-    /// [id]: ./struct.Cmc.html#method.id_map
-    #[cfg(feature = "cryptocurrency")]
-    pub async fn metadata<T: Into<String>>(&self, query: T) -> CmcResult<Metadata> {
-        let query = query.into();
-
-        if query.contains(',') {
-            return Err(CmcErrors::IncorrectQuery);
-        }
-
-        let rb = self.add_endpoint("v2/cryptocurrency/info");
-
-        let resp = match self.config.pass {
-            Pass::Symbol => rb.query(&[("symbol", &query)]).send().await?,
-            Pass::Id => rb.query(&[("id", &query)]).send().await?,
-            Pass::Slug => rb.query(&[("slug", &query.to_lowercase())]).send().await?,
-            Pass::Address => rb.query(&[("address", &query)]).send().await?,
-        };
-
-        match resp.status() {
-            StatusCode::OK => match self.config.pass {
-                Pass::Symbol => {
-                    let mut root = resp.json::<MDv2Symbol>().await?;
-                    let md_vec = root.data.remove(&query).unwrap();
-                    Ok(md_vec[0].clone())
-                }
-                Pass::Slug | Pass::Address => {
-                    let mut root = resp.json::<MDv2>().await?;
-                    let slug_id = root.data.iter().next().unwrap().0.to_owned();
-                    let md = root.data.remove(&slug_id).unwrap();
-                    Ok(md)
-                }
-                Pass::Id => {
-                    let mut root = resp.json::<MDv2>().await?;
-                    let md = root.data.remove(&query).unwrap();
-                    Ok(md)
-                }
-            },
-            code => {
-                let root = resp.json::<ApiError>().await?;
-                Err(CmcErrors::ApiError(format!(
-                    "Status Code: {}. Error message: {}",
-                    code, root.status.error_message
-                )))
-            }
-        }
-    }
-
-    /// Returns HashMap with all static metadata available for one or more cryptocurrencies.
-    /// This information includes details like logo, description, official website URL, social links,
-    /// and links to a cryptocurrency's technical documentation.
-    /// # Examples:
-    ///
-    /// Parameters:
-    ///
-    /// - **query**: One or more comma-separated CoinMarketCap cryptocurrency exchange ids. Example: "1,328,1027"
-    /// ```rust
+    /// ```rust,no_run
     /// use cmc::async_api::Cmc;
+    /// use cmc::CryptoId;
+    /// use std::time::Duration;
     ///
+    /// # async fn watch() {
     /// let cmc = Cmc::new("<API KEY>");
     ///
-    /// let query = "1,328,1027";
-    /// let map = cmc.metadata_map(query).await?;
-    ///
-    /// for m in map.values() {
-    ///     println!("{}", m.name);
+    /// let mut updates = cmc.watch_quotes([CryptoId(1), CryptoId(1027)], Duration::from_secs(60));
+    /// while let Some(update) = updates.recv().await {
+    ///     match update {
+    ///         Ok(update) => println!("{}: {:?}", update.quote.symbol, update.quote.quote["USD"].price),
+    ///         Err(err) => println!("{}", err),
+    ///     }
     /// }
+    /// # }
     /// ```
-    #[cfg(feature = "cryptocurrency")]
-    pub async fn metadata_map<T: Into<String>>(
+    #[cfg(all(feature = "cryptocurrency", feature = "async"))]
+    pub fn watch_quotes<T: Identifiers<CryptoId>>(
         &self,
-        query: T,
-    ) -> CmcResult<HashMap<String, Metadata>> {
-        let query = query.into();
-        let rb = self.add_endpoint("v2/cryptocurrency/info");
-        let resp = rb.query(&[("id", &query)]).send().await?;
-
-        match resp.status() {
-            StatusCode::OK => {
-                let root = resp.json::<MDv2>().await?;
-                let map = root.data;
-                Ok(map)
-            }
-            code => {
-                let root = resp.json::<ApiError>().await?;
-                Err(CmcErrors::ApiError(format!(
-                    "Status Code: {}. Error message: {}",
-                    code, root.status.error_message
-                )))
-            }
-        }
+        ids: T,
+        interval: Duration,
+    ) -> crate::watch::QuoteStream {
+        crate::watch::QuoteStream::new(self.clone(), ids.to_query(), interval)
     }
 
-    /// Returns the latest global cryptocurrency market metrics. Use the [convert()] to return
-    /// market values in multiple fiat and cryptocurrency conversions in the same call.
-    ///
-    /// ```rust
-    /// use cmc::async_api::CmcBuilder;
-    ///
-    /// let cmc = CmcBuilder::new("<API KEY>")
-    ///     .convert("EUR")
-    ///     .build();
-    ///
-    /// match cmc.global_metrics().await {
-    ///     Ok(gm) => println!("{}", gm.btc_dominance),
-    ///     Err(err) => println!("{}", err),
-    /// }
-    /// ```
-    /// [convert()]: ./struct.CmcBuilder.html#method.convert
-    #[cfg(feature = "global_metrics")]
-    pub async fn global_metrics(&self) -> CmcResult<GlobalMetrics> {
-        let rb = self.add_endpoint("v1/global-metrics/quotes/latest");
-
-        let resp = if let Some(currency_id) = &self.config.currency_id {
-            rb.query(&[("convert_id", currency_id)]).send().await?
-        } else {
-            rb.query(&[("convert", &self.config.currency)])
-                .send()
-                .await?
-        };
-
-        match resp.status() {
-            StatusCode::OK => {
-                let root = resp.json::<CmcGlobalMetrics>().await?;
-                Ok(root.data)
-            }
-            code => {
-                let root = resp.json::<ApiError>().await?;
-                Err(CmcErrors::ApiError(format!(
-                    "Status Code: {}. Error message: {}",
-                    code, root.status.error_message
-                )))
-            }
-        }
-    }
-
-    /// Returns all static metadata for one or more exchanges. This information includes details
-    /// like launch date, logo, official website URL, social links, and market fee documentation URL.
-    ///
-    /// # Examples:
-    ///
-    /// Parameters:
-    ///
-    /// - **Id**: One or more comma-separated CoinMarketCap cryptocurrency exchange ids. Example: "270,271"
-    ///
-    /// - **Slug**: Alternatively, one or more comma-separated exchange names in URL friendly
-    ///   shorthand "slug" format (all lowercase, spaces replaced with hyphens). Example: "binance,gdax".
-    ///
-    /// ```rust
-    /// use cmc::async_api::{CmcBuilder, Pass};
-    ///
-    /// // using Id
-    /// let cmc = CmcBuilder::new("<API KEY>")
-    ///     .pass(Pass::Id)
-    ///     .build();
-    ///
-    /// match cmc.exchange_metadata("270").await {
-    ///     Ok(metadata) => println!("{}", metadata.data.get("270").unwrap().name),
-    ///     Err(err) => println!("{}", err),
-    /// }
-    ///
-    /// // using Slug
-    /// let cmc = CmcBuilder::new("<API KEY>")
-    ///     .pass(Pass::Slug)
-    ///     .build();
-    ///
-    /// match cmc.exchange_metadata("binance").await {
-    ///     Ok(metadata) => println!("{}", metadata.data.get("binance").unwrap().name),
-    ///     Err(err) => println!("{}", err),
-    /// }
-    /// ```
-    #[cfg(feature = "exchange")]
-    pub async fn exchange_metadata<T: Into<String>>(
-        &self,
-        exchange: T,
-    ) -> CmcResult<ExchangeMetadata> {
-        let exchange = exchange.into();
-
-        let rb = self.add_endpoint("v1/exchange/info");
-
-        let resp = match self.config.pass {
-            Pass::Symbol => return Err(CmcErrors::PassIncompatible),
-            Pass::Id => rb.query(&[("id", &exchange)]).send().await?,
-            Pass::Slug => {
-                rb.query(&[("slug", &exchange.to_lowercase())])
-                    .send()
-                    .await?
+    /// Refreshes a stale cache entry on a background task, or in place without a Tokio runtime.
+    async fn refresh(&self, cache: RefCache, key: String, request: HttpRequest) {
+        let cmc = self.clone();
+        let refresh = async move {
+            if let Ok(body) = cmc.fetch(request).await {
+                let _ = cache.store(&key, &body);
             }
-            Pass::Address => return Err(CmcErrors::PassIncompatible),
+            cache.end_refresh(&key);
         };
-
-        match resp.status() {
-            StatusCode::OK => {
-                let root = resp.json::<ExchangeMetadata>().await?;
-                Ok(root)
-            }
-            code => {
-                let root = resp.json::<ApiError>().await?;
-                Err(CmcErrors::ApiError(format!(
-                    "Status Code: {}. Error message: {}",
-                    code, root.status.error_message
-                )))
-            }
-        }
-    }
-
-    /// Returns a paginated list of all active cryptocurrency exchanges by CoinMarketCap ID.
-    ///
-    /// # Examples:
-    ///
-    /// Parameters:
-    ///
-    /// - `listing_status`:
-    ///
-    ///  **Active**: Only active exchanges are returned.
-    ///
-    ///  **Inactive**: List of exchanges that are no longer active.
-    ///
-    ///  **Untracked**: List of exchanges that are registered but do not currently meet methodology requirements to have active markets tracked.
-    ///
-    /// - `start`: Optionally offset the start (1-based index) of the paginated list of items to return.
-    ///
-    /// - `limit`: Optionally specify the number of results to return. Use this parameter and the "start" parameter to determine your own pagination size.
-    ///
-    /// - `sort`: What field to sort the list of exchanges by.
-    ///
-    /// - `crypto_id`: Optionally include one fiat or cryptocurrency IDs to filter market pairs by.
-    ///
-    /// ```rust
-    /// use cmc::async_api::{Cmc, ListingStatusExchange, SortExchange};
-    ///
-    /// let cmc = Cmc::new("<API KEY>");
-    ///
-    /// match cmc.exchange_id_map(ListingStatusExchange::Active, 1, 10, SortExchange::Id, None).await {
-    ///     Ok(map) => println!("{}", map),
-    ///     Err(err) => println!("{}", err),
-    /// }
-    /// ```
-    #[cfg(feature = "exchange")]
-    pub async fn exchange_id_map(
-        &self,
-        listing_status: ListingStatusExchange,
-        start: usize,
-        limit: usize,
-        sort: SortExchange,
-        crypto_id: Option<&str>,
-    ) -> CmcResult<CmcExchangeIdMap> {
-        let rb = self
-            .add_endpoint("v1/exchange/map")
-            .query(&[("start", start), ("limit", limit)]);
-
-        let rb = match listing_status {
-            ListingStatusExchange::Active => rb.query(&[("listing_status", "active")]),
-            ListingStatusExchange::Inactive => rb.query(&[("listing_status", "inactive")]),
-            ListingStatusExchange::Untracked => rb.query(&[("listing_status", "untracked")]),
-        };
-
-        let rb = match sort {
-            SortExchange::Id => rb.query(&[("sort", "id")]),
-            SortExchange::Volume24h => rb.query(&[("sort", "volume_24h")]),
-        };
-
-        let resp = if let Some(id) = crypto_id {
-            rb.query(&[("crypto_id", id)]).send().await?
-        } else {
-            rb.send().await?
-        };
-
-        match resp.status() {
-            StatusCode::OK => {
-                let root = resp.json::<CmcExchangeIdMap>().await?;
-                Ok(root)
-            }
-            code => {
-                let root = resp.json::<ApiError>().await?;
-                Err(CmcErrors::ApiError(format!(
-                    "Status Code: {}. Error message: {}",
-                    code, root.status.error_message
-                )))
-            }
+        match tokio::runtime::Handle::try_current() {
+            Ok(runtime) => drop(runtime.spawn(refresh)),
+            Err(_) => refresh.await,
         }
     }
 }
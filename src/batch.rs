@@ -0,0 +1,170 @@
+//! Micro-batching of `price()` calls for the async client.
+//!
+//! Price lookups arriving within a short window are queued by kind (id, slug or symbol) and sent
+//! as one `v2/cryptocurrency/quotes/latest` request with all their values, e.g. `?id=1,1027,825`.
+//! A batch is sent when its window ends or when it holds the maximum number of distinct values,
+//! whichever comes first. Each caller then gets the price of its own asset from the response.
+//! Batches are sent from tasks on the current Tokio runtime; without one `price()` fails with
+//! `CmcErrors::NoRuntime`.
+
+use crate::api::cryptocurrency::quotes_latest_v2::{single, CryptoCurrency};
+use crate::api::{Amount, CmcResult};
+use crate::async_api::Cmc;
+use crate::errors::CmcErrors;
+use crate::flight::shared;
+use crate::ids::AssetRef;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::runtime::Handle;
+use tokio::sync::oneshot;
+
+type Waiter = oneshot::Sender<CmcResult<Amount>>;
+
+/// Lookups of one kind and currency waiting to be sent.
+#[derive(Debug, Default)]
+struct Batch {
+    id: u64,
+    values: Vec<(String, Vec<Waiter>)>,
+}
+
+/// Pending batches, by kind and currency.
+#[derive(Debug)]
+pub(crate) struct Batcher {
+    window: Duration,
+    max_size: usize,
+    next_id: AtomicU64,
+    pending: Mutex<HashMap<(Kind, String), Batch>>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Kind {
+    Id,
+    Slug,
+    Symbol,
+}
+
+impl Batcher {
+    pub(crate) fn new(window: Duration, max_size: usize) -> Self {
+        Self {
+            window,
+            max_size: max_size.max(1),
+            next_id: AtomicU64::new(0),
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Queues the lookup of `asset` and waits for the batch it ends up in.
+    pub(crate) async fn price(
+        self: &Arc<Self>,
+        cmc: &Cmc,
+        asset: AssetRef,
+        currency: &str,
+    ) -> CmcResult<Amount> {
+        let (kind, value) = match asset {
+            AssetRef::Id(id) => (Kind::Id, id.to_string()),
+            AssetRef::Slug(slug) => (Kind::Slug, slug.as_str().to_lowercase()),
+            AssetRef::Symbol(symbol) => (Kind::Symbol, symbol.as_str().to_uppercase()),
+            AssetRef::Address(_) => return Err(CmcErrors::PassIncompatible),
+        };
+        let runtime = Handle::try_current().map_err(|_| CmcErrors::NoRuntime)?;
+        let key = (kind, currency.to_owned());
+        let (tx, rx) = oneshot::channel();
+
+        {
+            let mut pending = self.pending.lock().unwrap();
+            let opened = !pending.contains_key(&key);
+            let batch = pending.entry(key.clone()).or_insert_with(|| Batch {
+                id: self.next_id.fetch_add(1, Ordering::Relaxed),
+                values: Vec::new(),
+            });
+            match batch.values.iter_mut().find(|(queued, _)| *queued == value) {
+                Some((_, waiters)) => waiters.push(tx),
+                None => batch.values.push((value, vec![tx])),
+            }
+
+            if batch.values.len() >= self.max_size {
+                let batch = pending.remove(&key).unwrap_or_default();
+                runtime.spawn(send(cmc.clone(), key, batch));
+            } else if opened {
+                let id = batch.id;
+                let batcher = self.clone();
+                let cmc = cmc.clone();
+                runtime.spawn(async move {
+                    tokio::time::sleep(batcher.window).await;
+                    let batch = {
+                        let mut pending = batcher.pending.lock().unwrap();
+                        match pending.get(&key) {
+                            Some(batch) if batch.id == id => pending.remove(&key),
+                            _ => None,
+                        }
+                    };
+                    if let Some(batch) = batch {
+                        send(cmc, key, batch).await;
+                    }
+                });
+            }
+        }
+
+        rx.await.unwrap_or(Err(CmcErrors::NullAnswer))
+    }
+}
+
+/// Sends one request for the whole batch and hands each waiter its price.
+async fn send(cmc: Cmc, (kind, currency): (Kind, String), batch: Batch) {
+    let query = batch
+        .values
+        .iter()
+        .map(|(value, _)| value.as_str())
+        .collect::<Vec<_>>()
+        .join(",");
+
+    let price = |cc: &CryptoCurrency| {
+        cc.quote
+            .get(&currency)
+            .and_then(|q| q.price)
+            .ok_or(CmcErrors::NullAnswer)
+    };
+    let prices: CmcResult<HashMap<String, CmcResult<Amount>>> = match kind {
+        Kind::Id => cmc.quotes().by_id(query).send().await.map(|root| {
+            root.data
+                .into_iter()
+                .map(|(id, cc)| (id, price(&cc)))
+                .collect()
+        }),
+        Kind::Slug => cmc.quotes().by_slug(query).send().await.map(|root| {
+            root.data
+                .into_values()
+                .map(|cc| (cc.slug.clone(), price(&cc)))
+                .collect()
+        }),
+        // A symbol shared by several cryptocurrencies is ambiguous, as in `price()`.
+        Kind::Symbol => cmc.quotes().by_symbol(query).send().await.map(|root| {
+            root.data
+                .iter()
+                .map(|(symbol, ccs)| (symbol.clone(), single(symbol, Some(ccs)).and_then(price)))
+                .collect()
+        }),
+    };
+
+    match prices {
+        Ok(mut prices) => {
+            for (value, waiters) in batch.values {
+                let price = prices
+                    .remove(&value)
+                    .unwrap_or(Err(CmcErrors::NullAnswer))
+                    .map_err(Arc::new);
+                for waiter in waiters {
+                    let _ = waiter.send(price.as_ref().copied().map_err(shared));
+                }
+            }
+        }
+        Err(err) => {
+            let err = Arc::new(err);
+            for waiter in batch.values.into_iter().flat_map(|(_, waiters)| waiters) {
+                let _ = waiter.send(Err(shared(&err)));
+            }
+        }
+    }
+}
@@ -0,0 +1,588 @@
+//! `cmc-tui`: live watchlist, global metrics and key usage in the terminal.
+//!
+//! Built with the `tui` feature: `cargo install cmc --features tui`.
+//!
+//! The API key, base URL and convert currency come from `CMC_API_KEY`, `CMC_BASE_URL` and
+//! `CMC_CONVERT`, or from the options. `--sandbox` uses the sandbox environment, with the public
+//! sandbox key unless a key is given, `--base-url` any server speaking the API, e.g. a local
+//! mock. Prices are in the first convert currency. Every refresh costs the credits of one quotes
+//! request and one global metrics request, failed refreshes back off up to 16 times the interval.
+//!
+//! Keys: `r` refreshes now, `q` or `Esc` quits.
+
+use clap::Parser;
+use cmc::api::cryptocurrency::quotes_latest_v2::CryptoCurrency;
+use cmc::api::global_metrics::GlobalMetrics;
+use cmc::api::key::KeyInfo;
+use cmc::api::{Amount, Cmc, CmcResult, SANDBOX_API_KEY};
+use cmc::{CmcBuilder, Environment, Symbol};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Cell, Paragraph, Row, Table};
+use ratatui::{DefaultTerminal, Frame};
+use std::collections::HashMap;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Live CoinMarketCap dashboard.
+#[derive(Debug, Parser)]
+#[command(name = "cmc-tui", version)]
+struct Cli {
+    /// Symbols of the watchlist.
+    #[arg(default_values_t = ["BTC".to_owned(), "ETH".to_owned()])]
+    symbols: Vec<String>,
+
+    /// API key.
+    #[arg(long, env = "CMC_API_KEY", hide_env_values = true)]
+    key: Option<String>,
+
+    /// API base URL, e.g. of a mock server. It has to end with a slash.
+    #[arg(long, env = "CMC_BASE_URL")]
+    base_url: Option<String>,
+
+    /// Use the sandbox environment, with the public sandbox key unless a key is given.
+    #[arg(long)]
+    sandbox: bool,
+
+    /// Currency to show prices in. Of a list like "USD,BTC" the first one.
+    #[arg(long, env = "CMC_CONVERT", default_value = "USD")]
+    convert: String,
+
+    /// Seconds between refreshes.
+    #[arg(long, default_value_t = 60)]
+    interval: u64,
+}
+
+/// Result of one poll of an endpoint.
+enum Update {
+    Quotes(CmcResult<Vec<CryptoCurrency>>),
+    Global(CmcResult<GlobalMetrics>),
+    Key(CmcResult<KeyInfo>),
+}
+
+/// Everything on screen.
+struct App {
+    symbols: Vec<String>,
+    currency: String,
+    source: String,
+    quotes: Vec<CryptoCurrency>,
+    global: Option<GlobalMetrics>,
+    key: Option<KeyInfo>,
+    error: Option<String>,
+    updated: Option<Instant>,
+}
+
+/// Longest wait after failed refreshes, in intervals.
+const MAX_BACKOFF: u32 = 16;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let cli = Cli::parse();
+    let (cmc, mut app) = client(&cli)?;
+
+    let (updates, rx) = mpsc::channel();
+    let (refresh, requests) = mpsc::channel();
+    let interval = Duration::from_secs(cli.interval.max(1));
+    let watchlist = app.symbols.clone();
+    thread::spawn(move || poll(cmc, watchlist, interval, requests, updates));
+
+    let mut terminal = ratatui::init();
+    let result = run(&mut terminal, &mut app, rx, refresh);
+    ratatui::restore();
+    result
+}
+
+/// Client for the options and the environment, and the app showing its data.
+fn client(cli: &Cli) -> Result<(Cmc, App), Box<dyn std::error::Error>> {
+    let key = cli
+        .key
+        .clone()
+        .or_else(|| cli.sandbox.then(|| SANDBOX_API_KEY.to_owned()))
+        .ok_or("no API key: pass --key or set CMC_API_KEY")?;
+
+    let mut builder = CmcBuilder::new(key).convert(&cli.convert);
+    let currency = cli.convert.split(',').next().unwrap_or_default();
+    let currency = currency.trim().to_uppercase();
+    let source = if cli.sandbox {
+        builder = builder.environment(Environment::Sandbox);
+        Environment::Sandbox.base_url().to_owned()
+    } else if let Some(base_url) = &cli.base_url {
+        builder = builder.base_url(base_url);
+        base_url.clone()
+    } else {
+        Environment::Production.base_url().to_owned()
+    };
+    let symbols = cli.symbols.iter().map(|s| s.to_uppercase()).collect();
+    Ok((builder.build()?, App::new(symbols, currency, source)))
+}
+
+/// Polls all panels every `interval`, or right away when asked to. After failed rounds the wait
+/// doubles, see `backoff`.
+fn poll(
+    cmc: Cmc,
+    symbols: Vec<String>,
+    interval: Duration,
+    requests: Receiver<()>,
+    updates: Sender<Update>,
+) {
+    let symbols: Vec<Symbol> = symbols.into_iter().map(Symbol::new).collect();
+    let mut failures = 0;
+    loop {
+        let quotes = cmc
+            .quotes()
+            .by_symbol(symbols.as_slice())
+            .send()
+            .map(|root| watchlist(&symbols, root.data));
+        let polled = [
+            Update::Quotes(quotes),
+            Update::Global(cmc.global_metrics()),
+            Update::Key(cmc.key_info()),
+        ];
+        let failed = polled.iter().any(Update::is_err);
+        failures = if failed { failures + 1 } else { 0 };
+        for update in polled {
+            if updates.send(update).is_err() {
+                return;
+            }
+        }
+
+        match requests.recv_timeout(backoff(interval, failures)) {
+            Ok(()) | Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => return,
+        }
+    }
+}
+
+/// The wait before the next round, doubled for each failed round in a row up to `MAX_BACKOFF`
+/// intervals, so a spent or rate limited key isn't hammered.
+fn backoff(interval: Duration, failures: u32) -> Duration {
+    let factor = 1u32.checked_shl(failures).unwrap_or(u32::MAX);
+    interval.saturating_mul(factor.min(MAX_BACKOFF))
+}
+
+/// The quotes in the order of the watchlist, without the symbols that weren't found.
+fn watchlist(
+    symbols: &[Symbol],
+    mut data: HashMap<String, Vec<CryptoCurrency>>,
+) -> Vec<CryptoCurrency> {
+    symbols
+        .iter()
+        .filter_map(|symbol| data.remove(symbol.as_str())?.into_iter().next())
+        .collect()
+}
+
+fn run(
+    terminal: &mut DefaultTerminal,
+    app: &mut App,
+    updates: Receiver<Update>,
+    refresh: Sender<()>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    loop {
+        for update in updates.try_iter() {
+            app.apply(update);
+        }
+        terminal.draw(|frame| app.draw(frame))?;
+
+        if event::poll(Duration::from_millis(250))? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind != KeyEventKind::Press {
+                    continue;
+                }
+                match key.code {
+                    KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                    KeyCode::Char('r') => {
+                        let _ = refresh.send(());
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+impl Update {
+    fn is_err(&self) -> bool {
+        match self {
+            Update::Quotes(result) => result.is_err(),
+            Update::Global(result) => result.is_err(),
+            Update::Key(result) => result.is_err(),
+        }
+    }
+}
+
+impl App {
+    fn new(symbols: Vec<String>, currency: String, source: String) -> App {
+        App {
+            symbols,
+            currency,
+            source,
+            quotes: Vec::new(),
+            global: None,
+            key: None,
+            error: None,
+            updated: None,
+        }
+    }
+
+    /// Takes in one update. The error line is cleared with every successful quotes poll, which
+    /// comes first in each round.
+    fn apply(&mut self, update: Update) {
+        match update {
+            Update::Quotes(Ok(quotes)) => {
+                self.quotes = quotes;
+                self.updated = Some(Instant::now());
+                self.error = None;
+            }
+            Update::Global(Ok(global)) => self.global = Some(global),
+            Update::Key(Ok(key)) => self.key = Some(key),
+            Update::Quotes(Err(err)) | Update::Global(Err(err)) | Update::Key(Err(err)) => {
+                self.error = Some(err.to_string());
+            }
+        }
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let [header, watchlist, panels, footer] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Min(5),
+            Constraint::Length(10),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+        let [global, key] =
+            Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)])
+                .areas(panels);
+
+        self.draw_header(frame, header);
+        self.draw_watchlist(frame, watchlist);
+        self.draw_global(frame, global);
+        self.draw_key(frame, key);
+
+        let help = match &self.error {
+            Some(err) => Line::styled(format!("error: {}", err), Style::new().fg(Color::Red)),
+            None => Line::raw("r refresh  q quit"),
+        };
+        frame.render_widget(Paragraph::new(help), footer);
+    }
+
+    fn draw_header(&self, frame: &mut Frame, area: Rect) {
+        let updated = match self.updated {
+            Some(at) => format!("updated {}s ago", at.elapsed().as_secs()),
+            None => "loading...".to_owned(),
+        };
+        let line = Line::from(vec![
+            Span::styled("cmc ", Style::new().add_modifier(Modifier::BOLD)),
+            Span::raw(format!("{}  {}  {}", self.source, self.currency, updated)),
+        ]);
+        frame.render_widget(Paragraph::new(line), area);
+    }
+
+    fn draw_watchlist(&self, frame: &mut Frame, area: Rect) {
+        let header = Row::new([
+            "#",
+            "Symbol",
+            "Name",
+            "Price",
+            "1h",
+            "24h",
+            "7d",
+            "Market cap",
+            "Volume 24h",
+        ])
+        .style(Style::new().add_modifier(Modifier::BOLD));
+
+        let rows = self.quotes.iter().map(|cc| {
+            let quote = cc.quote.get(&self.currency);
+            Row::new([
+                Cell::from(cc.cmc_rank.map(|rank| rank.to_string()).unwrap_or_default()),
+                Cell::from(cc.symbol.clone()),
+                Cell::from(cc.name.clone()),
+                Cell::from(quote.and_then(|q| q.price).map(price).unwrap_or_default()),
+                change(quote.and_then(|q| q.percent_change_1h)),
+                change(quote.and_then(|q| q.percent_change_24h)),
+                change(quote.and_then(|q| q.percent_change_7d)),
+                Cell::from(
+                    quote
+                        .and_then(|q| q.market_cap)
+                        .map(compact)
+                        .unwrap_or_default(),
+                ),
+                Cell::from(
+                    quote
+                        .and_then(|q| q.volume_24h)
+                        .map(compact)
+                        .unwrap_or_default(),
+                ),
+            ])
+        });
+
+        let widths = [
+            Constraint::Length(5),
+            Constraint::Length(8),
+            Constraint::Min(12),
+            Constraint::Length(14),
+            Constraint::Length(8),
+            Constraint::Length(8),
+            Constraint::Length(8),
+            Constraint::Length(11),
+            Constraint::Length(11),
+        ];
+        let missing = self.symbols.len().saturating_sub(self.quotes.len());
+        let title = match missing {
+            n if n > 0 && self.updated.is_some() => format!(" Watchlist ({} not found) ", n),
+            _ => " Watchlist ".to_owned(),
+        };
+        let table = Table::new(rows, widths)
+            .header(header)
+            .block(Block::new().borders(Borders::ALL).title(title));
+        frame.render_widget(table, area);
+    }
+
+    fn draw_global(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::new().borders(Borders::ALL).title(" Global metrics ");
+        let Some(global) = &self.global else {
+            frame.render_widget(Paragraph::new("loading...").block(block), area);
+            return;
+        };
+        let quote = global.quote.get(&self.currency);
+        let lines = vec![
+            Line::from(format!(
+                "Market cap {}   Volume 24h {}",
+                quote
+                    .map(|q| compact(q.total_market_cap))
+                    .unwrap_or_default(),
+                quote
+                    .map(|q| compact(q.total_volume_24h))
+                    .unwrap_or_default()
+            )),
+            Line::from(format!(
+                "BTC dominance {:.2}% ({:+.2}%)   ETH dominance {:.2}% ({:+.2}%)",
+                global.btc_dominance,
+                global.btc_dominance_24h_percentage_change,
+                global.eth_dominance,
+                global.eth_dominance_24h_percentage_change
+            )),
+            Line::from(format!(
+                "DeFi market cap {}   volume 24h {}   {:+.2}%",
+                compact_f64(global.defi_market_cap),
+                compact_f64(global.defi_volume_24h),
+                global.defi_24h_percentage_change
+            )),
+            Line::from(format!(
+                "Stablecoin market cap {}   volume 24h {}   {:+.2}%",
+                compact_f64(global.stablecoin_market_cap),
+                compact_f64(global.stablecoin_volume_24h),
+                global.stablecoin_24h_percentage_change
+            )),
+            Line::from(format!(
+                "Derivatives volume 24h {}   {:+.2}%",
+                compact_f64(global.derivatives_volume_24h),
+                global.derivatives_24h_percentage_change
+            )),
+            Line::from(format!(
+                "Active cryptocurrencies {}   exchanges {}   market pairs {}",
+                global.active_cryptocurrencies, global.active_exchanges, global.active_market_pairs
+            )),
+        ];
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+
+    fn draw_key(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::new().borders(Borders::ALL).title(" API key usage ");
+        let Some(key) = &self.key else {
+            frame.render_widget(Paragraph::new("loading...").block(block), area);
+            return;
+        };
+        let usage = &key.usage;
+        let lines = vec![
+            Line::from(format!(
+                "Month: {} used, {} left of {}",
+                usage.current_month.credits_used,
+                usage.current_month.credits_left,
+                key.plan.credit_limit_monthly
+            )),
+            Line::from(format!("Resets {}", key.plan.credit_limit_monthly_reset)),
+            Line::from(format!(
+                "Today: {} used{}",
+                usage.current_day.credits_used,
+                usage
+                    .current_day
+                    .credits_left
+                    .map(|left| format!(", {} left", left))
+                    .unwrap_or_default()
+            )),
+            Line::from(format!(
+                "Minute: {} requests, {} left of {}",
+                usage.current_minute.requests_made,
+                usage.current_minute.requests_left,
+                key.plan.rate_limit_minute
+            )),
+        ];
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+}
+
+fn to_f64(amount: Amount) -> f64 {
+    amount.to_string().parse().unwrap_or_default()
+}
+
+fn price(amount: Amount) -> String {
+    let value = to_f64(amount);
+    if value.abs() >= 1.0 {
+        format!("{:.2}", value)
+    } else {
+        format!("{:.6}", value)
+    }
+}
+
+fn compact(amount: Amount) -> String {
+    compact_f64(to_f64(amount))
+}
+
+fn compact_f64(value: f64) -> String {
+    let (value, suffix) = match value.abs() {
+        v if v >= 1e12 => (value / 1e12, "T"),
+        v if v >= 1e9 => (value / 1e9, "B"),
+        v if v >= 1e6 => (value / 1e6, "M"),
+        v if v >= 1e3 => (value / 1e3, "K"),
+        _ => (value, ""),
+    };
+    format!("{:.2}{}", value, suffix)
+}
+
+/// Percent change, green when up and red when down.
+fn change(percent: Option<f64>) -> Cell<'static> {
+    match percent {
+        Some(percent) => {
+            let color = if percent < 0.0 {
+                Color::Red
+            } else {
+                Color::Green
+            };
+            Cell::from(format!("{:+.2}%", percent)).style(Style::new().fg(color))
+        }
+        None => Cell::from("-"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cmc::errors::CmcErrors;
+
+    const KEY_INFO: &str = r#"{"plan":{"credit_limit_monthly":10000,"credit_limit_monthly_reset":"In 19 days","credit_limit_monthly_reset_timestamp":"2024-04-01T00:00:00.000Z","rate_limit_minute":30},"usage":{"current_minute":{"requests_made":0,"requests_left":30},"current_day":{"credits_used":40,"credits_left":null},"current_month":{"credits_used":9990,"credits_left":10}}}"#;
+
+    fn amount(value: &str) -> Amount {
+        serde_json::from_str(value).unwrap()
+    }
+
+    fn crypto(id: i64, symbol: &str, price: &str) -> CryptoCurrency {
+        serde_json::from_str(&format!(
+            r#"{{"id":{},"name":"{}","symbol":"{}","slug":"{}","is_active":1,"is_fiat":0,"last_updated":"2024-01-01T00:00:00.000Z","quote":{{"USD":{{"price":{},"last_updated":"2024-01-01T00:00:00.000Z"}}}}}}"#,
+            id,
+            symbol,
+            symbol,
+            symbol.to_lowercase(),
+            price
+        ))
+        .unwrap()
+    }
+
+    fn app() -> App {
+        App::new(
+            vec!["BTC".to_owned(), "ETH".to_owned()],
+            "USD".to_owned(),
+            "http://localhost/".to_owned(),
+        )
+    }
+
+    #[test]
+    fn compact_suffixes() {
+        assert_eq!(compact_f64(0.0), "0.00");
+        assert_eq!(compact_f64(999.994), "999.99");
+        assert_eq!(compact_f64(1_500.0), "1.50K");
+        assert_eq!(compact_f64(2_345_678.0), "2.35M");
+        assert_eq!(compact_f64(7.1e9), "7.10B");
+        assert_eq!(compact_f64(1.23e12), "1.23T");
+        assert_eq!(compact_f64(-4.2e6), "-4.20M");
+    }
+
+    #[test]
+    fn price_decimals() {
+        assert_eq!(price(amount("42000.123")), "42000.12");
+        assert_eq!(price(amount("1")), "1.00");
+        assert_eq!(price(amount("0.12345678")), "0.123457");
+        assert_eq!(price(amount("-2.5")), "-2.50");
+    }
+
+    #[test]
+    fn apply_updates() {
+        let mut app = app();
+        app.apply(Update::Quotes(Ok(vec![crypto(1, "BTC", "42000")])));
+        assert_eq!(app.quotes[0].symbol, "BTC");
+        assert!(app.updated.is_some());
+
+        app.apply(Update::Quotes(Ok(vec![
+            crypto(1, "BTC", "43000"),
+            crypto(1027, "ETH", "2500"),
+        ])));
+        let prices: Vec<_> = app
+            .quotes
+            .iter()
+            .map(|cc| price(cc.quote["USD"].price.unwrap()))
+            .collect();
+        assert_eq!(prices, ["43000.00", "2500.00"]);
+
+        app.apply(Update::Key(Ok(serde_json::from_str(KEY_INFO).unwrap())));
+        assert_eq!(app.key.as_ref().unwrap().plan.credit_limit_monthly, 10000);
+    }
+
+    #[test]
+    fn apply_errors() {
+        let mut app = app();
+        app.apply(Update::Quotes(Ok(vec![crypto(1, "BTC", "42000")])));
+        app.apply(Update::Global(Err(CmcErrors::ApiError("down".to_owned()))));
+        assert_eq!(app.error.as_deref(), Some("API Error: down"));
+        // A failed poll keeps what was shown, the next successful round clears the error.
+        app.apply(Update::Quotes(Err(CmcErrors::NullAnswer)));
+        assert_eq!(app.quotes.len(), 1);
+        assert!(app.error.is_some());
+        app.apply(Update::Quotes(Ok(Vec::new())));
+        assert_eq!(app.error, None);
+    }
+
+    #[test]
+    fn watchlist_order() {
+        let symbols = [Symbol::new("ETH"), Symbol::new("DOGE"), Symbol::new("BTC")];
+        let data = HashMap::from([
+            ("BTC".to_owned(), vec![crypto(1, "BTC", "42000")]),
+            ("ETH".to_owned(), vec![crypto(1027, "ETH", "2500")]),
+        ]);
+        let quotes = watchlist(&symbols, data);
+        let symbols: Vec<_> = quotes.iter().map(|cc| cc.symbol.as_str()).collect();
+        assert_eq!(symbols, ["ETH", "BTC"]);
+    }
+
+    #[test]
+    fn poll_backoff() {
+        let interval = Duration::from_secs(60);
+        assert_eq!(backoff(interval, 0), interval);
+        assert_eq!(backoff(interval, 1), interval * 2);
+        assert_eq!(backoff(interval, 3), interval * 8);
+        assert_eq!(backoff(interval, 4), interval * MAX_BACKOFF);
+        assert_eq!(backoff(interval, 40), interval * MAX_BACKOFF);
+        assert_eq!(backoff(Duration::MAX, 2), Duration::MAX);
+    }
+
+    #[test]
+    fn client_options() {
+        let cli = Cli::parse_from(["cmc-tui", "--sandbox", "--convert", "eur,btc", "btc"]);
+        let (cmc, app) = client(&cli).unwrap();
+        assert!(cmc.keys().usage()[0].key.ends_with("462c"));
+        assert_eq!(app.currency, "EUR");
+        assert_eq!(app.symbols, ["BTC"]);
+        assert_eq!(app.source, Environment::Sandbox.base_url());
+    }
+}
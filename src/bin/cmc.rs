@@ -0,0 +1,759 @@
+//! `cmc`: quick CoinMarketCap lookups from the command line.
+//!
+//! Built with the `cli` feature: `cargo install cmc --features cli`.
+//!
+//! The API key comes from `--key`, the `CMC_API_KEY` environment variable or the `api_key` entry
+//! of the config file, in that order. `--sandbox` falls back to the public sandbox key. The config file is `--config`, `$CMC_CONFIG`,
+//! `$XDG_CONFIG_HOME/cmc/config.toml` or `~/.config/cmc/config.toml`, a TOML file like:
+//!
+//! ```toml
+//! api_key = "<API KEY>"
+//! convert = "EUR"
+//! base_url = "https://sandbox-api.coinmarketcap.com/"
+//! ```
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use cmc::api::cryptocurrency::quotes_latest_v2::CryptoCurrency;
+use cmc::api::exchange::metadata::Exchange;
+use cmc::api::global_metrics::GlobalMetrics;
+use cmc::api::{Cmc, SANDBOX_API_KEY};
+use cmc::{
+    AssetRef, CmcBuilder, ContractAddress, CryptoId, Environment, ExchangeId,
+    ListingStatusExchange, Slug, Sort, SortExchange, SortFiat, Symbol,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::error::Error;
+use std::fmt::Display;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+type CliResult<T> = Result<T, Box<dyn Error>>;
+
+/// Quick lookups against the CoinMarketCap API.
+#[derive(Debug, Parser)]
+#[command(name = "cmc", version)]
+struct Cli {
+    /// API key.
+    #[arg(long, global = true, env = "CMC_API_KEY", hide_env_values = true)]
+    key: Option<String>,
+
+    /// Config file with `api_key`, `convert` and `base_url`.
+    #[arg(long, global = true, env = "CMC_CONFIG")]
+    config: Option<PathBuf>,
+
+    /// API base URL, e.g. of a mock server.
+    #[arg(long, global = true, env = "CMC_BASE_URL")]
+    base_url: Option<String>,
+
+    /// Use the sandbox environment, with the public sandbox key unless a key is given.
+    #[arg(long, global = true)]
+    sandbox: bool,
+
+    /// Currency symbol(s) to convert to. Example: "EUR" or "USD,BTC"
+    #[arg(long, global = true, env = "CMC_CONVERT")]
+    convert: Option<String>,
+
+    /// Output format.
+    #[arg(long, short, global = true, value_enum, default_value_t = Format::Text)]
+    output: Format,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum Format {
+    Text,
+    Json,
+    Csv,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Latest price of one cryptocurrency.
+    Price(Asset),
+    /// Latest quotes of one or more cryptocurrencies.
+    Quotes {
+        /// Ids or symbols, or slugs with `--slug`. Example: "BTC ETH" or "1 1027"
+        #[arg(required = true)]
+        assets: Vec<String>,
+        /// Look the cryptocurrencies up by slug.
+        #[arg(long)]
+        slug: bool,
+    },
+    /// Converts an amount of one cryptocurrency, optionally at a past point in time.
+    Convert {
+        amount: f64,
+        #[command(flatten)]
+        asset: Asset,
+        /// ISO 8601 date or Unix timestamp. Example: "2024-01-01"
+        #[arg(long)]
+        time: Option<String>,
+    },
+    /// Cryptocurrency id map.
+    Map {
+        #[command(flatten)]
+        page: Page,
+        #[arg(long, value_enum, default_value_t = MapSort::CmcRank)]
+        sort: MapSort,
+    },
+    /// Fiat id map.
+    FiatMap {
+        #[command(flatten)]
+        page: Page,
+        #[arg(long, value_enum, default_value_t = FiatSort::Id)]
+        sort: FiatSort,
+    },
+    /// Exchange id map.
+    Exchanges {
+        #[command(flatten)]
+        page: Page,
+        #[arg(long, value_enum, default_value_t = ExchangeSort::Id)]
+        sort: ExchangeSort,
+        #[arg(long, value_enum, default_value_t = Status::Active)]
+        status: Status,
+        /// Only exchanges listing this cryptocurrency id.
+        #[arg(long)]
+        crypto_id: Option<CryptoId>,
+    },
+    /// Metadata of one or more exchanges.
+    ExchangeInfo {
+        /// Ids or slugs. Example: "binance" or "270"
+        #[arg(required = true)]
+        exchanges: Vec<String>,
+    },
+    /// Coin categories.
+    Categories {
+        #[command(flatten)]
+        page: Page,
+    },
+    /// One coin category and its coins.
+    Category {
+        /// Category id. Example: "605e2ce9d41eae1066535f7c"
+        id: String,
+        #[command(flatten)]
+        page: Page,
+    },
+    /// Metadata of one cryptocurrency.
+    Metadata(Asset),
+    /// Global market metrics.
+    Global,
+    /// Plan and usage of the API key.
+    KeyInfo,
+}
+
+/// One cryptocurrency: an id ("1027"), a contract address ("0x...") or a symbol ("ETH").
+#[derive(Debug, Args)]
+struct Asset {
+    asset: String,
+    /// Look the cryptocurrency up by slug. Example: "ethereum"
+    #[arg(long)]
+    slug: bool,
+}
+
+impl Asset {
+    fn to_ref(&self) -> AssetRef {
+        if self.slug {
+            AssetRef::Slug(Slug::new(&self.asset))
+        } else if let Ok(id) = self.asset.parse::<CryptoId>() {
+            AssetRef::Id(id)
+        } else if self.asset.starts_with("0x") {
+            AssetRef::Address(ContractAddress::new(&self.asset))
+        } else {
+            AssetRef::Symbol(Symbol::new(&self.asset))
+        }
+    }
+}
+
+#[derive(Debug, Args)]
+struct Page {
+    /// Offset the start (1-based index).
+    #[arg(long, default_value_t = 1)]
+    start: usize,
+    /// Number of results.
+    #[arg(long, default_value_t = 100)]
+    limit: usize,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum MapSort {
+    Id,
+    CmcRank,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum FiatSort {
+    Id,
+    Name,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum ExchangeSort {
+    Id,
+    Volume24h,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum Status {
+    Active,
+    Inactive,
+    Untracked,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match run(cli) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(cli: Cli) -> CliResult<()> {
+    let (cmc, currency) = client(&cli)?;
+    let out = cli.output;
+
+    match cli.command {
+        Command::Price(asset) => {
+            let asset = asset.to_ref();
+            let price = cmc.price(asset.clone())?;
+            let record = serde_json::json!({
+                "asset": asset.to_string(),
+                "price": price,
+                "currency": currency,
+            });
+            print(out, &record, vec![record.clone()], || {
+                format!("{}: {} {}", asset, price, currency)
+            })
+        }
+        Command::Quotes { assets, slug } => {
+            let query = assets.join(",");
+            let quotes: Vec<CryptoCurrency> = if slug {
+                cmc.quotes()
+                    .by_slug(query)
+                    .send()?
+                    .data
+                    .into_values()
+                    .collect()
+            } else if assets.iter().all(|asset| asset.parse::<CryptoId>().is_ok()) {
+                cmc.quotes()
+                    .by_id(query)
+                    .send()?
+                    .data
+                    .into_values()
+                    .collect()
+            } else {
+                let data = cmc.quotes().by_symbol(query).send()?.data;
+                data.into_values().flatten().collect()
+            };
+            let mut quotes = quotes;
+            quotes.sort_by_key(|cc| (cc.cmc_rank.unwrap_or(u64::MAX), cc.id));
+            print(out, &quotes, records(&quotes)?, || quotes_text(&quotes))
+        }
+        Command::Convert {
+            amount,
+            asset,
+            time,
+        } => {
+            let mut call = cmc.convert(amount, asset.to_ref());
+            if let Some(time) = &time {
+                call = call.time(time);
+            }
+            let conversion = call.send()?;
+            let row = serde_json::to_value(&conversion)?;
+            print(out, &conversion, vec![row], || {
+                let mut quote: Vec<_> = conversion.quote.iter().collect();
+                quote.sort_by_key(|(currency, _)| *currency);
+                let mut text = format!(
+                    "{} {} ({})",
+                    conversion.amount, conversion.symbol, conversion.name
+                );
+                for (currency, price) in quote {
+                    text.push_str(&format!("\n= {} {}", opt(&price.price), currency));
+                }
+                if let Some(last_updated) = &conversion.last_updated {
+                    text.push_str(&format!("\nas of {}", last_updated));
+                }
+                text
+            })
+        }
+        Command::Map { page, sort } => {
+            let sort = match sort {
+                MapSort::Id => Sort::Id,
+                MapSort::CmcRank => Sort::CmcRank,
+            };
+            let map = cmc.id_map(page.start, page.limit, sort)?;
+            print(out, &map, records(&map.data)?, || map.to_string())
+        }
+        Command::FiatMap { page, sort } => {
+            let sort = match sort {
+                FiatSort::Id => SortFiat::Id,
+                FiatSort::Name => SortFiat::Name,
+            };
+            let map = cmc.fiat_id_map(page.start, page.limit, sort)?;
+            print(out, &map, records(&map.data)?, || map.to_string())
+        }
+        Command::Exchanges {
+            page,
+            sort,
+            status,
+            crypto_id,
+        } => {
+            let sort = match sort {
+                ExchangeSort::Id => SortExchange::Id,
+                ExchangeSort::Volume24h => SortExchange::Volume24h,
+            };
+            let status = match status {
+                Status::Active => ListingStatusExchange::Active,
+                Status::Inactive => ListingStatusExchange::Inactive,
+                Status::Untracked => ListingStatusExchange::Untracked,
+            };
+            let map = cmc.exchange_id_map(status, page.start, page.limit, sort, crypto_id)?;
+            print(out, &map, records(&map.data)?, || map.to_string())
+        }
+        Command::ExchangeInfo { exchanges } => {
+            let ids: Result<Vec<ExchangeId>, _> = exchanges.iter().map(|e| e.parse()).collect();
+            let call = match ids {
+                Ok(ids) => cmc.exchange_info().by_id(ids),
+                Err(_) => cmc.exchange_info().by_slug(exchanges.join(",")),
+            };
+            let mut data: Vec<Exchange> = call.send()?.data.into_values().collect();
+            data.sort_by_key(|exchange| exchange.id);
+            print(out, &data, records(&data)?, || exchanges_text(&data))
+        }
+        Command::Categories { page } => {
+            let categories = cmc
+                .category_list()
+                .start(page.start)
+                .limit(page.limit)
+                .send()?;
+            print(out, &categories, records(&categories.data)?, || {
+                categories.to_string()
+            })
+        }
+        Command::Category { id, page } => {
+            let category = cmc.category(id, page.start, page.limit)?;
+            let mut row = serde_json::to_value(&category)?;
+            if let Value::Object(map) = &mut row {
+                map.remove("coins");
+            }
+            print(out, &category, vec![row], || category.to_string())
+        }
+        Command::Metadata(asset) => {
+            let info = cmc.info();
+            let metadata = match asset.to_ref() {
+                AssetRef::Id(id) => info.by_id(id).send()?,
+                AssetRef::Slug(slug) => info.by_slug(slug).send()?,
+                AssetRef::Symbol(symbol) => info.by_symbol(symbol).send()?,
+                AssetRef::Address(address) => info.by_address(address).send()?,
+            };
+            let row = serde_json::to_value(&metadata)?;
+            print(out, &metadata, vec![row], || {
+                let website = metadata.urls.website.first().cloned().unwrap_or_default();
+                format!(
+                    "{} ({})\nId: {}\nSlug: {}\nCategory: {}\nWebsite: {}\n\n{}",
+                    metadata.name,
+                    metadata.symbol,
+                    metadata.id,
+                    metadata.slug,
+                    metadata.category,
+                    website,
+                    metadata.description
+                )
+            })
+        }
+        Command::Global => {
+            let metrics = cmc.global_metrics()?;
+            let row = serde_json::to_value(&metrics)?;
+            print(out, &metrics, vec![row], || global_text(&metrics))
+        }
+        Command::KeyInfo => {
+            let info = cmc.key_info()?;
+            let row = serde_json::to_value(&info)?;
+            print(out, &info, vec![row], || info.to_string())
+        }
+    }
+}
+
+/// Client for the options, the environment and the config file, and its first convert currency.
+fn client(cli: &Cli) -> CliResult<(Cmc, String)> {
+    let file = config(cli.config.clone())?;
+    let key = cli
+        .key
+        .clone()
+        .or(file.api_key)
+        .or_else(|| cli.sandbox.then(|| SANDBOX_API_KEY.to_owned()))
+        .ok_or("no API key: pass --key, set CMC_API_KEY or add api_key to the config file")?;
+
+    let mut builder = CmcBuilder::new(key);
+    let convert = cli.convert.clone().or(file.convert);
+    if let Some(convert) = &convert {
+        builder = builder.convert(convert);
+    }
+    let currency = convert.as_deref().unwrap_or("USD").split(',').next();
+    let currency = currency.unwrap_or_default().trim().to_uppercase();
+    if cli.sandbox {
+        builder = builder.environment(Environment::Sandbox);
+    } else if let Some(base_url) = cli.base_url.clone().or(file.base_url) {
+        builder = builder.base_url(base_url);
+    }
+    Ok((builder.build()?, currency))
+}
+
+/// Entries of the config file.
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+struct Config {
+    api_key: Option<String>,
+    convert: Option<String>,
+    base_url: Option<String>,
+}
+
+/// Reads the config file. A missing default config file is empty, a missing `--config` is an
+/// error.
+fn config(path: Option<PathBuf>) -> CliResult<Config> {
+    let (path, required) = match path {
+        Some(path) => (path, true),
+        None => {
+            let dir = std::env::var_os("XDG_CONFIG_HOME")
+                .map(PathBuf::from)
+                .or_else(|| {
+                    std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config"))
+                });
+            match dir {
+                Some(dir) => (dir.join("cmc").join("config.toml"), false),
+                None => return Ok(Config::default()),
+            }
+        }
+    };
+
+    let text = match std::fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(err) if !required && err.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(Config::default())
+        }
+        Err(err) => return Err(format!("{}: {}", path.display(), err).into()),
+    };
+    toml::from_str(&text).map_err(|err| format!("{}: {}", path.display(), err).into())
+}
+
+/// Prints `data` as text, pretty JSON, or `rows` as CSV.
+fn print<T: Serialize, F: FnOnce() -> String>(
+    format: Format,
+    data: &T,
+    rows: Vec<Value>,
+    text: F,
+) -> CliResult<()> {
+    match format {
+        Format::Text => println!("{}", text()),
+        Format::Json => println!("{}", serde_json::to_string_pretty(data)?),
+        Format::Csv => print!("{}", csv(rows)),
+    }
+    Ok(())
+}
+
+/// One CSV row per item.
+fn records<T: Serialize>(items: &[T]) -> CliResult<Vec<Value>> {
+    Ok(items
+        .iter()
+        .map(serde_json::to_value)
+        .collect::<Result<_, _>>()?)
+}
+
+/// CSV with a header of all columns. Nested objects become dotted columns ("quote.USD.price"),
+/// lists are written as JSON.
+fn csv(rows: Vec<Value>) -> String {
+    let rows: Vec<Map<String, Value>> = rows
+        .into_iter()
+        .map(|row| {
+            let mut flat = Map::new();
+            flatten("", row, &mut flat);
+            flat
+        })
+        .collect();
+
+    let mut columns: Vec<&String> = Vec::new();
+    for row in &rows {
+        for column in row.keys() {
+            if !columns.contains(&column) {
+                columns.push(column);
+            }
+        }
+    }
+
+    let mut out = String::new();
+    let header: Vec<_> = columns.iter().map(|column| field(column)).collect();
+    out.push_str(&header.join(","));
+    out.push('\n');
+    for row in &rows {
+        let fields: Vec<_> = columns
+            .iter()
+            .map(|column| match row.get(*column) {
+                None | Some(Value::Null) => String::new(),
+                Some(Value::String(value)) => field(value),
+                Some(value) => field(&value.to_string()),
+            })
+            .collect();
+        out.push_str(&fields.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+fn flatten(prefix: &str, value: Value, flat: &mut Map<String, Value>) {
+    match value {
+        Value::Object(map) => {
+            for (name, value) in map {
+                let name = match prefix {
+                    "" => name,
+                    _ => format!("{}.{}", prefix, name),
+                };
+                flatten(&name, value, flat);
+            }
+        }
+        value if prefix.is_empty() => {
+            flat.insert("value".to_owned(), value);
+        }
+        value => {
+            flat.insert(prefix.to_owned(), value);
+        }
+    }
+}
+
+/// Quotes a CSV field if needed.
+fn field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_owned()
+    }
+}
+
+fn opt<T: Display>(value: &Option<T>) -> String {
+    value
+        .as_ref()
+        .map(T::to_string)
+        .unwrap_or_else(|| "-".to_owned())
+}
+
+fn percent(value: Option<f64>) -> String {
+    value
+        .map(|value| format!("{:+.2}%", value))
+        .unwrap_or_else(|| "-".to_owned())
+}
+
+fn quotes_text(quotes: &[CryptoCurrency]) -> String {
+    let mut lines = Vec::new();
+    for cc in quotes {
+        let mut quote: Vec<_> = cc.quote.iter().collect();
+        quote.sort_by_key(|(currency, _)| *currency);
+        for (currency, q) in quote {
+            lines.push(format!(
+                "#{:<5} {:<8} {:<20} {} {} (1h {}, 24h {}, 7d {})",
+                opt(&cc.cmc_rank),
+                cc.symbol,
+                cc.name,
+                opt(&q.price),
+                currency,
+                percent(q.percent_change_1h),
+                percent(q.percent_change_24h),
+                percent(q.percent_change_7d)
+            ));
+        }
+    }
+    lines.join("\n")
+}
+
+fn exchanges_text(exchanges: &[Exchange]) -> String {
+    exchanges
+        .iter()
+        .map(|exchange| {
+            format!(
+                "{} ({}, id {})\nWebsite: {}\nMaker fee: {}\nTaker fee: {}\nSpot volume USD: {}",
+                exchange.name,
+                exchange.slug,
+                exchange.id,
+                exchange.urls.website.first().cloned().unwrap_or_default(),
+                exchange.maker_fee,
+                exchange.taker_fee,
+                opt(&exchange.spot_volume_usd)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+fn global_text(metrics: &GlobalMetrics) -> String {
+    let mut text = format!(
+        "Active cryptocurrencies: {}\nActive exchanges: {}\nBTC dominance: {:.2}%\n\
+        ETH dominance: {:.2}%\nDeFi market cap: {}\nStablecoin market cap: {}",
+        metrics.active_cryptocurrencies,
+        metrics.active_exchanges,
+        metrics.btc_dominance,
+        metrics.eth_dominance,
+        metrics.defi_market_cap,
+        metrics.stablecoin_market_cap
+    );
+    let mut quote: Vec<_> = metrics.quote.iter().collect();
+    quote.sort_by_key(|(currency, _)| *currency);
+    for (currency, q) in quote {
+        text.push_str(&format!(
+            "\nTotal market cap: {} {}\nTotal volume 24h: {} {}",
+            q.total_market_cap, currency, q.total_volume_24h, currency
+        ));
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::mpsc::{self, Receiver};
+
+    /// BTC quoted in USD and BTC.
+    const QUOTES: &str = r#"{"status":{"timestamp":"2024-01-01T00:00:00.000Z","error_code":0,"error_message":null,"elapsed":1,"credit_count":1,"notice":null},"data":{"BTC":[{"id":1,"name":"Bitcoin","symbol":"BTC","slug":"bitcoin","is_active":1,"is_fiat":0,"last_updated":"2024-01-01T00:00:00.000Z","quote":{"USD":{"price":42000,"last_updated":"2024-01-01T00:00:00.000Z"},"BTC":{"price":1,"last_updated":"2024-01-01T00:00:00.000Z"}}}]}}"#;
+
+    /// Serves `body` to every request and reports the request line of each one.
+    fn serve(body: &'static str) -> (String, Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut buf = [0; 4096];
+                let n = stream.read(&mut buf).unwrap();
+                let request = String::from_utf8_lossy(&buf[..n]);
+                let _ = tx.send(request.lines().next().unwrap_or_default().to_owned());
+                let _ = write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+            }
+        });
+        (url, rx)
+    }
+
+    /// Writes `text` to a config file unique to the test.
+    fn config_file(name: &str, text: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("cmc-{}-{}.toml", name, std::process::id()));
+        std::fs::write(&path, text).unwrap();
+        path
+    }
+
+    #[test]
+    fn field_quotes_when_needed() {
+        assert_eq!(field("BTC"), "BTC");
+        assert_eq!(field("1,2"), "\"1,2\"");
+        assert_eq!(field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(field("a\nb"), "\"a\nb\"");
+    }
+
+    #[test]
+    fn flatten_nested() {
+        let mut flat = Map::new();
+        flatten(
+            "",
+            json!({"id": 1, "quote": {"USD": {"price": 2.5}}, "tags": ["a"]}),
+            &mut flat,
+        );
+        assert_eq!(flat["id"], json!(1));
+        assert_eq!(flat["quote.USD.price"], json!(2.5));
+        assert_eq!(flat["tags"], json!(["a"]));
+
+        let mut flat = Map::new();
+        flatten("", json!(3), &mut flat);
+        assert_eq!(flat["value"], json!(3));
+    }
+
+    #[test]
+    fn csv_columns_union() {
+        let rows = vec![
+            json!({"symbol": "BTC", "quote": {"USD": {"price": 1}}}),
+            json!({"symbol": "A,B", "name": null, "rank": 2}),
+        ];
+        assert_eq!(
+            csv(rows),
+            "quote.USD.price,symbol,name,rank\n1,BTC,,\n,\"A,B\",,2\n"
+        );
+    }
+
+    #[test]
+    fn config_toml() {
+        let path = config_file(
+            "toml",
+            "# cmc\napi_key = \"key = 1\" # comment\nconvert = 'EUR'\n",
+        );
+        let config = config(Some(path.clone())).unwrap();
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(
+            config,
+            Config {
+                api_key: Some("key = 1".to_owned()),
+                convert: Some("EUR".to_owned()),
+                base_url: None,
+            }
+        );
+    }
+
+    #[test]
+    fn config_errors() {
+        let path = config_file("unknown", "api_kye = \"key\"\n");
+        let result = config(Some(path.clone()));
+        std::fs::remove_file(&path).unwrap();
+        assert!(result.unwrap_err().to_string().contains("api_kye"));
+
+        let missing = std::env::temp_dir().join("cmc-missing.toml");
+        assert!(config(Some(missing)).is_err());
+    }
+
+    #[test]
+    fn price_multi_currency_convert() {
+        let (url, request) = serve(QUOTES);
+        let path = config_file("multi", "");
+        let cli = Cli::parse_from([
+            "cmc",
+            "--key",
+            "key",
+            "--config",
+            path.to_str().unwrap(),
+            "--base-url",
+            &url,
+            "--convert",
+            "usd,btc",
+            "price",
+            "BTC",
+        ]);
+        let (cmc, currency) = client(&cli).unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(currency, "USD");
+        assert_eq!(cmc.price(Symbol::new("BTC")).unwrap().to_string(), "42000");
+        assert!(request.recv().unwrap().contains("convert=USD%2CBTC"));
+    }
+
+    #[test]
+    fn sandbox_key() {
+        let path = config_file("sandbox", "");
+        let cli = Cli::parse_from([
+            "cmc",
+            "--sandbox",
+            "--config",
+            path.to_str().unwrap(),
+            "global",
+        ]);
+        let result = client(&cli);
+        std::fs::remove_file(path).unwrap();
+
+        let (cmc, _) = result.unwrap();
+        assert!(cmc.keys().usage()[0].key.ends_with("462c"));
+    }
+}
@@ -0,0 +1,172 @@
+//! File-backed cache for slow-changing reference data.
+//!
+//! Responses of `id_map()`, `fiat_id_map()`, `exchange_id_map()` and `metadata_map()`
+//! are written to disk, so a process can warm-start without spending credits.
+//! Entries older than [`max_age()`][RefCache::max_age] are still served, while a fresh copy
+//! is downloaded in the background.
+//!
+#![cfg_attr(feature = "blocking", doc = " ```rust")]
+#![cfg_attr(not(feature = "blocking"), doc = " ```rust,ignore")]
+//! use cmc::{CmcBuilder, RefCache, Sort};
+//! use std::time::Duration;
+//!
+//! let cache = RefCache::new("/tmp/cmc-cache").max_age(Duration::from_secs(6 * 60 * 60));
+//! let cmc = CmcBuilder::new("<API KEY>").cache(cache).build().unwrap();
+//!
+//! match cmc.id_map(1, 50, Sort::CmcRank) {
+//!     Ok(map) => println!("{}", map),
+//!     Err(err) => println!("{}", err),
+//! }
+//! ```
+
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
+
+#[cfg(any(feature = "blocking", feature = "async"))]
+use serde::{Deserialize, Serialize};
+#[cfg(any(feature = "blocking", feature = "async"))]
+use serde_json::Value;
+#[cfg(any(feature = "blocking", feature = "async"))]
+use std::collections::HashSet;
+#[cfg(any(feature = "blocking", feature = "async"))]
+use std::sync::{Arc, Mutex};
+#[cfg(any(feature = "blocking", feature = "async"))]
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const DEFAULT_MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// On-disk cache for reference data endpoints.
+#[derive(Clone, Debug)]
+pub struct RefCache {
+    dir: PathBuf,
+    max_age: Duration,
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    refreshing: Arc<Mutex<HashSet<String>>>,
+}
+
+#[cfg(any(feature = "blocking", feature = "async"))]
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    fetched_at: u64,
+    body: Value,
+}
+
+/// Result of a cache lookup.
+#[cfg(any(feature = "blocking", feature = "async"))]
+pub(crate) enum Lookup {
+    Fresh(Value),
+    Stale(Value),
+    Miss,
+}
+
+impl RefCache {
+    /// Creates a cache that stores its entries in `dir`. The directory is created on first write.
+    ///
+    /// Entries are considered fresh for 24 hours by default.
+    pub fn new<P: Into<PathBuf>>(dir: P) -> Self {
+        Self {
+            dir: dir.into(),
+            max_age: DEFAULT_MAX_AGE,
+            #[cfg(any(feature = "blocking", feature = "async"))]
+            refreshing: Arc::new(Mutex::new(HashSet::new())),
+        }
+    }
+
+    /// Age after which an entry is refreshed in the background.
+    pub fn max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    /// Removes all cached entries.
+    pub fn clear(&self) -> io::Result<()> {
+        match fs::read_dir(&self.dir) {
+            Ok(entries) => {
+                for entry in entries {
+                    let path = entry?.path();
+                    if path.extension().is_some_and(|ext| ext == "json") {
+                        fs::remove_file(path)?;
+                    }
+                }
+                Ok(())
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+            Err(err) => Err(err),
+        }
+    }
+
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    pub(crate) fn lookup(&self, key: &str) -> Lookup {
+        let entry = match fs::read(self.path(key))
+            .ok()
+            .and_then(|raw| serde_json::from_slice::<Entry>(&raw).ok())
+        {
+            Some(entry) => entry,
+            None => return Lookup::Miss,
+        };
+
+        let age = now().saturating_sub(entry.fetched_at);
+        if age < self.max_age.as_secs() {
+            Lookup::Fresh(entry.body)
+        } else {
+            Lookup::Stale(entry.body)
+        }
+    }
+
+    /// Writes an entry through a temporary file, so readers never observe a partial write.
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    pub(crate) fn store(&self, key: &str, body: &Value) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let entry = Entry {
+            fetched_at: now(),
+            body: body.clone(),
+        };
+        let path = self.path(key);
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, serde_json::to_vec(&entry)?)?;
+        fs::rename(tmp, path)
+    }
+
+    /// Marks `key` as being refreshed. Returns `false` if a refresh is already running.
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    pub(crate) fn begin_refresh(&self, key: &str) -> bool {
+        self.refreshing.lock().unwrap().insert(key.to_owned())
+    }
+
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    pub(crate) fn end_refresh(&self, key: &str) {
+        self.refreshing.lock().unwrap().remove(key);
+    }
+
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    fn path(&self, key: &str) -> PathBuf {
+        let url = key.split('?').next().unwrap_or_default();
+        let mut segments: Vec<&str> = url.rsplit('/').take(2).collect();
+        segments.reverse();
+        let prefix: String = segments
+            .join("_")
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        self.dir
+            .join(format!("{}-{:016x}.json", prefix, fnv1a(key)))
+    }
+}
+
+#[cfg(any(feature = "blocking", feature = "async"))]
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// Stable 64-bit FNV-1a hash, used to derive file names from request urls.
+#[cfg(any(feature = "blocking", feature = "async"))]
+fn fnv1a(s: &str) -> u64 {
+    s.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
@@ -0,0 +1,1448 @@
+//! Client core shared by [`api`](crate::api) and [`async_api`](crate::async_api).
+//!
+//! The builder, every endpoint method and the request pipeline (cache, ledger, error mapping)
+//! are written once in `client!`. Each client module expands it with its own transport:
+//! `client!(;)` for blocking calls and `client!(async; .await)` for futures. The expanding
+//! module provides the imports, `Client` (its default `reqwest` client), `Transport` (the
+//! matching transport trait), a `fetch` method that sends a request via `fetch_pooled` and a
+//! `refresh` method that updates a stale cache entry in the background. An endpoint added
+//! here lands in both clients.
+
+macro_rules! client {
+    ($($async:ident)?; $($await:tt)*) => {
+    /// A `CmcBuilder` can be used to create a `Cmc` with custom configuration.
+    pub struct CmcBuilder {
+        keys: KeyPool,
+        transport: Option<Arc<dyn Transport>>,
+        connect_timeout: Option<Duration>,
+        proxy: Option<Proxy>,
+        convert: Option<String>,
+        config: Config,
+    }
+
+    impl CmcBuilder {
+        /// Creates a builder for one API key, or for several as a [`KeyPool`] or a list.
+        pub fn new<T: Into<KeyPool>>(api_key: T) -> Self {
+            Self {
+                keys: api_key.into(),
+                transport: None,
+                connect_timeout: None,
+                proxy: None,
+                convert: None,
+                config: Config::default(),
+            }
+        }
+
+        /// # Set pass:
+        ///
+        /// - **Id**: Cryptocurrency coinmarketcap id. Example: "1027"
+        ///
+        /// - **Slug**: Alternatively pass one cryptocurrency slug. Example: "ethereum"
+        ///
+        /// - **Symbol**: Alternatively pass one cryptocurrency symbol. Example: "BTC"
+        ///
+        /// **NOTE**: `CoinMarketCap recommend utilizing CMC ID instead of cryptocurrency symbols to securely identify cryptocurrencies with other endpoints and in your own application logic`
+        /// (Can be obtained using the method [id_map()][id]).
+        /// # Example:
+        #[cfg_attr(feature = "blocking", doc = " ```rust")]
+        #[cfg_attr(not(feature = "blocking"), doc = " ```rust,ignore")]
+        /// use cmc::{CmcBuilder, Pass};
+        ///
+        /// let cmc = CmcBuilder::new("<API KEY>").pass(Pass::Id).build().unwrap();
+        ///
+        /// match cmc.price("1027") { // 1027 is Ethereum id.
+        ///     Ok(price) => println!("Price: {}", price),
+        ///     Err(err) => println!("Error: {}", err),
+        /// }
+        /// ```
+        /// [id]: ./struct.Cmc.html#method.id_map
+        pub fn pass(mut self, pass: Pass) -> CmcBuilder {
+            self.config.pass = pass;
+            self
+        }
+
+        /// Optionally calculate market quotes in up to 120 currencies by passing cryptocurrency or fiat.
+        /// # Example:
+        #[cfg_attr(feature = "blocking", doc = " ```rust")]
+        #[cfg_attr(not(feature = "blocking"), doc = " ```rust,ignore")]
+        /// use cmc::CmcBuilder;
+        ///
+        /// let cmc = CmcBuilder::new("<API KEY>").convert("EUR").build().unwrap();
+        ///
+        /// match cmc.price("ETH") {
+        ///     Ok(price) => println!("Price: {}", price), // In Euro
+        ///     Err(err) => println!("Error: {}", err),
+        /// }
+        /// ```
+        pub fn convert<T: Into<String>>(mut self, currency: T) -> CmcBuilder {
+            self.convert = Some(currency.into().to_uppercase());
+            self
+        }
+
+        /// Optionally calculate market quotes in up to 120 currencies by passing cryptocurrency or fiat.
+        /// # Example:
+        #[cfg_attr(feature = "blocking", doc = " ```rust")]
+        #[cfg_attr(not(feature = "blocking"), doc = " ```rust,ignore")]
+        /// use cmc::CmcBuilder;
+        ///
+        /// let cmc = CmcBuilder::new("<API KEY>").convert_id("1027").build().unwrap();
+        ///
+        /// match cmc.price("BTC") {
+        ///     Ok(price) => println!("Price: {}", price), // In ETH
+        ///     Err(err) => println!("Error: {}", err),
+        /// }
+        /// ```
+        pub fn convert_id<T: Identifiers<CurrencyId>>(mut self, currency_id: T) -> CmcBuilder {
+            self.config.currency_id = Some(currency_id.to_query());
+            self
+        }
+
+        /// Optionally set the coinmarketcap base url.
+        pub fn base_url<T: Into<String>>(mut self, base_url: T) -> CmcBuilder {
+            self.config.base_url = base_url.into();
+            self
+        }
+
+        /// Optionally select the API host, `Environment::Production` by default.
+        /// # Example:
+        #[cfg_attr(feature = "blocking", doc = " ```rust")]
+        #[cfg_attr(not(feature = "blocking"), doc = " ```rust,ignore")]
+        /// use cmc::api::SANDBOX_API_KEY;
+        /// use cmc::{CmcBuilder, Environment};
+        ///
+        /// let cmc = CmcBuilder::new(SANDBOX_API_KEY)
+        ///     .environment(Environment::Sandbox)
+        ///     .build()
+        ///     .unwrap();
+        ///
+        /// match cmc.price("BTC") {
+        ///     Ok(price) => println!("Price: {}", price), // Mock data
+        ///     Err(err) => println!("Error: {}", err),
+        /// }
+        /// ```
+        pub fn environment(self, environment: Environment) -> CmcBuilder {
+            self.base_url(environment.base_url())
+        }
+
+        /// Creates a builder from environment variables:
+        ///
+        /// - `CMC_API_KEY`: API key. `build()` fails if it is missing.
+        /// - `CMC_BASE_URL`: Optional base url, for example of the sandbox.
+        /// - `CMC_CONVERT`: Optional default currency symbol(s). Example: "EUR"
+        /// - `CMC_CONVERT_ID`: Optional default currency id(s). Example: "2790"
+        ///
+        /// Further builder methods override these settings.
+        /// # Example:
+        #[cfg_attr(feature = "blocking", doc = " ```rust")]
+        #[cfg_attr(not(feature = "blocking"), doc = " ```rust,ignore")]
+        /// use cmc::CmcBuilder;
+        ///
+        /// match CmcBuilder::from_env().build() {
+        ///     Ok(cmc) => println!("{:?}", cmc), // The API key is not printed
+        ///     Err(err) => println!("Error: {}", err),
+        /// }
+        /// ```
+        pub fn from_env() -> Self {
+            let var = |name: &str| std::env::var(name).ok().filter(|value| !value.is_empty());
+
+            let mut builder = CmcBuilder::new(var("CMC_API_KEY").unwrap_or_default());
+            if let Some(base_url) = var("CMC_BASE_URL") {
+                builder = builder.base_url(base_url);
+            }
+            if let Some(currency) = var("CMC_CONVERT") {
+                builder = builder.convert(currency);
+            }
+            if let Some(currency_id) = var("CMC_CONVERT_ID") {
+                builder.config.currency_id = Some(currency_id);
+            }
+            builder
+        }
+
+        /// Optionally keep reference data (`id_map()`, `fiat_id_map()`, `exchange_id_map()`,
+        /// `metadata_map()`) in an on-disk cache. The async client refreshes stale entries on a
+        /// task spawned on the current Tokio runtime. Without one, the call refreshes the entry
+        /// in place before serving the stale copy.
+        /// # Example:
+        #[cfg_attr(feature = "blocking", doc = " ```rust")]
+        #[cfg_attr(not(feature = "blocking"), doc = " ```rust,ignore")]
+        /// use cmc::{CmcBuilder, RefCache, SortFiat};
+        /// use std::time::Duration;
+        ///
+        /// let cache = RefCache::new("/tmp/cmc-cache").max_age(Duration::from_secs(3600));
+        /// let cmc = CmcBuilder::new("<API KEY>").cache(cache).build().unwrap();
+        ///
+        /// match cmc.fiat_id_map(1, 100, SortFiat::Name) {
+        ///     Ok(map) => println!("{}", map), // Served from disk for the next hour
+        ///     Err(err) => println!("{}", err),
+        /// }
+        /// ```
+        pub fn cache(mut self, cache: RefCache) -> CmcBuilder {
+            self.config.cache = Some(cache);
+            self
+        }
+
+        /// Optionally share a [`Ledger`] that counts the credits spent and enforces a budget.
+        /// Every client has its own ledger otherwise, see [ledger()][ledger].
+        /// # Example:
+        #[cfg_attr(feature = "blocking", doc = " ```rust")]
+        #[cfg_attr(not(feature = "blocking"), doc = " ```rust,ignore")]
+        /// use cmc::{CmcBuilder, Ledger};
+        ///
+        /// let ledger = Ledger::new().daily_budget(100);
+        /// let cmc = CmcBuilder::new("<API KEY>").ledger(ledger.clone()).build().unwrap();
+        ///
+        /// match cmc.price("BTC") {
+        ///     Ok(price) => println!("Price: {}", price),
+        ///     Err(err) => println!("Error: {}", err), // `BudgetExceeded` after 100 credits today
+        /// }
+        /// println!("Credits spent: {}", ledger.total());
+        /// ```
+        /// [ledger]: ./struct.Cmc.html#method.ledger
+        pub fn ledger(mut self, ledger: Ledger) -> CmcBuilder {
+            self.config.ledger = ledger;
+            self
+        }
+
+        /// Optionally limit the time to establish a connection.
+        ///
+        /// Applies to the default `reqwest` client, not to a [client()][client] or
+        /// [transport()][transport] set explicitly.
+        ///
+        /// [client]: ./struct.CmcBuilder.html#method.client
+        /// [transport]: ./struct.CmcBuilder.html#method.transport
+        pub fn connect_timeout(mut self, timeout: Duration) -> CmcBuilder {
+            self.connect_timeout = Some(timeout);
+            self
+        }
+
+        /// Optionally limit the time of a whole request, from connecting until the response is
+        /// read. Calls can override it with `timeout()`. A call that runs out of time fails with
+        /// `CmcErrors::Timeout`.
+        /// # Example:
+        #[cfg_attr(feature = "blocking", doc = " ```rust")]
+        #[cfg_attr(not(feature = "blocking"), doc = " ```rust,ignore")]
+        /// use cmc::{CmcBuilder, CryptoId};
+        /// use std::time::Duration;
+        ///
+        /// let cmc = CmcBuilder::new("<API KEY>")
+        ///     .connect_timeout(Duration::from_secs(5))
+        ///     .timeout(Duration::from_secs(10))
+        ///     .build().unwrap();
+        ///
+        /// // Historical conversions may take longer.
+        /// let call = cmc.conversion(1).by_id(CryptoId(1)).time("2021-01-01");
+        /// match call.timeout(Duration::from_secs(30)).send() {
+        ///     Ok(price) => println!("Price: {}", price),
+        ///     Err(err) => println!("Error: {}", err),
+        /// }
+        /// ```
+        pub fn timeout(mut self, timeout: Duration) -> CmcBuilder {
+            self.config.timeout = Some(timeout);
+            self
+        }
+
+        /// Optionally send requests through a proxy. Example: `Proxy::https("http://proxy:3128")`
+        ///
+        /// Applies to the default `reqwest` client, not to a [client()][client] or
+        /// [transport()][transport] set explicitly.
+        ///
+        /// [client]: ./struct.CmcBuilder.html#method.client
+        /// [transport]: ./struct.CmcBuilder.html#method.transport
+        pub fn proxy(mut self, proxy: Proxy) -> CmcBuilder {
+            self.proxy = Some(proxy);
+            self
+        }
+
+        /// Optionally set the `User-Agent` header of all requests.
+        pub fn user_agent<T: Into<String>>(self, user_agent: T) -> CmcBuilder {
+            self.header("User-Agent", user_agent)
+        }
+
+        /// Optionally add a header to all requests.
+        /// # Example:
+        #[cfg_attr(feature = "blocking", doc = " ```rust")]
+        #[cfg_attr(not(feature = "blocking"), doc = " ```rust,ignore")]
+        /// use cmc::CmcBuilder;
+        ///
+        /// let cmc = CmcBuilder::new("<API KEY>")
+        ///     .user_agent("portfolio-bot/1.0")
+        ///     .header("X-Request-Source", "reports")
+        ///     .build().unwrap();
+        /// ```
+        pub fn header<K: Into<String>, V: Into<String>>(mut self, name: K, value: V) -> CmcBuilder {
+            self.config.headers.push((name.into(), value.into()));
+            self
+        }
+
+        /// Optionally send requests through a pre-configured `reqwest` client, for example one
+        /// with a proxy, custom root certificates or timeouts.
+        /// # Example:
+        #[cfg_attr(feature = "blocking", doc = " ```rust")]
+        #[cfg_attr(not(feature = "blocking"), doc = " ```rust,ignore")]
+        /// use cmc::CmcBuilder;
+        /// use std::time::Duration;
+        ///
+        /// let client = reqwest::blocking::Client::builder()
+        ///     .timeout(Duration::from_secs(10))
+        ///     .build()
+        ///     .unwrap();
+        /// let cmc = CmcBuilder::new("<API KEY>").client(client).build().unwrap();
+        /// ```
+        pub fn client(self, client: Client) -> CmcBuilder {
+            self.transport(client)
+        }
+
+        /// Optionally send requests through a custom transport, see [`crate::transport`].
+        pub fn transport<T: Transport + 'static>(mut self, transport: T) -> CmcBuilder {
+            self.transport = Some(Arc::new(transport));
+            self
+        }
+
+        /// Returns a Cmc client that uses this CmcBuilder configuration.
+        ///
+        /// Fails with `CmcErrors::ConfigError` if the API key is empty, the base url is not a url
+        /// ending with a slash, a currency is not a valid symbol or id, or both `convert` and
+        /// `convert_id` are set. Fails with `CmcErrors::RequestError` if the HTTP client can not
+        /// be initialized.
+        pub fn build(mut self) -> CmcResult<Cmc> {
+            if let Some(currency) = self.convert.take() {
+                if self.config.currency_id.is_some() {
+                    return Err(ConfigError::ConvertConflict.into());
+                }
+                self.config.currency = currency;
+            }
+            if !self.keys.validate() {
+                return Err(ConfigError::EmptyApiKey.into());
+            }
+            self.config.validate()?;
+
+            let transport = match self.transport {
+                Some(transport) => transport,
+                None => {
+                    let mut client = Client::builder().pool_idle_timeout(None);
+                    if let Some(timeout) = self.connect_timeout {
+                        client = client.connect_timeout(timeout);
+                    }
+                    if let Some(proxy) = self.proxy {
+                        client = client.proxy(proxy);
+                    }
+                    Arc::new(client.build()?)
+                }
+            };
+
+            Ok(Cmc {
+                keys: self.keys,
+                transport,
+                config: self.config,
+            })
+        }
+    }
+
+    /// A `Cmc` can be used to create a CoinMarketCap client with default configuration.
+    #[derive(Clone)]
+    pub struct Cmc {
+        keys: KeyPool,
+        transport: Arc<dyn Transport>,
+        config: Config,
+    }
+
+    impl std::fmt::Debug for Cmc {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("Cmc")
+                .field("keys", &self.keys)
+                .field("transport", &self.transport)
+                .field("config", &self.config)
+                .finish()
+        }
+    }
+
+    impl Cmc {
+        /// Constructs a new CoinMarketCap Client.
+        ///
+        /// An empty API key is not checked here: every request then fails with
+        /// `ConfigError::EmptyApiKey`. Use `CmcBuilder::new(api_key).build()` to validate the
+        /// configuration up front.
+        ///
+        /// # Panics
+        ///
+        /// If the HTTP client can not be initialized, like `reqwest::Client::new()`.
+        pub fn new<T: Into<KeyPool>>(api_key: T) -> Self {
+            let client = Client::builder()
+                .pool_idle_timeout(None)
+                .build()
+                .expect("HTTP client can not be initialized");
+            Cmc {
+                keys: api_key.into(),
+                transport: Arc::new(client),
+                config: Config::default(),
+            }
+        }
+
+        /// API keys of this client, with their usage.
+        pub fn keys(&self) -> &KeyPool {
+            &self.keys
+        }
+
+        /// Asks `key_info()` for the credits left on each key, used by `Strategy::MostCredits`.
+        /// Key info requests do not cost credits.
+        #[cfg(feature = "key")]
+        pub $($async)? fn refresh_key_credits(&self) -> CmcResult<()> {
+            let request = self.request(KeyUsage::PATH, &[], None)?;
+            for (index, api_key) in self.keys.keys().into_iter().enumerate() {
+                if let Some(body) = self.fetch_with(&request, index, &api_key) $($await)*? {
+                    let info = serde_json::from_value::<CmcKeyInfo>(body)?;
+                    self.keys.seed(&api_key, &info.data);
+                }
+            }
+            Ok(())
+        }
+
+        /// Credits spent by this client and its clones, per endpoint and per day.
+        pub fn ledger(&self) -> &Ledger {
+            &self.config.ledger
+        }
+
+        fn request(
+            &self,
+            endpoint: &str,
+            query: &[(&str, String)],
+            timeout: Option<Duration>,
+        ) -> CmcResult<HttpRequest> {
+            let url = format!("{}{}", self.config.base_url, endpoint);
+            let mut request = HttpRequest::new(&url, query)?.header("Accepts", "application/json");
+            request.headers.extend(self.config.headers.iter().cloned());
+            request.timeout = timeout.or(self.config.timeout);
+            Ok(request)
+        }
+
+        /// Sends a reference data request through the [`RefCache`], if one is configured.
+        ///
+        /// Stale entries are returned immediately and refreshed on a background thread.
+        $($async)? fn send_cached<T: DeserializeOwned>(&self, request: HttpRequest) -> CmcResult<T> {
+            let cache = match &self.config.cache {
+                Some(cache) => cache,
+                None => {
+                    let body = self.fetch(request) $($await)*?;
+                    return Ok(serde_json::from_value(body)?);
+                }
+            };
+
+            let key = request.url.to_string();
+
+            match cache.lookup(&key) {
+                Lookup::Fresh(body) => Ok(serde_json::from_value(body)?),
+                Lookup::Stale(body) => {
+                    if cache.begin_refresh(&key) {
+                        self.refresh(cache.clone(), key, request) $($await)*;
+                    }
+                    Ok(serde_json::from_value(body)?)
+                }
+                Lookup::Miss => {
+                    let body = self.fetch(request) $($await)*?;
+                    let _ = cache.store(&key, &body);
+                    Ok(serde_json::from_value(body)?)
+                }
+            }
+        }
+
+        /// Sends a request with the next key of the pool, failing over to the other keys while
+        /// they are rate limited.
+        $($async)? fn fetch_pooled(&self, request: HttpRequest) -> CmcResult<Value> {
+            if !self.keys.validate() {
+                return Err(ConfigError::EmptyApiKey.into());
+            }
+            for _ in 0..self.keys.len() {
+                let (index, api_key) = self.keys.next()?;
+                if let Some(body) = self.fetch_with(&request, index, &api_key) $($await)*? {
+                    return Ok(body);
+                }
+            }
+            Err(self.keys.next().err().unwrap_or(CmcErrors::RateLimited(Duration::ZERO)))
+        }
+
+        /// Sends a request with one key. `None` if the key was rate limited and put on cooldown.
+        $($async)? fn fetch_with(
+            &self,
+            request: &HttpRequest,
+            index: usize,
+            api_key: &str,
+        ) -> CmcResult<Option<Value>> {
+            let ledger = &self.config.ledger;
+            let endpoint = self.config.endpoint_of(&request.url);
+            // Released once the response is recorded, or on error.
+            let _reservation = ledger.reserve(&endpoint)?;
+            let request = request.clone().header("X-CMC_PRO_API_KEY", api_key);
+
+            let resp = self.transport.send(request) $($await)*?;
+            match resp.status {
+                StatusCode::OK => {
+                    let body = serde_json::from_slice::<Value>(&resp.body)?;
+                    ledger.record_body(&endpoint, &body);
+                    let credits = body["status"]["credit_count"].as_u64().unwrap_or_default();
+                    self.keys.record(index, credits);
+                    Ok(Some(body))
+                }
+                code => {
+                    // Read leniently: a rate limited key has to rotate whatever the body is,
+                    // proxies answer 429 with HTML or nothing at all.
+                    let body = serde_json::from_slice::<Value>(&resp.body).unwrap_or_default();
+                    let status = &body["status"];
+                    let credits = status["credit_count"].as_u64().unwrap_or_default();
+                    ledger.record(&endpoint, credits);
+                    self.keys.record(index, credits);
+                    let error_code = status["error_code"].as_i64().unwrap_or_default();
+                    if self.keys.rate_limited(index, code.as_u16(), error_code) {
+                        return Ok(None);
+                    }
+                    let message = match &status["error_message"] {
+                        Value::Null => String::from_utf8_lossy(&resp.body).into_owned(),
+                        message => message.to_string(),
+                    };
+                    Err(CmcErrors::ApiError(format!(
+                        "Status Code: {}. Error message: {}",
+                        code, message
+                    )))
+                }
+            }
+        }
+
+        /// Per-call request for the latest market quotes of one or more cryptocurrencies.
+        ///
+        #[cfg_attr(feature = "blocking", doc = " ```rust")]
+        #[cfg_attr(not(feature = "blocking"), doc = " ```rust,ignore")]
+        /// use cmc::{Cmc, CryptoId};
+        ///
+        /// let cmc = Cmc::new("<API KEY>");
+        ///
+        /// match cmc.quotes().by_id([CryptoId(1), CryptoId(1027)]).convert("EUR").send() {
+        ///     Ok(quotes) => println!("{}", quotes.data.len()),
+        ///     Err(err) => println!("{}", err),
+        /// }
+        /// ```
+        #[cfg(feature = "cryptocurrency")]
+        pub fn quotes(&self) -> QuotesBy<'_, Cmc> {
+            QuotesBy { cmc: self }
+        }
+
+        /// Per-call request for the cryptocurrency id map, see [id_map()][id_map].
+        /// [id_map]: ./struct.Cmc.html#method.id_map
+        #[cfg(feature = "cryptocurrency")]
+        pub fn crypto_map(&self) -> Call<'_, Cmc, CryptoMap> {
+            Call::new(self, CryptoMap::default())
+        }
+
+        /// Per-call request for the fiat id map, see [fiat_id_map()][fiat_id_map].
+        /// [fiat_id_map]: ./struct.Cmc.html#method.fiat_id_map
+        #[cfg(feature = "fiat")]
+        pub fn fiat_map(&self) -> Call<'_, Cmc, FiatMap> {
+            Call::new(self, FiatMap::default())
+        }
+
+        /// Per-call request for the exchange id map, see [exchange_id_map()][exchange_id_map].
+        /// [exchange_id_map]: ./struct.Cmc.html#method.exchange_id_map
+        #[cfg(feature = "exchange")]
+        pub fn exchange_map(&self) -> Call<'_, Cmc, ExchangeMap> {
+            Call::new(self, ExchangeMap::default())
+        }
+
+        /// Per-call request for a price conversion of `amount`.
+        ///
+        #[cfg_attr(feature = "blocking", doc = " ```rust")]
+        #[cfg_attr(not(feature = "blocking"), doc = " ```rust,ignore")]
+        /// use cmc::{Cmc, CryptoId, FiatId};
+        ///
+        /// let cmc = Cmc::new("<API KEY>");
+        ///
+        /// // 1.6 ETH in EUR a year ago.
+        /// let call = cmc
+        ///     .conversion(1.6)
+        ///     .by_id(CryptoId(1027))
+        ///     .convert_id(FiatId(2790))
+        ///     .time("2023-01-01T00:00:00Z");
+        ///
+        /// match call.send() {
+        ///     Ok(price) => println!("Total price: {}", price),
+        ///     Err(err) => println!("Error: {}", err),
+        /// }
+        /// ```
+        #[cfg(feature = "tools")]
+        pub fn conversion<A: AmountQuery>(&self, amount: A) -> ConversionBy<'_, Cmc> {
+            ConversionBy {
+                cmc: self,
+                amount: amount.to_amount(),
+            }
+        }
+
+        /// Per-call request converting `amount` of one cryptocurrency, by id or symbol, into one
+        /// or more currencies. Unlike [conversion()][conversion] it returns the whole conversion
+        /// record: id, name, `last_updated` and the quote of every target currency.
+        ///
+        #[cfg_attr(feature = "blocking", doc = " ```rust")]
+        #[cfg_attr(not(feature = "blocking"), doc = " ```rust,ignore")]
+        /// use cmc::{Cmc, CryptoId, Symbol};
+        ///
+        /// let cmc = Cmc::new("<API KEY>");
+        ///
+        /// // 2.5 ETH in EUR and BTC at the start of 2024.
+        /// let call = cmc
+        ///     .convert(2.5, CryptoId(1027))
+        ///     .convert("EUR,BTC")
+        ///     .time(1704067200_i64);
+        ///
+        /// match call.send() {
+        ///     Ok(conversion) => println!("{} {}: {:?}", conversion.amount, conversion.name, conversion.quote),
+        ///     Err(err) => println!("Error: {}", err),
+        /// }
+        ///
+        /// match cmc.convert(100, Symbol::new("SOL")).send() {
+        ///     Ok(conversion) => println!("{:?}", conversion.last_updated),
+        ///     Err(err) => println!("Error: {}", err),
+        /// }
+        /// ```
+        /// [conversion]: ./struct.Cmc.html#method.conversion
+        #[cfg(feature = "tools")]
+        pub fn convert<A: AmountQuery, R: Into<AssetRef>>(&self, amount: A, asset: R) -> Call<'_, Cmc, Convert> {
+            Call::new(self, Convert::new(amount.to_amount(), asset.into()))
+        }
+
+        /// Values tax lots given as `(amount, asset, time)` in the client's convert currency.
+        /// Each distinct asset and point in time is priced once with `price_conversion`, however
+        /// many lots share it; the lots keep their order.
+        ///
+        /// # Example:
+        ///
+        #[cfg_attr(feature = "blocking", doc = " ```rust")]
+        #[cfg_attr(not(feature = "blocking"), doc = " ```rust,ignore")]
+        /// use cmc::api::Amount;
+        /// use cmc::{AssetRef, Cmc, CryptoId, Symbol};
+        ///
+        /// let cmc = Cmc::new("<API KEY>");
+        ///
+        /// let lots = vec![
+        ///     ("0.5".parse().unwrap(), AssetRef::from(CryptoId(1)), "2023-03-01"),
+        ///     ("0.25".parse().unwrap(), AssetRef::from(CryptoId(1)), "2023-03-01"),
+        ///     (Amount::from(4), AssetRef::from(Symbol::new("ETH")), "2023-06-15"),
+        /// ];
+        ///
+        /// match cmc.value_lots(lots) {
+        ///     Ok(values) => values.iter().for_each(|lot| println!("{} {}", lot.value, lot.currency)),
+        ///     Err(err) => println!("{}", err),
+        /// }
+        /// ```
+        #[cfg(feature = "tools")]
+        pub $($async)? fn value_lots<I, T>(&self, lots: I) -> CmcResult<Vec<LotValue>>
+        where
+            I: IntoIterator<Item = (Amount, AssetRef, T)>,
+            T: TimeQuery,
+        {
+            let currency = first(&self.config.convert_param(None).1);
+            let mut prices: HashMap<(AssetRef, String), Amount> = HashMap::new();
+            let mut values = Vec::new();
+            for (amount, asset, time) in lots {
+                let time = time.to_time();
+                let key = (asset, time);
+                let price = match prices.get(&key) {
+                    Some(price) => *price,
+                    None => {
+                        let conversion = self.convert(1, key.0.clone()).time(&key.1).send() $($await)*?;
+                        let price = conversion
+                            .quote
+                            .get(&currency)
+                            .and_then(|quote| quote.price)
+                            .ok_or(CmcErrors::NullAnswer)?;
+                        prices.insert(key.clone(), price);
+                        price
+                    }
+                };
+                let (asset, time) = key;
+                values.push(LotValue {
+                    amount,
+                    asset,
+                    time,
+                    currency: currency.clone(),
+                    price,
+                    value: price * amount,
+                });
+            }
+            Ok(values)
+        }
+
+        /// Per-call request for the list of coin categories, see [categories()][categories].
+        /// [categories]: ./struct.Cmc.html#method.categories
+        #[cfg(feature = "cryptocurrency")]
+        pub fn category_list(&self) -> Call<'_, Cmc, CategoryList> {
+            Call::new(self, CategoryList::default())
+        }
+
+        /// Per-call request for a single coin category, see [category()][category].
+        /// [category]: ./struct.Cmc.html#method.category
+        #[cfg(feature = "cryptocurrency")]
+        pub fn category_info<T: Into<CategoryId>>(&self, id: T) -> Call<'_, Cmc, CategoryInfo> {
+            Call::new(self, CategoryInfo::new(id.into()))
+        }
+
+        /// Per-call request for cryptocurrency metadata, see [metadata()][metadata].
+        ///
+        #[cfg_attr(feature = "blocking", doc = " ```rust")]
+        #[cfg_attr(not(feature = "blocking"), doc = " ```rust,ignore")]
+        /// use cmc::Cmc;
+        ///
+        /// let cmc = Cmc::new("<API KEY>");
+        ///
+        /// match cmc.info().by_address("0xc40af1e4fecfa05ce6bab79dcd8b373d2e436c4e").send() {
+        ///     Ok(metadata) => println!("{}", metadata.description),
+        ///     Err(err) => println!("{}", err),
+        /// }
+        /// ```
+        /// [metadata]: ./struct.Cmc.html#method.metadata
+        #[cfg(feature = "cryptocurrency")]
+        pub fn info(&self) -> InfoBy<'_, Cmc> {
+            InfoBy { cmc: self }
+        }
+
+        /// Per-call request for exchange metadata, see [exchange_metadata()][exchange_metadata].
+        /// [exchange_metadata]: ./struct.Cmc.html#method.exchange_metadata
+        #[cfg(feature = "exchange")]
+        pub fn exchange_info(&self) -> ExchangeInfoBy<'_, Cmc> {
+            ExchangeInfoBy { cmc: self }
+        }
+
+        /// Per-call request for global market metrics, see [global_metrics()][global_metrics].
+        /// [global_metrics]: ./struct.Cmc.html#method.global_metrics
+        #[cfg(feature = "global_metrics")]
+        pub fn global_quotes(&self) -> Call<'_, Cmc, GlobalQuotes> {
+            Call::new(self, GlobalQuotes)
+        }
+
+        /// Per-call request for API key details, see [key_info()][key_info].
+        /// [key_info]: ./struct.Cmc.html#method.key_info
+        #[cfg(feature = "key")]
+        pub fn key_usage(&self) -> Call<'_, Cmc, KeyUsage> {
+            Call::new(self, KeyUsage)
+        }
+
+        /// Returns a mapping of all cryptocurrencies to unique CoinMarketCap ids.
+        ///
+        /// # Example:
+        ///
+        /// Parameters:
+        /// - `start` Offset the start.
+        /// - `limit` Specify the number of results to return.
+        /// - `sort` What field to sort the list of cryptocurrencies by.
+        ///
+        #[cfg_attr(feature = "blocking", doc = " ```rust")]
+        #[cfg_attr(not(feature = "blocking"), doc = " ```rust,ignore")]
+        /// use cmc::{Cmc, Sort};
+        ///
+        /// let cmc = Cmc::new("<API KEY>");
+        ///
+        /// match cmc.id_map(1, 50, Sort::CmcRank) {
+        ///     Ok(map) => println!("{}", map),
+        ///     Err(err) => println!("{}", err),
+        /// }
+        /// ```
+        #[cfg(feature = "cryptocurrency")]
+        pub $($async)? fn id_map(&self, start: usize, limit: usize, sort: Sort) -> CmcResult<CmcIdMap> {
+            self.crypto_map()
+                .start(start)
+                .limit(limit)
+                .sort(sort)
+                .send() $($await)*
+        }
+
+        /// Downloads the whole cryptocurrency id map, inactive and untracked cryptocurrencies
+        /// included, and returns a [`Resolver`] built from it.
+        ///
+        /// Combine with [cache()][cache] to avoid paging through the map on every start.
+        ///
+        /// # Example:
+        ///
+        #[cfg_attr(feature = "blocking", doc = " ```rust")]
+        #[cfg_attr(not(feature = "blocking"), doc = " ```rust,ignore")]
+        /// use cmc::Cmc;
+        ///
+        /// let cmc = Cmc::new("<API KEY>");
+        ///
+        /// match cmc.resolver() {
+        ///     Ok(resolver) => match resolver.active_only(true).by_slug("ethereum") {
+        ///         Ok(id) => println!("Ethereum id: {}", id),
+        ///         Err(err) => println!("{}", err),
+        ///     },
+        ///     Err(err) => println!("{}", err),
+        /// }
+        /// ```
+        /// [cache]: ./struct.CmcBuilder.html#method.cache
+        #[cfg(feature = "cryptocurrency")]
+        pub $($async)? fn resolver(&self) -> CmcResult<Resolver> {
+            let mut entries = Vec::new();
+            loop {
+                let page = self
+                    .crypto_map()
+                    .listing_status([
+                        crate::ListingStatus::Active,
+                        crate::ListingStatus::Inactive,
+                        crate::ListingStatus::Untracked,
+                    ])
+                    .start(entries.len() + 1)
+                    .limit(ID_MAP_PAGE)
+                    .sort(Sort::Id)
+                    .send() $($await)*?
+                    .data;
+                let last = page.len() < ID_MAP_PAGE;
+                entries.extend(page);
+                if last {
+                    break;
+                }
+            }
+            Ok(Resolver::new(entries))
+        }
+
+        /// Returns a mapping of all supported fiat currencies to unique CoinMarketCap ids.
+        ///
+        /// # Example:
+        ///
+        /// Parameters:
+        /// - `start` Offset the start.
+        /// - `limit` Specify the number of results to return.
+        /// - `sort` What field to sort the list of currencies by.
+        ///
+        /// Basic usage:
+        ///
+        #[cfg_attr(feature = "blocking", doc = " ```rust")]
+        #[cfg_attr(not(feature = "blocking"), doc = " ```rust,ignore")]
+        /// use cmc::{Cmc, SortFiat};
+        ///
+        /// let cmc = Cmc::new("<API KEY>");
+        ///
+        /// match cmc.fiat_id_map(1, 100, SortFiat::Name) {
+        ///     Ok(map) => println!("{}", map),
+        ///     Err(err) => println!("{}", err),
+        /// }
+        /// ```
+        #[cfg(feature = "fiat")]
+        pub $($async)? fn fiat_id_map(
+            &self,
+            start: usize,
+            limit: usize,
+            sort: SortFiat,
+        ) -> CmcResult<CmcFiatIdMap> {
+            self.fiat_map().start(start).limit(limit).sort(sort).send() $($await)*
+        }
+
+        /// Latest price for cryptocurrency in USD, or in the first `convert` currency of the
+        /// client.
+        ///
+        /// Symbols are not unique: a symbol shared by several cryptocurrencies fails with
+        /// `CmcErrors::Ambiguous`, pass an id or a slug instead.
+        ///
+        /// # Example:
+        ///
+        #[cfg_attr(feature = "blocking", doc = " ```rust")]
+        #[cfg_attr(not(feature = "blocking"), doc = " ```rust,ignore")]
+        /// use cmc::Cmc;
+        ///
+        /// let cmc = Cmc::new("<API KEY>");
+        ///
+        /// match cmc.price("BTC") {
+        ///     Ok(price) => println!("Price: {}", price),
+        ///     Err(err) => println!("Error: {}", err),
+        /// }
+        /// ```
+        #[cfg(feature = "cryptocurrency")]
+        pub $($async)? fn price<T: AssetQuery>(&self, query: T) -> CmcResult<Amount> {
+            // The quotes are keyed by each convert currency, the price is in the first one.
+            let currency = first(self.config.currency_id.as_ref().unwrap_or(&self.config.currency));
+
+            let asset = query.to_asset(&self.config.pass)?;
+            self.lookup(asset, &currency) $($await)*
+        }
+
+        /// Price of a resolved asset, with one request of its own.
+        #[cfg(feature = "cryptocurrency")]
+        $($async)? fn price_of(&self, asset: AssetRef, currency: &str) -> CmcResult<Amount> {
+            match asset {
+                AssetRef::Symbol(symbol) => Ok(self.price_by_symbol(symbol.as_str(), currency) $($await)*?),
+                AssetRef::Id(id) => Ok(self.price_by_id(&id.to_string(), currency) $($await)*?),
+                AssetRef::Slug(slug) => Ok(self.price_by_slug(slug.as_str(), currency) $($await)*?),
+                AssetRef::Address(_) => Err(CmcErrors::PassIncompatible),
+            }
+        }
+
+        #[cfg(feature = "cryptocurrency")]
+        $($async)? fn price_by_id(&self, id: &str, currency: &str) -> CmcResult<Amount> {
+            let root = self.quotes().by_id(id).send() $($await)*?;
+            root.data
+                .get(id)
+                .and_then(|cc| cc.quote.get(currency))
+                .and_then(|quote| quote.price)
+                .ok_or(CmcErrors::NullAnswer)
+        }
+
+        #[cfg(feature = "cryptocurrency")]
+        $($async)? fn price_by_slug(&self, slug: &str, currency: &str) -> CmcResult<Amount> {
+            let root = self.quotes().by_slug(slug).send() $($await)*?;
+            root.data
+                .values()
+                .next()
+                .and_then(|cc| cc.quote.get(currency))
+                .and_then(|quote| quote.price)
+                .ok_or(CmcErrors::NullAnswer)
+        }
+
+        #[cfg(feature = "cryptocurrency")]
+        $($async)? fn price_by_symbol(&self, symbol: &str, currency: &str) -> CmcResult<Amount> {
+            let root = self.quotes().by_symbol(symbol).send() $($await)*?;
+            let symbol = symbol.to_uppercase();
+            crate::api::cryptocurrency::quotes_latest_v2::single(&symbol, root.data.get(&symbol))?
+                .quote
+                .get(currency)
+                .and_then(|quote| quote.price)
+                .ok_or(CmcErrors::NullAnswer)
+        }
+
+        /// Returns the latest market quote for 1 or more cryptocurrencies (using id's).
+        #[cfg(feature = "cryptocurrency")]
+        pub $($async)? fn quotes_latest_by_id<T: Identifiers<CryptoId>>(&self, ids: T) -> CmcResult<QLv2Id> {
+            self.quotes().by_id(ids).send() $($await)*
+        }
+
+        /// Returns the latest market quote for 1 or more cryptocurrencies (using slug's).
+        #[cfg(feature = "cryptocurrency")]
+        pub $($async)? fn quotes_latest_by_slug<T: Identifiers<Slug>>(&self, slugs: T) -> CmcResult<QLv2Slug> {
+            self.quotes().by_slug(slugs).send() $($await)*
+        }
+
+        /// Returns the latest market quote for 1 or more cryptocurrencies (using symbol's).
+        #[cfg(feature = "cryptocurrency")]
+        pub $($async)? fn quotes_latest_by_symbol<T: Identifiers<Symbol>>(
+            &self,
+            symbols: T,
+        ) -> CmcResult<QLv2Symbol> {
+            self.quotes().by_symbol(symbols).send() $($await)*
+        }
+
+        /// Values a portfolio with the latest quotes: total, per asset, allocation and 1h, 24h
+        /// and 7d PnL. Needs one request per kind of reference held (ids, slugs, symbols), see
+        /// [`crate::portfolio`]. Fails with `CmcErrors::Ambiguous` for a symbol shared by several
+        /// cryptocurrencies.
+        ///
+        /// # Example:
+        ///
+        #[cfg_attr(feature = "blocking", doc = " ```rust")]
+        #[cfg_attr(not(feature = "blocking"), doc = " ```rust,ignore")]
+        /// use cmc::api::Amount;
+        /// use cmc::portfolio::Portfolio;
+        /// use cmc::{Cmc, CryptoId, Slug};
+        ///
+        /// let cmc = Cmc::new("<API KEY>");
+        ///
+        /// let portfolio = Portfolio::new()
+        ///     .hold(CryptoId(1), "0.5".parse().unwrap())
+        ///     .hold(Slug::new("ethereum"), Amount::from(3))
+        ///     .hold(CryptoId(825), Amount::from(1000));
+        ///
+        /// match cmc.value(&portfolio) {
+        ///     Ok(valuation) => println!("{}", valuation),
+        ///     Err(err) => println!("{}", err),
+        /// }
+        /// ```
+        #[cfg(feature = "cryptocurrency")]
+        pub $($async)? fn value(&self, portfolio: &Portfolio) -> CmcResult<Valuation> {
+            let quotes = self.portfolio_quotes(portfolio) $($await)*?;
+            portfolio.valuation(&portfolio.currency(&self.config), &quotes)
+        }
+
+        /// Values a portfolio at a past point in time, with one `price_conversion` request per
+        /// cryptocurrency on top of the requests of [value()][value] that identify the assets.
+        /// Holdings of the same cryptocurrency by different references share one request. PnL is
+        /// `None`.
+        ///
+        /// # Example:
+        ///
+        #[cfg_attr(feature = "blocking", doc = " ```rust")]
+        #[cfg_attr(not(feature = "blocking"), doc = " ```rust,ignore")]
+        /// use cmc::portfolio::Portfolio;
+        /// use cmc::{Cmc, Slug};
+        ///
+        /// let cmc = Cmc::new("<API KEY>");
+        ///
+        /// let portfolio = Portfolio::new().hold(Slug::new("bitcoin"), "0.5".parse().unwrap());
+        ///
+        /// match cmc.value_at(&portfolio, "2024-01-01T00:00:00Z") {
+        ///     Ok(valuation) => println!("{}", valuation.total),
+        ///     Err(err) => println!("{}", err),
+        /// }
+        /// ```
+        /// [value]: ./struct.Cmc.html#method.value
+        #[cfg(all(feature = "cryptocurrency", feature = "tools"))]
+        pub $($async)? fn value_at<T: TimeQuery>(&self, portfolio: &Portfolio, time: T) -> CmcResult<Valuation> {
+            let quotes = self.portfolio_quotes(portfolio) $($await)*?;
+            let resolved = portfolio.resolve(&quotes)?;
+            let time = time.to_time();
+            let mut prices = HashMap::new();
+            for (id, _) in &resolved {
+                if prices.contains_key(id) {
+                    continue;
+                }
+                let mut call = self.conversion(1).by_id(CurrencyId::Crypto(*id)).time(&time);
+                call.convert = portfolio.convert_param();
+                prices.insert(*id, call.send() $($await)*?);
+            }
+            Ok(portfolio.valuation_at(&portfolio.currency(&self.config), resolved, &prices))
+        }
+
+        /// Takes one quotes snapshot of `ids` in the client's convert currencies and returns it as
+        /// a [`RateTable`][crate::rates::RateTable] for offline cross rates.
+        ///
+        /// # Example:
+        ///
+        #[cfg_attr(feature = "blocking", doc = " ```rust")]
+        #[cfg_attr(not(feature = "blocking"), doc = " ```rust,ignore")]
+        /// use cmc::{Cmc, CryptoId};
+        ///
+        /// let cmc = Cmc::new("<API KEY>");
+        ///
+        /// match cmc.rate_table([CryptoId(1), CryptoId(1027)]) {
+        ///     Ok(table) => println!("{:?}", table.rate("ETH", "BTC")),
+        ///     Err(err) => println!("{}", err),
+        /// }
+        /// ```
+        #[cfg(feature = "cryptocurrency")]
+        pub $($async)? fn rate_table<T: Identifiers<CryptoId>>(&self, ids: T) -> CmcResult<RateTable> {
+            let quotes = self.quotes().by_id(ids).send() $($await)*?;
+            Ok(RateTable::from_quotes(&quotes))
+        }
+
+        /// Latest quotes of the holdings, one request per kind of reference.
+        #[cfg(feature = "cryptocurrency")]
+        $($async)? fn portfolio_quotes(&self, portfolio: &Portfolio) -> CmcResult<PortfolioQuotes> {
+            let (ids, slugs, symbols) = portfolio.group()?;
+            let mut quotes = PortfolioQuotes::default();
+            if !ids.is_empty() {
+                let mut call = self.quotes().by_id(ids);
+                call.convert = portfolio.convert_param();
+                quotes.by_id = call.send() $($await)*?.data;
+            }
+            if !slugs.is_empty() {
+                let mut call = self.quotes().by_slug(slugs);
+                call.convert = portfolio.convert_param();
+                quotes.by_slug = call.send() $($await)*?.data;
+            }
+            if !symbols.is_empty() {
+                let mut call = self.quotes().by_symbol(symbols);
+                call.convert = portfolio.convert_param();
+                quotes.by_symbol = call.send() $($await)*?.data;
+            }
+            Ok(quotes)
+        }
+
+        /// Returns API key details and usage stats.
+        #[cfg(feature = "key")]
+        pub $($async)? fn key_info(&self) -> CmcResult<KeyInfo> {
+            self.key_usage().send() $($await)*
+        }
+
+        /// Convert an amount of one cryptocurrency or fiat currency into one or more different currencies
+        /// utilizing the latest market rate for each currency.
+        ///
+        /// # Example:
+        ///
+        /// Parameters:
+        /// - `amount` An amount of currency to convert, any number or with the `decimal` feature a `Decimal`.
+        /// - `symbol` Alternatively the currency symbol of the base cryptocurrency or fiat to convert from.
+        /// - `time` Optional timestamp (Unix seconds, ISO 8601 or any [`TimeQuery`]) to reference historical pricing during conversion. If not passed, the current time will be used.
+        /// - `convert` Pass  fiat or cryptocurrency symbols to convert the source amount to.
+        ///
+        /// Basic usage:
+        ///
+        #[cfg_attr(feature = "blocking", doc = " ```rust")]
+        #[cfg_attr(not(feature = "blocking"), doc = " ```rust,ignore")]
+        /// use cmc::Cmc;
+        ///
+        /// let cmc = Cmc::new("<API KEY>");
+        ///
+        /// // 2.5 BTC in EUR
+        /// match cmc.price_conversion(2.5, "BTC", None::<i64>, "EUR") {
+        ///     Ok(price) => println!("Total price: {}", price),
+        ///     Err(err) => println!("Error: {}", err),
+        /// }
+        ///
+        /// // 2.5 BTC in EUR at the start of 2024
+        /// match cmc.price_conversion(2.5, "BTC", Some(1704067200_i64), "EUR") {
+        ///     Ok(price) => println!("Total price: {}", price),
+        ///     Err(err) => println!("Error: {}", err),
+        /// }
+        /// ```
+        #[cfg(feature = "tools")]
+        pub $($async)? fn price_conversion<S: Identifiers<Symbol>, C: Identifiers<Symbol>>(
+            &self,
+            amount: impl AmountQuery,
+            symbol: S,
+            time: Option<impl TimeQuery>,
+            convert: C,
+        ) -> CmcResult<Amount> {
+            let call = self.conversion(amount).by_symbol(symbol).convert(convert);
+            match time {
+                Some(t) => call.time(t).send() $($await)*,
+                None => call.send() $($await)*,
+            }
+        }
+
+        /// Convert an amount of one cryptocurrency or fiat currency into one or more different currencies
+        /// utilizing the latest market rate for each currency.
+        ///
+        /// # Example:
+        ///
+        /// Parameters:
+        /// - `amount` An amount of currency to convert, any number or with the `decimal` feature a `Decimal`.
+        /// - `id` The CoinMarketCap currency ID of the base cryptocurrency or fiat to convert from.
+        /// - `time` Optional timestamp (Unix seconds, ISO 8601 or any [`TimeQuery`]) to reference historical pricing during conversion. If not passed, the current time will be used.
+        /// - `convert_id` Optionally calculate market quotes by CoinMarketCap ID instead of symbol. This option is identical to convert outside of ID format.
+        ///
+        /// Basic usage:
+        ///
+        #[cfg_attr(feature = "blocking", doc = " ```rust")]
+        #[cfg_attr(not(feature = "blocking"), doc = " ```rust,ignore")]
+        /// use cmc::Cmc;
+        ///
+        /// let cmc = Cmc::new("<API KEY>");
+        ///
+        /// // 1.6 ETH in Monero (XMR).
+        /// match cmc.price_conversion_id(1.6, "1027", None::<i64>, "328") {
+        ///     Ok(price) => println!("Total price: {}", price),
+        ///     Err(err) => println!("Error: {}", err),
+        /// }
+        /// ```
+        #[cfg(feature = "tools")]
+        pub $($async)? fn price_conversion_id<I: Identifiers<CurrencyId>, C: Identifiers<CurrencyId>>(
+            &self,
+            amount: impl AmountQuery,
+            id: I,
+            time: Option<impl TimeQuery>,
+            convert_id: C,
+        ) -> CmcResult<Amount> {
+            let call = self.conversion(amount).by_id(id).convert_id(convert_id);
+            match time {
+                Some(t) => call.time(t).send() $($await)*,
+                None => call.send() $($await)*,
+            }
+        }
+
+        /// Returns information about all coin categories available on CoinMarketCap.
+        ///
+        /// # Example:
+        ///
+        /// Parameters:
+        /// - `start` Optionally offset the start (1-based index) of the paginated list of items to return.
+        /// - `limit` Optionally specify the number of results to return. Use this parameter and the "start" parameter to determine your own pagination size.
+        /// - `pass` Cryptocurrency pass (id, slug, symbol)
+        ///
+        /// Basic usage:
+        ///
+        #[cfg_attr(feature = "blocking", doc = " ```rust")]
+        #[cfg_attr(not(feature = "blocking"), doc = " ```rust,ignore")]
+        /// use cmc::{CmcBuilder, Pass};
+        ///
+        /// let cmc = CmcBuilder::new("<API KEY>")
+        ///     .pass(Pass::Id)
+        ///     .build().unwrap();
+        ///
+        /// match cmc.categories(1, 10, "1027") {
+        ///     Ok(categories) => println!("{categories}"),
+        ///     Err(err) => println!("{err}"),
+        /// }
+        /// ```
+        #[cfg(feature = "cryptocurrency")]
+        pub $($async)? fn categories<T: AssetQuery>(
+            &self,
+            start: usize,
+            limit: usize,
+            pass: T,
+        ) -> CmcResult<CmcCategories> {
+            let call = self.category_list().start(start).limit(limit);
+            let call = match pass.to_param(&self.config.pass)? {
+                ("id", query) => call.id(query),
+                ("slug", query) => call.slug(query),
+                ("symbol", query) => call.symbol(query),
+                _ => return Err(CmcErrors::PassIncompatible),
+            };
+            call.send() $($await)*
+        }
+
+        /// Returns information about a single coin category available on CoinMarketCap.
+        ///
+        /// # Example:
+        ///
+        /// Parameters:
+        /// - `id` The Category ID. This can be found using the [categories()].
+        /// - `start` Optionally offset the start (1-based index) of the paginated list of coins to return.
+        /// - `limit` Optionally specify the number of coins to return. Use this parameter and the "start" parameter to determine your own pagination size.
+        ///
+        /// Basic usage:
+        ///
+        #[cfg_attr(feature = "blocking", doc = " ```rust")]
+        #[cfg_attr(not(feature = "blocking"), doc = " ```rust,ignore")]
+        /// use cmc::CmcBuilder;
+        ///
+        /// let cmc = CmcBuilder::new("<API KEY>")
+        ///     .convert("EUR")
+        ///     .build().unwrap();
+        ///
+        /// match cmc.category("605e2ce9d41eae1066535f7c", 1, 10) {
+        ///     Ok(category) => println!("{category}"),
+        ///     Err(err) => println!("{err}"),
+        /// }
+        /// ```
+        /// [categories()]: ./struct.Cmc.html#method.categories
+        #[cfg(feature = "cryptocurrency")]
+        pub $($async)? fn category<T: Into<CategoryId>>(
+            &self,
+            id: T,
+            start: usize,
+            limit: usize,
+        ) -> CmcResult<Category> {
+            self.category_info(id).start(start).limit(limit).send() $($await)*
+        }
+
+        /// Returns all static metadata available for one cryptocurrency.
+        /// This information includes details like logo, description, official website URL, social links,
+        /// and links to a cryptocurrency's technical documentation.
+        ///
+        /// Parameters:
+        ///
+        /// - **Id**: Cryptocurrency coinmarketcap id. Example: "1027"
+        ///
+        /// - **Slug**: Alternatively pass one cryptocurrency slug. Example: "ethereum"
+        ///
+        /// - **Symbol**: Alternatively pass one cryptocurrency symbol. Example: "BTC"
+        ///
+        /// - **Address**: Alternatively pass in a contract address. Example: "0xc40af1e4fecfa05ce6bab79dcd8b373d2e436c4e"
+        ///
+        /// **NOTE**: `CoinMarketCap recommend utilizing CMC ID instead of cryptocurrency symbols to securely identify cryptocurrencies with other endpoints and in your own application logic`
+        /// (Can be obtained using the method [id_map()][id]).
+        #[cfg_attr(feature = "blocking", doc = " ```rust")]
+        #[cfg_attr(not(feature = "blocking"), doc = " ```rust,ignore")]
+        /// use cmc::{CmcBuilder, Pass};
+        ///
+        /// let cmc = CmcBuilder::new("<API KEY>")
+        ///     .pass(Pass::Id)
+        ///     .build().unwrap();
+        /// // Cryptocurrency metadata.
+        /// match cmc.metadata("1027") {
+        ///     Ok(metadata) => println!("{}", metadata.description),
+        ///     Err(err) => println!("{}", err),
+        /// }
+        ///
+        /// let cmc = CmcBuilder::new("<API KEY>")
+        ///     .pass(Pass::Address)
+        ///     .build().unwrap();
+        /// // Contract address metadata.
+        /// match cmc.metadata("0xc40af1e4fecfa05ce6bab79dcd8b373d2e436c4e") {
+        ///     Ok(metadata) => println!("{}", metadata.description),
+        ///     Err(err) => println!("{}", err),
+        /// }
+        ///```
+        /// [id]: ./struct.Cmc.html#method.id_map
+        #[cfg(feature = "cryptocurrency")]
+        pub $($async)? fn metadata<T: AssetQuery>(&self, query: T) -> CmcResult<Metadata> {
+            let asset = query.to_asset(&self.config.pass)?;
+            Call::new(self, CryptoInfo(asset)).send() $($await)*
+        }
+
+        /// Returns HashMap with all static metadata available for one or more cryptocurrencies.
+        /// This information includes details like logo, description, official website URL, social links,
+        /// and links to a cryptocurrency's technical documentation.
+        /// # Examples:
+        ///
+        /// Parameters:
+        ///
+        /// - **query**: One or more comma-separated CoinMarketCap cryptocurrency exchange ids. Example: "1,328,1027"
+        #[cfg_attr(feature = "blocking", doc = " ```rust")]
+        #[cfg_attr(not(feature = "blocking"), doc = " ```rust,ignore")]
+        /// use cmc::Cmc;
+        ///
+        /// let cmc = Cmc::new("<API KEY>");
+        ///
+        /// let query = "1,328,1027";
+        /// match cmc.metadata_map(query) {
+        ///     Ok(map) => {
+        ///         for m in map.values() {
+        ///             println!("{}", m.name);
+        ///         }
+        ///     }
+        ///     Err(err) => println!("{}", err),
+        /// }
+        /// ```
+        #[cfg(feature = "cryptocurrency")]
+        pub $($async)? fn metadata_map<T: Identifiers<CryptoId>>(
+            &self,
+            query: T,
+        ) -> CmcResult<HashMap<String, Metadata>> {
+            self.info().by_ids(query).send() $($await)*
+        }
+
+        /// Returns the latest global cryptocurrency market metrics. Use the [convert()] to return
+        /// market values in multiple fiat and cryptocurrency conversions in the same call.
+        ///
+        #[cfg_attr(feature = "blocking", doc = " ```rust")]
+        #[cfg_attr(not(feature = "blocking"), doc = " ```rust,ignore")]
+        /// use cmc::CmcBuilder;
+        ///
+        /// let cmc = CmcBuilder::new("<API KEY>")
+        ///     .convert("EUR")
+        ///     .build().unwrap();
+        ///
+        /// match cmc.global_metrics() {
+        ///     Ok(gm) => println!("{}", gm.btc_dominance),
+        ///     Err(err) => println!("{}", err),
+        /// }
+        /// ```
+        /// [convert()]: ./struct.CmcBuilder.html#method.convert
+        #[cfg(feature = "global_metrics")]
+        pub $($async)? fn global_metrics(&self) -> CmcResult<GlobalMetrics> {
+            self.global_quotes().send() $($await)*
+        }
+
+        /// Returns all static metadata for one or more exchanges. This information includes details
+        /// like launch date, logo, official website URL, social links, and market fee documentation URL.
+        ///
+        /// # Examples:
+        ///
+        /// Parameters:
+        ///
+        /// - **Id**: One or more comma-separated CoinMarketCap cryptocurrency exchange ids. Example: "270,271"
+        ///
+        /// - **Slug**: Alternatively, one or more comma-separated exchange names in URL friendly
+        ///   shorthand "slug" format (all lowercase, spaces replaced with hyphens). Example: "binance,gdax".
+        ///
+        #[cfg_attr(feature = "blocking", doc = " ```rust")]
+        #[cfg_attr(not(feature = "blocking"), doc = " ```rust,ignore")]
+        /// use cmc::{CmcBuilder, Pass};
+        ///
+        /// // using Id
+        /// let cmc = CmcBuilder::new("<API KEY>")
+        ///     .pass(Pass::Id)
+        ///     .build().unwrap();
+        ///
+        /// match cmc.exchange_metadata("270") {
+        ///     Ok(metadata) => println!("{}", metadata.data.get("270").unwrap().name),
+        ///     Err(err) => println!("{}", err),
+        /// }
+        ///
+        /// // using Slug
+        /// let cmc = CmcBuilder::new("<API KEY>")
+        ///     .pass(Pass::Slug)
+        ///     .build().unwrap();
+        ///
+        /// match cmc.exchange_metadata("binance") {
+        ///     Ok(metadata) => println!("{}", metadata.data.get("binance").unwrap().name),
+        ///     Err(err) => println!("{}", err),
+        /// }
+        /// ```
+        #[cfg(feature = "exchange")]
+        pub $($async)? fn exchange_metadata<T: ExchangeQuery>(&self, exchange: T) -> CmcResult<ExchangeMetadata> {
+            let param = exchange.to_exchange_param(&self.config.pass)?;
+            Call::new(self, ExchangeInfo(param)).send() $($await)*
+        }
+
+        /// Returns a paginated list of all active cryptocurrency exchanges by CoinMarketCap ID.
+        ///
+        /// # Examples:
+        ///
+        /// Parameters:
+        ///
+        /// - `listing_status`:
+        ///
+        ///  **Active**: Only active exchanges are returned.
+        ///
+        ///  **Inactive**: List of exchanges that are no longer active.
+        ///
+        ///  **Untracked**: List of exchanges that are registered but do not currently meet methodology requirements to have active markets tracked.
+        ///
+        /// - `start`: Optionally offset the start (1-based index) of the paginated list of items to return.
+        ///
+        /// - `limit`: Optionally specify the number of results to return. Use this parameter and the "start" parameter to determine your own pagination size.
+        ///
+        /// - `sort`: What field to sort the list of exchanges by.
+        ///
+        /// - `crypto_id`: Optionally include one cryptocurrency id to filter market pairs by. Use
+        ///   [exchange_map()][exchange_map] to filter by a fiat id.
+        ///
+        #[cfg_attr(feature = "blocking", doc = " ```rust")]
+        #[cfg_attr(not(feature = "blocking"), doc = " ```rust,ignore")]
+        /// use cmc::{Cmc, ListingStatusExchange, SortExchange};
+        ///
+        /// let cmc = Cmc::new("<API KEY>");
+        ///
+        /// match cmc.exchange_id_map(ListingStatusExchange::Active, 1, 10, SortExchange::Id, None) {
+        ///     Ok(map) => println!("{}", map),
+        ///     Err(err) => println!("{}", err),
+        /// }
+        /// ```
+        /// [exchange_map]: ./struct.Cmc.html#method.exchange_map
+        #[cfg(feature = "exchange")]
+        pub $($async)? fn exchange_id_map(
+            &self,
+            listing_status: ListingStatusExchange,
+            start: usize,
+            limit: usize,
+            sort: SortExchange,
+            crypto_id: Option<CryptoId>,
+        ) -> CmcResult<CmcExchangeIdMap> {
+            let call = self
+                .exchange_map()
+                .listing_status(listing_status)
+                .start(start)
+                .limit(limit)
+                .sort(sort);
+
+            match crypto_id {
+                Some(id) => call.crypto_id(id).send() $($await)*,
+                None => call.send() $($await)*,
+            }
+        }
+    }
+
+    impl<E: Endpoint> Call<'_, Cmc, E> {
+        /// Sends the request.
+        pub $($async)? fn send(self) -> CmcResult<E::Output> {
+            self.send_parts() $($await)*.map(|(_, data)| data)
+        }
+
+        /// Sends the request and keeps the response [`Status`][crate::api::response::Status]
+        /// (credits spent, elapsed time, deprecation notices) next to the data. Cached reference
+        /// data carries the status it was fetched with.
+        pub $($async)? fn send_with_status(self) -> CmcResult<Response<E::Output>> {
+            let (status, data) = self.send_parts() $($await)*?;
+            Ok(Response {
+                status: serde_json::from_value(status)?,
+                data,
+            })
+        }
+
+        $($async)? fn send_parts(self) -> CmcResult<(Value, E::Output)> {
+            self.endpoint.check()?;
+            let cmc = self.cmc;
+            let convert = E::CONVERT.then(|| cmc.config.convert_param(self.convert));
+
+            let mut query = self.endpoint.query();
+            if let Some((name, currency)) = &convert {
+                query.push((name, currency.clone()));
+            }
+            let request = cmc.request(E::PATH, &query, self.timeout)?;
+
+            let body = if E::CACHED {
+                cmc.send_cached::<Value>(request) $($await)*?
+            } else {
+                cmc.fetch(request) $($await)*?
+            };
+
+            let status = body.get("status").cloned().unwrap_or_default();
+            let convert = convert.map(|(_, currency)| currency).unwrap_or_default();
+            Ok((status, self.endpoint.parse(body, &convert)?))
+        }
+    }
+    };
+}
+
+pub(crate) use client;
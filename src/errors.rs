@@ -1,3 +1,4 @@
+use crate::ids::CryptoId;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use thiserror::Error;
@@ -6,7 +7,22 @@ use thiserror::Error;
 #[derive(Error, Debug)]
 pub enum CmcErrors {
     #[error("Request Error: {0}")]
-    RequestError(#[from] reqwest::Error),
+    RequestError(reqwest::Error),
+    #[error("Request timed out")]
+    Timeout,
+    /// Error of a coalesced request, shared by all callers that waited for it.
+    #[error("{0}")]
+    Shared(std::sync::Arc<CmcErrors>),
+    #[error("All API keys are rate limited, next one available in {0:?}")]
+    RateLimited(std::time::Duration),
+    #[error("Configuration Error: {0}")]
+    ConfigError(#[from] ConfigError),
+    #[error("Transport Error: {0}")]
+    TransportError(Box<dyn std::error::Error + Send + Sync>),
+    #[error("Invalid url: {0}")]
+    InvalidUrl(String),
+    #[error("Json Error: {0}")]
+    JsonError(#[from] serde_json::Error),
     #[error("Query must not contain commas")]
     IncorrectQuery,
     #[error("Api answer is null")]
@@ -15,6 +31,30 @@ pub enum CmcErrors {
     ApiError(String),
     #[error("Pass is incompatible with query")]
     PassIncompatible,
+    #[error("Nothing found for `{0}`")]
+    NotFound(String),
+    #[error("Ambiguous query `{0}`, candidates: {1:?}")]
+    Ambiguous(String, Vec<CryptoId>),
+    #[error("Credit budget exceeded: {0} of {1} credits spent")]
+    BudgetExceeded(u64, u64),
+    /// Background tasks of the async client (batching, `watch_quotes()`) need a Tokio runtime.
+    #[error("No Tokio runtime to spawn the background task on")]
+    NoRuntime,
+}
+
+/// Invalid `CmcBuilder` settings, reported by `build()`.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ConfigError {
+    #[error("API key is empty")]
+    EmptyApiKey,
+    #[error("Invalid base url `{0}`: {1}")]
+    InvalidBaseUrl(String, String),
+    #[error("Base url `{0}` must end with a slash")]
+    BaseUrlWithoutSlash(String),
+    #[error("Invalid currency `{0}`")]
+    InvalidCurrency(String),
+    #[error("`convert` and `convert_id` can not be combined")]
+    ConvertConflict,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -30,3 +70,13 @@ pub struct StatusError {
     pub elapsed: i64,
     pub credit_count: i64,
 }
+
+impl From<reqwest::Error> for CmcErrors {
+    fn from(err: reqwest::Error) -> Self {
+        if err.is_timeout() {
+            CmcErrors::Timeout
+        } else {
+            CmcErrors::RequestError(err)
+        }
+    }
+}
@@ -0,0 +1,81 @@
+//! Single-flight request coalescing for the async client.
+//!
+//! Identical requests that are in flight at the same time share one HTTP request: the first
+//! caller sends it, the others wait for its response. Requests are identical if they have the
+//! same endpoint and the same query parameters, in any order.
+
+use crate::api::CmcResult;
+use crate::errors::CmcErrors;
+use crate::transport::HttpRequest;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use tokio::sync::OnceCell;
+
+type Flight = Arc<OnceCell<Result<Value, Arc<CmcErrors>>>>;
+
+/// Requests in flight, by normalized url.
+#[derive(Debug, Default)]
+pub(crate) struct Flights {
+    in_flight: Mutex<HashMap<String, Flight>>,
+}
+
+impl Flights {
+    /// Runs `fetch`, unless an identical request is in flight already, and returns its result.
+    pub(crate) async fn run<F>(&self, request: &HttpRequest, fetch: F) -> CmcResult<Value>
+    where
+        F: Future<Output = CmcResult<Value>>,
+    {
+        let key = normalize(request);
+        let flight = self
+            .in_flight
+            .lock()
+            .unwrap()
+            .entry(key.clone())
+            .or_default()
+            .clone();
+
+        let result = flight
+            .get_or_init(|| async { fetch.await.map_err(Arc::new) })
+            .await
+            .clone();
+
+        let mut in_flight = self.in_flight.lock().unwrap();
+        if in_flight
+            .get(&key)
+            .is_some_and(|current| Arc::ptr_eq(current, &flight))
+        {
+            in_flight.remove(&key);
+        }
+        result.map_err(|err| shared(&err))
+    }
+}
+
+/// Url with the query parameters sorted by name.
+fn normalize(request: &HttpRequest) -> String {
+    let mut query: Vec<_> = request.url.query_pairs().collect();
+    query.sort();
+    let mut key = request.url.path().to_owned();
+    for (name, value) in query {
+        key.push(if key.contains('?') { '&' } else { '?' });
+        key.push_str(&format!("{}={}", name, value));
+    }
+    key
+}
+
+/// Copy of a shared error for each waiting caller.
+pub(crate) fn shared(err: &Arc<CmcErrors>) -> CmcErrors {
+    match err.as_ref() {
+        CmcErrors::Timeout => CmcErrors::Timeout,
+        CmcErrors::RateLimited(retry) => CmcErrors::RateLimited(*retry),
+        CmcErrors::BudgetExceeded(spent, budget) => CmcErrors::BudgetExceeded(*spent, *budget),
+        CmcErrors::ApiError(message) => CmcErrors::ApiError(message.clone()),
+        CmcErrors::InvalidUrl(url) => CmcErrors::InvalidUrl(url.clone()),
+        CmcErrors::ConfigError(config) => CmcErrors::ConfigError(config.clone()),
+        CmcErrors::NullAnswer => CmcErrors::NullAnswer,
+        CmcErrors::NotFound(query) => CmcErrors::NotFound(query.clone()),
+        CmcErrors::Ambiguous(query, ids) => CmcErrors::Ambiguous(query.clone(), ids.clone()),
+        _ => CmcErrors::Shared(err.clone()),
+    }
+}
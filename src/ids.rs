@@ -0,0 +1,415 @@
+//! Strongly typed identifiers.
+//!
+//! Every endpoint that takes a cryptocurrency, fiat or exchange accepts these types, so the way a
+//! value is interpreted no longer depends on the builder-wide [`Pass`]. Plain strings are still
+//! accepted and interpreted according to [`Pass`] as before.
+//!
+#![cfg_attr(feature = "blocking", doc = " ```rust")]
+#![cfg_attr(not(feature = "blocking"), doc = " ```rust,ignore")]
+//! use cmc::{AssetRef, Cmc, CryptoId, Slug, Symbol};
+//!
+//! let cmc = Cmc::new("<API KEY>");
+//!
+//! // Same client, different lookups.
+//! let btc = cmc.price(Symbol::new("BTC"));
+//! let eth = cmc.price(CryptoId(1027));
+//! let xmr = cmc.price(AssetRef::from(Slug::new("monero")));
+//! ```
+
+use crate::api::{CmcResult, Pass};
+use crate::errors::CmcErrors;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::num::ParseIntError;
+use std::str::FromStr;
+
+macro_rules! numeric_id {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+        #[serde(transparent)]
+        pub struct $name(pub u64);
+
+        impl Display for $name {
+            fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+                write!(f, "{}", self.0)
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = ParseIntError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                Ok($name(s.trim().parse()?))
+            }
+        }
+
+        impl From<u64> for $name {
+            fn from(id: u64) -> Self {
+                $name(id)
+            }
+        }
+    };
+}
+
+macro_rules! string_id {
+    ($(#[$meta:meta])* $name:ident, $normalize:expr) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+        #[serde(transparent)]
+        pub struct $name(String);
+
+        impl $name {
+            pub fn new<T: Into<String>>(value: T) -> Self {
+                let normalize: fn(String) -> String = $normalize;
+                $name(normalize(value.into()))
+            }
+
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl Display for $name {
+            fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(value: &str) -> Self {
+                $name::new(value)
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(value: String) -> Self {
+                $name::new(value)
+            }
+        }
+    };
+}
+
+numeric_id!(
+    /// CoinMarketCap cryptocurrency id. Example: `CryptoId(1027)` is Ethereum.
+    CryptoId
+);
+numeric_id!(
+    /// CoinMarketCap fiat currency id. Example: `FiatId(2781)` is USD.
+    FiatId
+);
+numeric_id!(
+    /// CoinMarketCap exchange id. Example: `ExchangeId(270)` is Binance.
+    ExchangeId
+);
+string_id!(
+    /// URL friendly name of a cryptocurrency or exchange, stored in lowercase. Example: "ethereum"
+    Slug,
+    |s| s.to_lowercase()
+);
+string_id!(
+    /// Ticker symbol, stored in uppercase. Example: "ETH"
+    Symbol,
+    |s| s.to_uppercase()
+);
+string_id!(
+    /// Token contract address. Example: "0xdac17f958d2ee523a2206206994597c13d831ec7"
+    ContractAddress,
+    |s| s
+);
+string_id!(
+    /// CoinMarketCap coin category id. Example: "605e2ce9d41eae1066535f7c"
+    CategoryId,
+    |s| s.to_lowercase()
+);
+
+/// A reference to one cryptocurrency.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AssetRef {
+    Id(CryptoId),
+    Slug(Slug),
+    Symbol(Symbol),
+    Address(ContractAddress),
+}
+
+impl AssetRef {
+    /// Query parameter name and value for this reference.
+    pub(crate) fn param(&self) -> (&'static str, String) {
+        match self {
+            AssetRef::Id(id) => ("id", id.to_string()),
+            AssetRef::Slug(slug) => ("slug", slug.to_string()),
+            AssetRef::Symbol(symbol) => ("symbol", symbol.to_string()),
+            AssetRef::Address(address) => ("address", address.to_string()),
+        }
+    }
+}
+
+impl Display for AssetRef {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AssetRef::Id(id) => write!(f, "{}", id),
+            AssetRef::Slug(slug) => write!(f, "{}", slug),
+            AssetRef::Symbol(symbol) => write!(f, "{}", symbol),
+            AssetRef::Address(address) => write!(f, "{}", address),
+        }
+    }
+}
+
+impl From<CryptoId> for AssetRef {
+    fn from(id: CryptoId) -> Self {
+        AssetRef::Id(id)
+    }
+}
+
+impl From<Slug> for AssetRef {
+    fn from(slug: Slug) -> Self {
+        AssetRef::Slug(slug)
+    }
+}
+
+impl From<Symbol> for AssetRef {
+    fn from(symbol: Symbol) -> Self {
+        AssetRef::Symbol(symbol)
+    }
+}
+
+impl From<ContractAddress> for AssetRef {
+    fn from(address: ContractAddress) -> Self {
+        AssetRef::Address(address)
+    }
+}
+
+/// A cryptocurrency or fiat currency id, as accepted by `convert_id` parameters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum CurrencyId {
+    Crypto(CryptoId),
+    Fiat(FiatId),
+}
+
+impl Display for CurrencyId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CurrencyId::Crypto(id) => write!(f, "{}", id),
+            CurrencyId::Fiat(id) => write!(f, "{}", id),
+        }
+    }
+}
+
+impl From<CryptoId> for CurrencyId {
+    fn from(id: CryptoId) -> Self {
+        CurrencyId::Crypto(id)
+    }
+}
+
+impl From<FiatId> for CurrencyId {
+    fn from(id: FiatId) -> Self {
+        CurrencyId::Fiat(id)
+    }
+}
+
+/// A single asset: either a typed reference, or a string interpreted according to [`Pass`].
+pub trait AssetQuery {
+    #[doc(hidden)]
+    fn to_asset(&self, pass: &Pass) -> CmcResult<AssetRef>;
+
+    /// Query parameter for endpoints that also accept comma-separated lists.
+    #[doc(hidden)]
+    fn to_param(&self, pass: &Pass) -> CmcResult<(&'static str, String)> {
+        Ok(self.to_asset(pass)?.param())
+    }
+}
+
+impl AssetQuery for AssetRef {
+    fn to_asset(&self, _pass: &Pass) -> CmcResult<AssetRef> {
+        if self.to_string().contains(',') {
+            return Err(CmcErrors::IncorrectQuery);
+        }
+        Ok(self.clone())
+    }
+}
+
+macro_rules! typed_asset_query {
+    ($($name:ty),*) => {
+        $(
+            impl AssetQuery for $name {
+                fn to_asset(&self, pass: &Pass) -> CmcResult<AssetRef> {
+                    AssetRef::from(self.clone()).to_asset(pass)
+                }
+            }
+        )*
+    };
+}
+
+typed_asset_query!(CryptoId, Slug, Symbol, ContractAddress);
+
+impl AssetQuery for str {
+    fn to_asset(&self, pass: &Pass) -> CmcResult<AssetRef> {
+        if self.contains(',') {
+            return Err(CmcErrors::IncorrectQuery);
+        }
+
+        Ok(match pass {
+            Pass::Id => AssetRef::Id(self.parse().map_err(|_| CmcErrors::IncorrectQuery)?),
+            Pass::Slug => AssetRef::Slug(Slug::new(self)),
+            Pass::Symbol => AssetRef::Symbol(Symbol::new(self)),
+            Pass::Address => AssetRef::Address(ContractAddress::new(self)),
+        })
+    }
+
+    fn to_param(&self, pass: &Pass) -> CmcResult<(&'static str, String)> {
+        Ok(match pass {
+            Pass::Id => ("id", self.to_owned()),
+            Pass::Slug => ("slug", self.to_owned()),
+            Pass::Symbol => ("symbol", self.to_owned()),
+            Pass::Address => ("address", self.to_owned()),
+        })
+    }
+}
+
+impl AssetQuery for String {
+    fn to_asset(&self, pass: &Pass) -> CmcResult<AssetRef> {
+        self.as_str().to_asset(pass)
+    }
+
+    fn to_param(&self, pass: &Pass) -> CmcResult<(&'static str, String)> {
+        self.as_str().to_param(pass)
+    }
+}
+
+impl<T: AssetQuery + ?Sized> AssetQuery for &T {
+    fn to_asset(&self, pass: &Pass) -> CmcResult<AssetRef> {
+        (**self).to_asset(pass)
+    }
+
+    fn to_param(&self, pass: &Pass) -> CmcResult<(&'static str, String)> {
+        (**self).to_param(pass)
+    }
+}
+
+/// One or more identifiers of kind `K`, joined into a comma-separated query value.
+///
+/// Implemented for a single identifier, arrays, slices and vectors of identifiers, and for plain
+/// strings, which are passed through unchanged. Example: `[CryptoId(1), CryptoId(1027)]` or "1,1027".
+pub trait Identifiers<K> {
+    #[doc(hidden)]
+    fn to_query(&self) -> String;
+}
+
+impl<K> Identifiers<K> for str {
+    fn to_query(&self) -> String {
+        self.to_owned()
+    }
+}
+
+impl<K> Identifiers<K> for String {
+    fn to_query(&self) -> String {
+        self.clone()
+    }
+}
+
+impl<K, T: Identifiers<K> + ?Sized> Identifiers<K> for &T {
+    fn to_query(&self) -> String {
+        (**self).to_query()
+    }
+}
+
+fn join<T: Display>(items: &[T]) -> String {
+    items
+        .iter()
+        .map(|item| item.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+macro_rules! identifiers {
+    ($kind:ty: $($name:ty),*) => {
+        $(
+            impl Identifiers<$kind> for $name {
+                fn to_query(&self) -> String {
+                    self.to_string()
+                }
+            }
+
+            impl Identifiers<$kind> for [$name] {
+                fn to_query(&self) -> String {
+                    join(self)
+                }
+            }
+
+            impl<const N: usize> Identifiers<$kind> for [$name; N] {
+                fn to_query(&self) -> String {
+                    join(self)
+                }
+            }
+
+            impl Identifiers<$kind> for Vec<$name> {
+                fn to_query(&self) -> String {
+                    join(self)
+                }
+            }
+        )*
+    };
+}
+
+identifiers!(CryptoId: CryptoId);
+identifiers!(ExchangeId: ExchangeId);
+identifiers!(Slug: Slug);
+identifiers!(Symbol: Symbol);
+identifiers!(CurrencyId: CurrencyId, CryptoId, FiatId);
+
+/// One or more exchanges: typed ids or slugs, or a string interpreted according to [`Pass`].
+pub trait ExchangeQuery {
+    #[doc(hidden)]
+    fn to_exchange_param(&self, pass: &Pass) -> CmcResult<(&'static str, String)>;
+}
+
+impl ExchangeQuery for str {
+    fn to_exchange_param(&self, pass: &Pass) -> CmcResult<(&'static str, String)> {
+        match pass {
+            Pass::Id => Ok(("id", self.to_owned())),
+            Pass::Slug => Ok(("slug", self.to_lowercase())),
+            Pass::Symbol | Pass::Address => Err(CmcErrors::PassIncompatible),
+        }
+    }
+}
+
+impl ExchangeQuery for String {
+    fn to_exchange_param(&self, pass: &Pass) -> CmcResult<(&'static str, String)> {
+        self.as_str().to_exchange_param(pass)
+    }
+}
+
+impl<T: ExchangeQuery + ?Sized> ExchangeQuery for &T {
+    fn to_exchange_param(&self, pass: &Pass) -> CmcResult<(&'static str, String)> {
+        (**self).to_exchange_param(pass)
+    }
+}
+
+macro_rules! exchange_query {
+    ($param:literal: $($name:ty),*) => {
+        $(
+            impl ExchangeQuery for $name {
+                fn to_exchange_param(&self, _pass: &Pass) -> CmcResult<(&'static str, String)> {
+                    Ok(($param, self.to_query()))
+                }
+            }
+        )*
+    };
+}
+
+exchange_query!("id": ExchangeId, [ExchangeId], Vec<ExchangeId>);
+exchange_query!("slug": Slug, [Slug], Vec<Slug>);
+
+impl<const N: usize> ExchangeQuery for [ExchangeId; N] {
+    fn to_exchange_param(&self, _pass: &Pass) -> CmcResult<(&'static str, String)> {
+        Ok(("id", self.to_query()))
+    }
+}
+
+impl<const N: usize> ExchangeQuery for [Slug; N] {
+    fn to_exchange_param(&self, _pass: &Pass) -> CmcResult<(&'static str, String)> {
+        Ok(("slug", self.to_query()))
+    }
+}
@@ -0,0 +1,324 @@
+//! Several API keys behind one client.
+//!
+//! A [`KeyPool`] hands out its keys round-robin or by the most credits left, and puts a key on
+//! cooldown when CoinMarketCap rejects it with HTTP 429 or a rate limit error (1008–1011). The
+//! request is then retried with the next key. Clones share the pool, so several clients and
+//! workloads can draw from the same keys.
+//!
+#![cfg_attr(feature = "blocking", doc = " ```rust")]
+#![cfg_attr(not(feature = "blocking"), doc = " ```rust,ignore")]
+//! use cmc::{CmcBuilder, KeyPool, Strategy};
+//!
+//! let pool = KeyPool::new(["<TEAM A KEY>", "<TEAM B KEY>"]).strategy(Strategy::MostCredits);
+//! let cmc = CmcBuilder::new(pool.clone()).build().unwrap();
+//!
+//! // Learn the credits left on each key, used by `Strategy::MostCredits`.
+//! if let Err(err) = cmc.refresh_key_credits() {
+//!     println!("{}", err);
+//! }
+//!
+//! match cmc.price("BTC") {
+//!     Ok(price) => println!("{}", price),
+//!     Err(err) => println!("{}", err), // `RateLimited` while all keys cool down
+//! }
+//!
+//! for usage in pool.usage() {
+//!     println!("{}: {} requests, {} credits", usage.key, usage.requests, usage.credits);
+//! }
+//! ```
+
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+#[cfg(any(feature = "blocking", feature = "async"))]
+use crate::api::CmcResult;
+#[cfg(any(feature = "blocking", feature = "async"))]
+use crate::errors::CmcErrors;
+#[cfg(any(feature = "blocking", feature = "async"))]
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[cfg(feature = "key")]
+use crate::api::key::KeyInfo;
+
+const DEFAULT_COOLDOWN: Duration = Duration::from_secs(60);
+
+/// How a [`KeyPool`] picks the key for the next request.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Strategy {
+    /// One key after the other.
+    #[default]
+    RoundRobin,
+    /// The key with the most credits left, as seeded by `refresh_key_credits()` and counted
+    /// down since. Keys with unknown credits come first.
+    MostCredits,
+}
+
+/// API keys shared by all clones. `Debug` prints the masked keys only.
+#[derive(Clone)]
+pub struct KeyPool {
+    state: Arc<Mutex<State>>,
+}
+
+struct State {
+    strategy: Strategy,
+    cooldown: Duration,
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    next: usize,
+    keys: Vec<Key>,
+}
+
+struct Key {
+    key: String,
+    requests: u64,
+    credits: u64,
+    rate_limited: u64,
+    credits_left: Option<u64>,
+    cooldown_until: Option<Instant>,
+}
+
+/// Usage report of one key of a [`KeyPool`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeyReport {
+    /// The key with all but its last 4 characters masked, and at least half of it.
+    pub key: String,
+    /// Requests sent with the key.
+    pub requests: u64,
+    /// Credits charged to the key.
+    pub credits: u64,
+    /// Times the key was put on cooldown.
+    pub rate_limited: u64,
+    /// Credits left, if known.
+    pub credits_left: Option<u64>,
+    /// Time until the key is used again, if it is on cooldown.
+    pub cooldown: Option<Duration>,
+}
+
+impl KeyPool {
+    /// Creates a round-robin pool of `keys`.
+    pub fn new<I, K>(keys: I) -> Self
+    where
+        I: IntoIterator<Item = K>,
+        K: Into<String>,
+    {
+        let keys = keys
+            .into_iter()
+            .map(|key| Key {
+                key: key.into(),
+                requests: 0,
+                credits: 0,
+                rate_limited: 0,
+                credits_left: None,
+                cooldown_until: None,
+            })
+            .collect();
+        let state = State {
+            strategy: Strategy::default(),
+            cooldown: DEFAULT_COOLDOWN,
+            #[cfg(any(feature = "blocking", feature = "async"))]
+            next: 0,
+            keys,
+        };
+        Self {
+            state: Arc::new(Mutex::new(state)),
+        }
+    }
+
+    /// How to pick the key for the next request.
+    pub fn strategy(self, strategy: Strategy) -> Self {
+        self.state.lock().unwrap().strategy = strategy;
+        self
+    }
+
+    /// Cooldown after HTTP 429 or the per-minute and IP rate limits (1008, 1011), 60 seconds by
+    /// default. Keys out of daily or monthly credits (1009, 1010) rest until the next UTC day.
+    pub fn cooldown(self, cooldown: Duration) -> Self {
+        self.state.lock().unwrap().cooldown = cooldown;
+        self
+    }
+
+    /// Number of keys.
+    pub fn len(&self) -> usize {
+        self.state.lock().unwrap().keys.len()
+    }
+
+    /// Returns `true` if the pool has no keys.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Usage per key, in the order the keys were given.
+    pub fn usage(&self) -> Vec<KeyReport> {
+        let now = Instant::now();
+        let state = self.state.lock().unwrap();
+        state
+            .keys
+            .iter()
+            .map(|key| KeyReport {
+                key: mask(&key.key),
+                requests: key.requests,
+                credits: key.credits,
+                rate_limited: key.rate_limited,
+                credits_left: key.credits_left,
+                cooldown: key
+                    .cooldown_until
+                    .filter(|until| *until > now)
+                    .map(|until| until - now),
+            })
+            .collect()
+    }
+
+    /// Sets the credits left on `key` as reported by `key_info()`.
+    #[cfg(feature = "key")]
+    pub fn seed(&self, key: &str, info: &KeyInfo) {
+        let month = info.usage.current_month.credits_left.max(0) as u64;
+        let left = match info.usage.current_day.credits_left {
+            Some(day) => month.min(day.max(0) as u64),
+            None => month,
+        };
+        let mut state = self.state.lock().unwrap();
+        for entry in state.keys.iter_mut().filter(|entry| entry.key == key) {
+            entry.credits_left = Some(left);
+        }
+    }
+
+    #[cfg(all(feature = "key", any(feature = "blocking", feature = "async")))]
+    pub(crate) fn keys(&self) -> Vec<String> {
+        let state = self.state.lock().unwrap();
+        state.keys.iter().map(|key| key.key.clone()).collect()
+    }
+
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    pub(crate) fn validate(&self) -> bool {
+        let state = self.state.lock().unwrap();
+        !state.keys.is_empty() && state.keys.iter().all(|key| !key.key.trim().is_empty())
+    }
+
+    /// Index and value of the key for the next request.
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    pub(crate) fn next(&self) -> CmcResult<(usize, String)> {
+        let now = Instant::now();
+        let mut state = self.state.lock().unwrap();
+        let len = state.keys.len();
+        let ready = |key: &Key| !matches!(key.cooldown_until, Some(until) if until > now);
+
+        let index = match state.strategy {
+            Strategy::RoundRobin => (0..len)
+                .map(|offset| (state.next + offset) % len)
+                .find(|&index| ready(&state.keys[index])),
+            Strategy::MostCredits => (0..len)
+                .filter(|&index| ready(&state.keys[index]))
+                .max_by_key(|&index| {
+                    (
+                        state.keys[index].credits_left.unwrap_or(u64::MAX),
+                        len - index,
+                    )
+                }),
+        };
+
+        match index {
+            Some(index) => {
+                state.next = (index + 1) % len;
+                let key = &mut state.keys[index];
+                key.cooldown_until = None;
+                Ok((index, key.key.clone()))
+            }
+            None => {
+                let retry = state
+                    .keys
+                    .iter()
+                    .filter_map(|key| key.cooldown_until)
+                    .min()
+                    .map(|until| until.saturating_duration_since(now))
+                    .unwrap_or_default();
+                Err(CmcErrors::RateLimited(retry))
+            }
+        }
+    }
+
+    /// Counts a response and the credits it charged to a key.
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    pub(crate) fn record(&self, index: usize, credits: u64) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(key) = state.keys.get_mut(index) {
+            key.requests += 1;
+            key.credits += credits;
+            key.credits_left = key.credits_left.map(|left| left.saturating_sub(credits));
+        }
+    }
+
+    /// Puts a key on cooldown if the error is a rate limit. Returns `true` if it did.
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    pub(crate) fn rate_limited(&self, index: usize, http_status: u16, error_code: i64) -> bool {
+        let cooldown = match error_code {
+            1009 | 1010 => until_next_day(),
+            1008 | 1011 => self.state.lock().unwrap().cooldown,
+            _ if http_status == 429 => self.state.lock().unwrap().cooldown,
+            _ => return false,
+        };
+        let mut state = self.state.lock().unwrap();
+        if let Some(key) = state.keys.get_mut(index) {
+            key.rate_limited += 1;
+            key.cooldown_until = Some(Instant::now() + cooldown);
+        }
+        true
+    }
+}
+
+impl fmt::Debug for KeyPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyPool")
+            .field("keys", &self.usage())
+            .finish()
+    }
+}
+
+impl From<&str> for KeyPool {
+    fn from(key: &str) -> Self {
+        KeyPool::new([key])
+    }
+}
+
+impl From<String> for KeyPool {
+    fn from(key: String) -> Self {
+        KeyPool::new([key])
+    }
+}
+
+impl From<&String> for KeyPool {
+    fn from(key: &String) -> Self {
+        KeyPool::new([key.as_str()])
+    }
+}
+
+impl<K: Into<String>> From<Vec<K>> for KeyPool {
+    fn from(keys: Vec<K>) -> Self {
+        KeyPool::new(keys)
+    }
+}
+
+impl<K: Into<String>, const N: usize> From<[K; N]> for KeyPool {
+    fn from(keys: [K; N]) -> Self {
+        KeyPool::new(keys)
+    }
+}
+
+/// Masks all but the last 4 characters of a key, and always at least half of it.
+fn mask(key: &str) -> String {
+    let chars: Vec<char> = key.chars().collect();
+    let hidden = chars.len() - (chars.len() / 2).min(4);
+    chars
+        .iter()
+        .enumerate()
+        .map(|(i, c)| if i < hidden { '*' } else { *c })
+        .collect()
+}
+
+#[cfg(any(feature = "blocking", feature = "async"))]
+fn until_next_day() -> Duration {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    Duration::from_secs(86_400 - secs % 86_400)
+}
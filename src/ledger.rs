@@ -0,0 +1,297 @@
+//! Credit accounting for API calls.
+//!
+//! Every response reports the credits it was charged in `status.credit_count`. A [`Ledger`]
+//! sums them per endpoint and per UTC day, and can enforce a hard daily or monthly budget:
+//! once it is spent, calls fail with [`CmcErrors::BudgetExceeded`] before a request is sent.
+//! Cached reference data does not count, as it is not requested again.
+//!
+//! A request in flight holds the credits its endpoint was last charged (one if unknown) against
+//! the budget, so concurrent calls cannot all pass when only one credit is left. Free requests,
+//! like `key_info()`, still go through once the budget is spent. The API only
+//! reports the real charge in the response, so a call charged more than that can still end
+//! above the budget by the difference.
+//!
+#![cfg_attr(feature = "blocking", doc = " ```rust")]
+#![cfg_attr(not(feature = "blocking"), doc = " ```rust,ignore")]
+//! use cmc::{CmcBuilder, Ledger};
+//!
+//! let ledger = Ledger::new().daily_budget(300);
+//! let cmc = CmcBuilder::new("<API KEY>").ledger(ledger.clone()).build().unwrap();
+//!
+//! // Count the credits already used today and this month by other processes.
+//! if let Ok(info) = cmc.key_info() {
+//!     ledger.seed(&info);
+//! }
+//!
+//! match cmc.price("BTC") {
+//!     Ok(price) => println!("{}", price),
+//!     Err(err) => println!("{}", err), // `BudgetExceeded` once 300 credits are spent today
+//! }
+//!
+//! for (endpoint, credits) in ledger.per_endpoint() {
+//!     println!("{}: {}", endpoint, credits);
+//! }
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[cfg(any(feature = "blocking", feature = "async"))]
+use crate::api::CmcResult;
+#[cfg(any(feature = "blocking", feature = "async"))]
+use crate::errors::CmcErrors;
+#[cfg(any(feature = "blocking", feature = "async"))]
+use serde_json::Value;
+
+#[cfg(feature = "key")]
+use crate::api::key::KeyInfo;
+
+/// Key info requests do not cost credits.
+#[cfg(any(feature = "blocking", feature = "async"))]
+const FREE_ENDPOINT: &str = "v1/key/info";
+
+/// Calendar day in UTC.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Day {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl Day {
+    /// Current day in UTC.
+    pub fn today() -> Self {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        Self::from_days((secs / 86_400) as i64)
+    }
+
+    /// Civil date of a day count since 1970-01-01.
+    fn from_days(days: i64) -> Self {
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z.rem_euclid(146_097);
+        let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = (yoe + era * 400 + i64::from(month <= 2)) as i32;
+        Day { year, month, day }
+    }
+
+    fn same_month(&self, other: &Day) -> bool {
+        self.year == other.year && self.month == other.month
+    }
+}
+
+impl Display for Day {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// Credits spent by a client, shared by all its clones.
+#[derive(Clone, Debug, Default)]
+pub struct Ledger {
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    daily_budget: Option<u64>,
+    monthly_budget: Option<u64>,
+    per_endpoint: BTreeMap<String, u64>,
+    per_day: BTreeMap<Day, u64>,
+    /// Last charge per endpoint path, used as the estimate of the next one.
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    last_charge: BTreeMap<String, u64>,
+    /// Credits held by requests in flight.
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    reserved: u64,
+    /// Credits used outside this ledger, as reported by `key_info()`.
+    seed_day: Option<(Day, u64)>,
+    seed_month: Option<(Day, u64)>,
+}
+
+impl State {
+    fn spent_on(&self, today: Day) -> u64 {
+        let seed = match self.seed_day {
+            Some((day, credits)) if day == today => credits,
+            _ => 0,
+        };
+        seed + self.per_day.get(&today).copied().unwrap_or_default()
+    }
+
+    fn spent_in_month_of(&self, today: Day) -> u64 {
+        let seed = match self.seed_month {
+            Some((day, credits)) if day.same_month(&today) => credits,
+            _ => 0,
+        };
+        seed + self.recorded_in_month_of(today)
+    }
+
+    fn recorded_in_month_of(&self, today: Day) -> u64 {
+        self.per_day
+            .iter()
+            .filter(|(day, _)| day.same_month(&today))
+            .map(|(_, credits)| credits)
+            .sum()
+    }
+}
+
+impl Ledger {
+    /// Creates an empty ledger without a budget.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Hard limit of credits per UTC day, including seeded usage.
+    pub fn daily_budget(self, credits: u64) -> Self {
+        self.state.lock().unwrap().daily_budget = Some(credits);
+        self
+    }
+
+    /// Hard limit of credits per calendar month, including seeded usage.
+    pub fn monthly_budget(self, credits: u64) -> Self {
+        self.state.lock().unwrap().monthly_budget = Some(credits);
+        self
+    }
+
+    /// Counts the credits used today and this month as reported by `key_info()`, so usage by
+    /// other clients and processes is included. Budgets that are not set explicitly are taken
+    /// from the plan allowance (`credits_used + credits_left`).
+    #[cfg(feature = "key")]
+    pub fn seed(&self, info: &KeyInfo) {
+        let today = Day::today();
+        let mut state = self.state.lock().unwrap();
+
+        let day = &info.usage.current_day;
+        let day_used = day.credits_used.max(0) as u64;
+        let recorded = state.per_day.get(&today).copied().unwrap_or_default();
+        state.seed_day = Some((today, day_used.saturating_sub(recorded)));
+        if state.daily_budget.is_none() {
+            if let Some(left) = day.credits_left {
+                state.daily_budget = Some(day_used + left.max(0) as u64);
+            }
+        }
+
+        let month = &info.usage.current_month;
+        let month_used = month.credits_used.max(0) as u64;
+        let recorded = state.recorded_in_month_of(today);
+        state.seed_month = Some((today, month_used.saturating_sub(recorded)));
+        if state.monthly_budget.is_none() {
+            state.monthly_budget = Some(month_used + month.credits_left.max(0) as u64);
+        }
+    }
+
+    /// Credits recorded by this ledger.
+    pub fn total(&self) -> u64 {
+        self.state.lock().unwrap().per_endpoint.values().sum()
+    }
+
+    /// Credits recorded per endpoint path. Example: "v2/cryptocurrency/quotes/latest"
+    pub fn per_endpoint(&self) -> BTreeMap<String, u64> {
+        self.state.lock().unwrap().per_endpoint.clone()
+    }
+
+    /// Credits recorded per UTC day.
+    pub fn per_day(&self) -> BTreeMap<Day, u64> {
+        self.state.lock().unwrap().per_day.clone()
+    }
+
+    /// Credits spent today, including seeded usage.
+    pub fn spent_today(&self) -> u64 {
+        self.state.lock().unwrap().spent_on(Day::today())
+    }
+
+    /// Credits spent this month, including seeded usage.
+    pub fn spent_this_month(&self) -> u64 {
+        self.state.lock().unwrap().spent_in_month_of(Day::today())
+    }
+
+    /// Credits left before a budget is hit, or `None` without a budget.
+    pub fn remaining(&self) -> Option<u64> {
+        let today = Day::today();
+        let state = self.state.lock().unwrap();
+        let daily = state
+            .daily_budget
+            .map(|budget| budget.saturating_sub(state.spent_on(today)));
+        let monthly = state
+            .monthly_budget
+            .map(|budget| budget.saturating_sub(state.spent_in_month_of(today)));
+        match (daily, monthly) {
+            (Some(daily), Some(monthly)) => Some(daily.min(monthly)),
+            (daily, monthly) => daily.or(monthly),
+        }
+    }
+
+    /// Holds the expected credits of a request to `endpoint` against the budgets until the
+    /// returned reservation is dropped. Fails if they do not fit, so the request is not sent.
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    pub(crate) fn reserve(&self, endpoint: &str) -> CmcResult<Reservation> {
+        let today = Day::today();
+        let mut state = self.state.lock().unwrap();
+        let credits = match endpoint {
+            FREE_ENDPOINT => 0,
+            _ => state.last_charge.get(endpoint).copied().unwrap_or(1),
+        };
+        if credits == 0 {
+            return Ok(Reservation {
+                ledger: self.clone(),
+                credits,
+            });
+        }
+        let pending = state.reserved + credits;
+        if let Some(budget) = state.daily_budget {
+            let spent = state.spent_on(today);
+            if spent + pending > budget {
+                return Err(CmcErrors::BudgetExceeded(spent, budget));
+            }
+        }
+        if let Some(budget) = state.monthly_budget {
+            let spent = state.spent_in_month_of(today);
+            if spent + pending > budget {
+                return Err(CmcErrors::BudgetExceeded(spent, budget));
+            }
+        }
+        state.reserved += credits;
+        Ok(Reservation {
+            ledger: self.clone(),
+            credits,
+        })
+    }
+
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    pub(crate) fn record(&self, endpoint: &str, credits: u64) {
+        let mut state = self.state.lock().unwrap();
+        *state.per_endpoint.entry(endpoint.to_owned()).or_default() += credits;
+        *state.per_day.entry(Day::today()).or_default() += credits;
+        state.last_charge.insert(endpoint.to_owned(), credits);
+    }
+
+    /// Records the `status.credit_count` of a response body.
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    pub(crate) fn record_body(&self, endpoint: &str, body: &Value) {
+        let credits = body["status"]["credit_count"].as_u64().unwrap_or_default();
+        self.record(endpoint, credits);
+    }
+}
+
+/// Credits held for a request in flight, released on drop.
+#[cfg(any(feature = "blocking", feature = "async"))]
+pub(crate) struct Reservation {
+    ledger: Ledger,
+    credits: u64,
+}
+
+#[cfg(any(feature = "blocking", feature = "async"))]
+impl Drop for Reservation {
+    fn drop(&mut self) {
+        self.ledger.state.lock().unwrap().reserved -= self.credits;
+    }
+}
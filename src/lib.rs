@@ -4,7 +4,8 @@
 //!
 //! ## CoinMarketCap ID Map
 //! **NOTE**: `CoinMarketCap recommend utilizing CMC ID instead of cryptocurrency symbols to securely identify cryptocurrencies with other endpoints and in your own application logic.`
-//!```rust
+#![cfg_attr(feature = "blocking", doc = "```rust")]
+#![cfg_attr(not(feature = "blocking"), doc = "```rust,ignore")]
 //! use cmc::{Cmc, Sort};
 //!
 //! let cmc = Cmc::new("<API KEY>");
@@ -16,7 +17,8 @@
 //!```
 //!
 //! ## Price cryptocurrency
-//!```rust
+#![cfg_attr(feature = "blocking", doc = "```rust")]
+#![cfg_attr(not(feature = "blocking"), doc = "```rust,ignore")]
 //! use cmc::Cmc;
 //!
 //! let cmc = Cmc::new("<API KEY>");
@@ -28,13 +30,14 @@
 //!```
 //!
 //! ## Price with custom settings
-//!```rust
+#![cfg_attr(feature = "blocking", doc = "```rust")]
+#![cfg_attr(not(feature = "blocking"), doc = "```rust,ignore")]
 //! use cmc::{CmcBuilder, Pass};
 //!
 //! let cmc = CmcBuilder::new("<API KEY>")
 //!     .pass(Pass::Id)
 //!     .convert("EUR")
-//!     .build();
+//!     .build().unwrap();
 //!
 //! match cmc.price("1027") { // 1027 is Ethereum id.
 //!     Ok(price) => println!("Price: {}", price), // In Euro instead default USD
@@ -43,26 +46,53 @@
 //!```
 //!
 //! ## Price conversion
-//!```rust
+#![cfg_attr(feature = "blocking", doc = "```rust")]
+#![cfg_attr(not(feature = "blocking"), doc = "```rust,ignore")]
 //! use cmc::Cmc;
 //!
 //! let cmc = Cmc::new("<API KEY>");
 //!
 //! // 2.5 BTC in EUR (using symbols)
-//! match cmc.price_conversion(2.5, "BTC", None, "EUR") {
+//! match cmc.price_conversion(2.5, "BTC", None::<i64>, "EUR") {
 //!     Ok(price) => println!("Total price: {}", price),
 //!     Err(err) => println!("Error: {}", err),
 //! }
 //!
 //! // 1.6 ETH in XMR (using id's)
-//! match cmc.price_conversion_id(1.6, "1027", None, "328") {
+//! match cmc.price_conversion_id(1.6, "1027", None::<i64>, "328") {
 //!     Ok(price) => println!("Total price: {}", price),
 //!     Err(err) => println!("Error: {}", err),
 //! }
 //!```
 //!
+//! ## Per-call requests
+//! Lookup and conversion options can also be set per call, independently of the builder:
+#![cfg_attr(feature = "blocking", doc = "```rust")]
+#![cfg_attr(not(feature = "blocking"), doc = "```rust,ignore")]
+//! use cmc::{Cmc, ExchangeId};
+//!
+//! let cmc = Cmc::new("<API KEY>");
+//!
+//! match cmc.quotes().by_slug("bitcoin").convert("EUR").send() {
+//!     Ok(quotes) => println!("{}", quotes.data.len()),
+//!     Err(err) => println!("Error: {}", err),
+//! }
+//!
+//! match cmc.exchange_info().by_id(ExchangeId(270)).send() {
+//!     Ok(metadata) => println!("{}", metadata.data["270"].name),
+//!     Err(err) => println!("Error: {}", err),
+//! }
+//!
+//! // Credits spent and API notices come with `send_with_status()`.
+//! match cmc.global_quotes().send_with_status() {
+//!     Ok(global) => println!("{} credits, {:?}", global.status.credit_count, global.status.notice),
+//!     Err(err) => println!("Error: {}", err),
+//! }
+//!```
+//!
 //! ## Exchange ID Map
-//!```rust
+#![cfg_attr(feature = "blocking", doc = "```rust")]
+#![cfg_attr(not(feature = "blocking"), doc = "```rust,ignore")]
 //! use cmc::{Cmc, ListingStatusExchange, SortExchange};
 //!
 //! let cmc = Cmc::new("<API KEY>");
@@ -72,11 +102,30 @@
 //!     Err(err) => println!("{}", err),
 //! }
 //!```
+//!
+//! ## Reference data cache
+//! ID maps and metadata change rarely, so they can be kept on disk between runs:
+#![cfg_attr(feature = "blocking", doc = "```rust")]
+#![cfg_attr(not(feature = "blocking"), doc = "```rust,ignore")]
+//! use cmc::{CmcBuilder, RefCache, Sort};
+//! use std::time::Duration;
+//!
+//! let cmc = CmcBuilder::new("<API KEY>")
+//!     .cache(RefCache::new("/tmp/cmc-cache").max_age(Duration::from_secs(86400)))
+//!     .build().unwrap();
+//!
+//! match cmc.id_map(1, 50, Sort::CmcRank) {
+//!     Ok(map) => println!("{}", map), // Downloaded at most once a day
+//!     Err(err) => println!("{}", err),
+//! }
+//!```
 //! [coinmarketcap]: https://coinmarketcap.com/api/
 //!
 //! ## Crate Features
 //! This crate supports default features:
 //!
+//! - `blocking`
+//! - `native-tls`
 //! - `cryptocurrency`
 //! - `exchange`
 //! - `fiat`
@@ -87,7 +136,47 @@
 //! Disable all functions except the necessary ones:
 //!```toml
 //! [dependencies]
-//! cmc = { version = "0.4.0", default-features = false, features = ["cryptocurrency"] }
+//! cmc = { version = "0.4.0", default-features = false, features = ["blocking", "native-tls", "cryptocurrency"] }
+//!```
+//! ## Transport and TLS
+//! `blocking` provides [`Cmc`] and `async` provides `async_api::Cmc`; the endpoint modules
+//! compile with either. TLS uses the system library (`native-tls`) by default. Select `rustls-tls`
+//! instead to build without OpenSSL, for example for static musl binaries:
+//!```toml
+//! [dependencies]
+//! cmc = { version = "0.4.0", default-features = false, features = ["async", "rustls-tls", "cryptocurrency"] }
+//!```
+//! ## Timestamps
+//! Timestamps are plain ISO 8601 strings. Enable the `chrono` feature to get them as
+//! `chrono::DateTime<Utc>` and to pass `DateTime`/`NaiveDate` values to historical parameters:
+//! ```toml
+//! [dependencies]
+//! cmc = { version = "0.4.0", features = ["chrono"] }
+//! ```
+//! ## Decimal amounts
+//! Prices, market caps, volumes and conversion amounts are `f64`. Enable the `decimal` feature to
+//! get them as `rust_decimal::Decimal`. It turns on serde_json's `arbitrary_precision`, so amounts
+//! are read digit for digit from the JSON text and `0.1` stays `0.1`; an amount that does not fit
+//! a `Decimal` fails to deserialize instead of being rounded:
+//! ```toml
+//! [dependencies]
+//! cmc = { version = "0.4.0", features = ["decimal"] }
+//! ```
+//! ## Credits
+//! Each client counts the credits it spends per endpoint and per day, and can stop at a budget:
+#![cfg_attr(feature = "blocking", doc = "```rust")]
+#![cfg_attr(not(feature = "blocking"), doc = "```rust,ignore")]
+//! use cmc::{CmcBuilder, Ledger};
+//!
+//! let cmc = CmcBuilder::new("<API KEY>")
+//!     .ledger(Ledger::new().daily_budget(300))
+//!     .build().unwrap();
+//!
+//! match cmc.price("BTC") {
+//!     Ok(price) => println!("Price: {}", price),
+//!     Err(err) => println!("Error: {}", err), // `BudgetExceeded` after 300 credits today
+//! }
+//! println!("Credits spent today: {}", cmc.ledger().spent_today());
 //!```
 //! ## Async
 //! Asynchronous versions of functions are available through enabling the async feature:
@@ -96,24 +185,66 @@
 //! cmc = { version = "0.4.0", features = ["async"] }
 //! ```
 //! And then the code:
-//! ```rust
-//! #[tokio::main]
-//! async fn main() {
-//!     use cmc::async_api::Cmc;
+#![cfg_attr(feature = "async", doc = " ```rust,no_run")]
+#![cfg_attr(not(feature = "async"), doc = " ```rust,ignore")]
+//! use cmc::async_api::Cmc;
 //!
-//!     let cmc = Cmc::new("<API KEY>");
+//! # async fn run() {
+//! let cmc = Cmc::new("<API KEY>");
 //!
-//!     match cmc.price("BTC").await {
-//!         Ok(price) => println!("Price: {}", price),
-//!         Err(err) => println!("Error: {}", err),
-//!     }
+//! match cmc.price("BTC").await {
+//!     Ok(price) => println!("Price: {}", price),
+//!     Err(err) => println!("Error: {}", err),
 //! }
+//! # }
 //! ```
+//! The futures run on a Tokio runtime, e.g. in an `async fn main()` marked `#[tokio::main]`.
+//! Both clients are generated from the same code and offer the same methods.
 
+#[cfg(feature = "cryptocurrency")]
+pub mod alerts;
 pub mod api;
-#[cfg(any(feature = "async", doc))]
+#[cfg(any(feature = "async", all(doc, feature = "blocking")))]
 pub mod async_api;
+#[cfg(all(feature = "async", feature = "cryptocurrency"))]
+mod batch;
+pub mod cache;
+#[cfg(any(feature = "blocking", feature = "async"))]
+mod client;
 pub mod errors;
+#[cfg(feature = "async")]
+mod flight;
+pub mod ids;
+pub mod keys;
+pub mod ledger;
+#[cfg(feature = "cryptocurrency")]
+pub mod portfolio;
+#[cfg(feature = "cryptocurrency")]
+pub mod rates;
+pub mod request;
+#[cfg(feature = "cryptocurrency")]
+pub mod resolver;
+pub mod transport;
+#[cfg(feature = "cryptocurrency")]
+pub mod watch;
 
+#[cfg(feature = "blocking")]
+#[doc(inline)]
+pub use self::api::{Cmc, CmcBuilder};
+#[doc(inline)]
+pub use self::api::{
+    Environment, ListingStatus, ListingStatusExchange, Pass, Sort, SortExchange, SortFiat,
+};
+#[doc(inline)]
+pub use self::cache::RefCache;
+#[doc(inline)]
+pub use self::ids::{
+    AssetRef, CategoryId, ContractAddress, CryptoId, CurrencyId, ExchangeId, FiatId, Slug, Symbol,
+};
+#[doc(inline)]
+pub use self::keys::{KeyPool, Strategy};
+#[doc(inline)]
+pub use self::ledger::Ledger;
+#[cfg(feature = "cryptocurrency")]
 #[doc(inline)]
-pub use self::api::{Cmc, CmcBuilder, ListingStatusExchange, Pass, Sort, SortExchange, SortFiat};
+pub use self::resolver::Resolver;
@@ -0,0 +1,296 @@
+//! Portfolio valuation.
+//!
+//! A [`Portfolio`] is a list of holdings. `value()` prices it with the latest quotes, fetched
+//! with one `quotes_latest_*` call per kind of reference in use (ids, slugs, symbols), and
+//! derives the 1h, 24h and 7d profit and loss from the `percent_change_*` fields. `value_at()`
+//! prices it at a past point in time through `price_conversion` with its `time` parameter, once
+//! per cryptocurrency held.
+//!
+//! Symbols are not unique on CoinMarketCap. A symbol held in a portfolio that matches several
+//! cryptocurrencies fails with [`CmcErrors::Ambiguous`], hold it by id or slug instead.
+//!
+#![cfg_attr(feature = "blocking", doc = " ```rust,no_run")]
+#![cfg_attr(not(feature = "blocking"), doc = " ```rust,ignore")]
+//! use cmc::api::Amount;
+//! use cmc::portfolio::Portfolio;
+//! use cmc::{Cmc, CryptoId, Slug};
+//!
+//! let cmc = Cmc::new("<API KEY>");
+//!
+//! let portfolio = Portfolio::new()
+//!     .hold(Slug::new("bitcoin"), "0.5".parse().unwrap())
+//!     .hold(CryptoId(1027), Amount::from(3))
+//!     .convert("EUR");
+//!
+//! match cmc.value(&portfolio) {
+//!     Ok(valuation) => println!("{}", valuation),
+//!     Err(err) => println!("{}", err),
+//! }
+//!
+//! match cmc.value_at(&portfolio, "2024-01-01") {
+//!     Ok(valuation) => println!("{}", valuation.total),
+//!     Err(err) => println!("{}", err),
+//! }
+//! ```
+
+use crate::api::Amount;
+use crate::ids::{AssetRef, CryptoId};
+use std::fmt;
+
+#[cfg(any(feature = "blocking", feature = "async"))]
+use crate::api::cryptocurrency::quotes_latest_v2::{single, CryptoCurrency};
+#[cfg(any(feature = "blocking", feature = "async"))]
+use crate::api::{CmcResult, Config};
+#[cfg(any(feature = "blocking", feature = "async"))]
+use crate::errors::CmcErrors;
+#[cfg(any(feature = "blocking", feature = "async"))]
+use crate::ids::{Slug, Symbol};
+#[cfg(any(feature = "blocking", feature = "async"))]
+use crate::request::first;
+#[cfg(any(feature = "blocking", feature = "async"))]
+use std::collections::HashMap;
+
+/// Amounts held per asset.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Portfolio {
+    holdings: Vec<(AssetRef, Amount)>,
+    convert: Option<String>,
+}
+
+impl Portfolio {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `amount` of `asset`. Amounts of the same reference add up.
+    pub fn hold<A: Into<AssetRef>>(mut self, asset: A, amount: Amount) -> Self {
+        let asset = asset.into();
+        match self.holdings.iter_mut().find(|(held, _)| *held == asset) {
+            Some((_, held)) => *held += amount,
+            None => self.holdings.push((asset, amount)),
+        }
+        self
+    }
+
+    /// Fiat or cryptocurrency symbol to value the portfolio in, instead of the client default.
+    /// Example: "EUR"
+    pub fn convert<T: Into<String>>(mut self, currency: T) -> Self {
+        self.convert = Some(currency.into().to_uppercase());
+        self
+    }
+
+    /// Holdings in the order they were added.
+    pub fn holdings(&self) -> &[(AssetRef, Amount)] {
+        &self.holdings
+    }
+}
+
+#[cfg(any(feature = "blocking", feature = "async"))]
+impl Portfolio {
+    /// `convert` parameter of the requests, if it overrides the client default.
+    pub(crate) fn convert_param(&self) -> Option<(&'static str, String)> {
+        self.convert.clone().map(|convert| ("convert", convert))
+    }
+
+    /// Currency the quotes are keyed by.
+    pub(crate) fn currency(&self, config: &Config) -> String {
+        first(&config.convert_param(self.convert_param()).1)
+    }
+
+    /// Holdings split by kind of reference.
+    pub(crate) fn group(&self) -> CmcResult<(Vec<CryptoId>, Vec<Slug>, Vec<Symbol>)> {
+        let (mut ids, mut slugs, mut symbols) = (Vec::new(), Vec::new(), Vec::new());
+        for (asset, _) in &self.holdings {
+            match asset {
+                AssetRef::Id(id) => ids.push(*id),
+                AssetRef::Slug(slug) => slugs.push(slug.clone()),
+                AssetRef::Symbol(symbol) => symbols.push(symbol.clone()),
+                AssetRef::Address(_) => return Err(CmcErrors::PassIncompatible),
+            }
+        }
+        Ok((ids, slugs, symbols))
+    }
+
+    /// Values the holdings with the latest quotes.
+    pub(crate) fn valuation(&self, currency: &str, quotes: &Quotes) -> CmcResult<Valuation> {
+        let mut assets = Vec::new();
+        for (asset, amount) in &self.holdings {
+            let crypto = quotes.get(asset)?;
+            let quote = crypto.quote.get(currency).ok_or(CmcErrors::NullAnswer)?;
+            let price = quote.price.ok_or(CmcErrors::NullAnswer)?;
+            let value = price * *amount;
+            assets.push(AssetValue {
+                asset: asset.clone(),
+                id: CryptoId(crypto.id as u64),
+                symbol: crypto.symbol.clone(),
+                amount: *amount,
+                price,
+                value,
+                allocation: Amount::default(),
+                pnl_1h: pnl(value, quote.percent_change_1h),
+                pnl_24h: pnl(value, quote.percent_change_24h),
+                pnl_7d: pnl(value, quote.percent_change_7d),
+            });
+        }
+        Ok(Valuation::new(currency, assets))
+    }
+
+    /// Id and symbol of each holding.
+    #[cfg(feature = "tools")]
+    pub(crate) fn resolve(&self, quotes: &Quotes) -> CmcResult<Vec<(CryptoId, String)>> {
+        self.holdings
+            .iter()
+            .map(|(asset, _)| {
+                let crypto = quotes.get(asset)?;
+                Ok((CryptoId(crypto.id as u64), crypto.symbol.clone()))
+            })
+            .collect()
+    }
+
+    /// Values the holdings of `resolve()` with the unit price of each cryptocurrency.
+    #[cfg(feature = "tools")]
+    pub(crate) fn valuation_at(
+        &self,
+        currency: &str,
+        resolved: Vec<(CryptoId, String)>,
+        prices: &HashMap<CryptoId, Amount>,
+    ) -> Valuation {
+        let assets = self
+            .holdings
+            .iter()
+            .zip(resolved)
+            .map(|((asset, amount), (id, symbol))| {
+                let price = prices[&id];
+                AssetValue {
+                    asset: asset.clone(),
+                    id,
+                    symbol,
+                    amount: *amount,
+                    price,
+                    value: price * *amount,
+                    allocation: Amount::default(),
+                    pnl_1h: None,
+                    pnl_24h: None,
+                    pnl_7d: None,
+                }
+            })
+            .collect();
+        Valuation::new(currency, assets)
+    }
+}
+
+/// Quotes of a portfolio's assets, by the reference used to fetch them.
+#[cfg(any(feature = "blocking", feature = "async"))]
+#[derive(Debug, Default)]
+pub(crate) struct Quotes {
+    pub(crate) by_id: HashMap<String, CryptoCurrency>,
+    pub(crate) by_slug: HashMap<String, CryptoCurrency>,
+    pub(crate) by_symbol: HashMap<String, Vec<CryptoCurrency>>,
+}
+
+#[cfg(any(feature = "blocking", feature = "async"))]
+impl Quotes {
+    /// Quote of one holding. A symbol shared by several cryptocurrencies is ambiguous.
+    fn get(&self, asset: &AssetRef) -> CmcResult<&CryptoCurrency> {
+        let found = match asset {
+            AssetRef::Id(id) => self.by_id.get(&id.to_string()),
+            AssetRef::Slug(slug) => self.by_slug.values().find(|cc| cc.slug == slug.as_str()),
+            AssetRef::Symbol(symbol) => {
+                return single(symbol.as_str(), self.by_symbol.get(symbol.as_str()))
+            }
+            AssetRef::Address(_) => None,
+        };
+        found.ok_or_else(|| CmcErrors::NotFound(asset.to_string()))
+    }
+}
+
+/// Value of one holding.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AssetValue {
+    /// The reference the asset was held by.
+    pub asset: AssetRef,
+    pub id: CryptoId,
+    pub symbol: String,
+    pub amount: Amount,
+    /// Price of one unit.
+    pub price: Amount,
+    /// `price * amount`.
+    pub value: Amount,
+    /// Share of the total value in percent.
+    pub allocation: Amount,
+    /// Change in value over the last hour, if known. `None` for historical valuations.
+    pub pnl_1h: Option<Amount>,
+    /// Change in value over the last 24 hours, if known.
+    pub pnl_24h: Option<Amount>,
+    /// Change in value over the last 7 days, if known.
+    pub pnl_7d: Option<Amount>,
+}
+
+/// Value of a portfolio.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Valuation {
+    /// Currency of all values.
+    pub currency: String,
+    pub total: Amount,
+    /// Holdings in the order they were added.
+    pub assets: Vec<AssetValue>,
+    /// Sum of the 1h changes of the holdings that have one.
+    pub pnl_1h: Option<Amount>,
+    pub pnl_24h: Option<Amount>,
+    pub pnl_7d: Option<Amount>,
+}
+
+#[cfg(any(feature = "blocking", feature = "async"))]
+impl Valuation {
+    fn new(currency: &str, mut assets: Vec<AssetValue>) -> Self {
+        let total = assets
+            .iter()
+            .fold(Amount::default(), |total, asset| total + asset.value);
+        if total != Amount::default() {
+            for asset in &mut assets {
+                asset.allocation = asset.value * Amount::from(100) / total;
+            }
+        }
+        let sum = |pnl: fn(&AssetValue) -> Option<Amount>| {
+            assets.iter().filter_map(pnl).reduce(|sum, pnl| sum + pnl)
+        };
+        Self {
+            currency: currency.to_owned(),
+            total,
+            pnl_1h: sum(|asset| asset.pnl_1h),
+            pnl_24h: sum(|asset| asset.pnl_24h),
+            pnl_7d: sum(|asset| asset.pnl_7d),
+            assets,
+        }
+    }
+}
+
+impl fmt::Display for Valuation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for asset in &self.assets {
+            writeln!(
+                f,
+                "{}: {} x {} = {} {} ({:.2}%)",
+                asset.symbol,
+                asset.amount,
+                asset.price,
+                asset.value,
+                self.currency,
+                asset.allocation
+            )?;
+        }
+        write!(f, "Total: {} {}", self.total, self.currency)
+    }
+}
+
+/// Change of `value` since it was `percent_change` percent lower.
+#[cfg(any(feature = "blocking", feature = "async"))]
+fn pnl(value: Amount, percent_change: Option<f64>) -> Option<Amount> {
+    let change = Amount::try_from(percent_change?).ok()?;
+    let hundred = Amount::from(100);
+    if hundred + change == Amount::default() {
+        return None;
+    }
+    let before = value * hundred / (hundred + change);
+    Some(value - before)
+}
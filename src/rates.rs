@@ -0,0 +1,195 @@
+//! Offline cross rates.
+//!
+//! A [`RateTable`] is built from one quotes snapshot of several cryptocurrencies, e.g. the
+//! response of `quotes_latest_by_id()`. It values every cryptocurrency and every quote currency
+//! of the snapshot in one base currency (USD if present) and converts between any two of them
+//! locally, triangulating through the base. Conversions cost no requests; the snapshot time
+//! tells how old the rates are.
+//!
+//! Currencies are symbols ("ETH", "EUR") or [`CryptoId`]s of the cryptocurrencies in the
+//! snapshot. The two never mix: a cryptocurrency whose symbol looks like an id does not shadow
+//! the id.
+//!
+#![cfg_attr(feature = "blocking", doc = " ```rust,no_run")]
+#![cfg_attr(not(feature = "blocking"), doc = " ```rust,ignore")]
+//! use cmc::api::Amount;
+//! use cmc::{CmcBuilder, CryptoId};
+//!
+//! let cmc = CmcBuilder::new("<API KEY>").convert("USD,EUR").build().unwrap();
+//!
+//! let table = cmc.rate_table([CryptoId(1), CryptoId(1027), CryptoId(825)]).unwrap();
+//! println!("Rates as of {}", table.timestamp());
+//!
+//! // ETH -> BTC, BTC -> EUR and EUR -> USDT, without further requests.
+//! let btc = table.convert(Amount::from(3), "ETH", "BTC").unwrap();
+//! let eur = table.convert(btc, "BTC", "EUR").unwrap();
+//! let usdt = table.convert(eur, "EUR", CryptoId(825)).unwrap();
+//! ```
+
+use crate::api::cryptocurrency::quotes_latest_v2::{CryptoCurrency, QLv2Id, QLv2Symbol};
+use crate::api::{Amount, CmcResult, Timestamp};
+use crate::errors::CmcErrors;
+use crate::ids::CryptoId;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+/// A currency of a [`RateTable`]: a cryptocurrency by id, or a cryptocurrency or quote currency
+/// by symbol. Strings are symbols.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Unit {
+    Id(CryptoId),
+    /// Uppercase symbol. Example: "EUR"
+    Symbol(String),
+}
+
+impl Display for Unit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Unit::Id(id) => write!(f, "{}", id),
+            Unit::Symbol(symbol) => f.write_str(symbol),
+        }
+    }
+}
+
+impl From<CryptoId> for Unit {
+    fn from(id: CryptoId) -> Self {
+        Unit::Id(id)
+    }
+}
+
+impl From<&str> for Unit {
+    fn from(symbol: &str) -> Self {
+        Unit::Symbol(symbol.to_uppercase())
+    }
+}
+
+impl From<String> for Unit {
+    fn from(symbol: String) -> Self {
+        Unit::Symbol(symbol.to_uppercase())
+    }
+}
+
+/// Cross rates of one quotes snapshot.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RateTable {
+    base: String,
+    timestamp: Timestamp,
+    /// Value of one unit in the base currency.
+    values: HashMap<Unit, Amount>,
+}
+
+impl RateTable {
+    /// Table of a `quotes_latest_by_id()` or `quotes_latest_by_slug()` response.
+    pub fn from_quotes(quotes: &QLv2Id) -> Self {
+        Self::new(quotes.data.values(), quotes.status.timestamp.to_owned())
+    }
+
+    /// Table of a `quotes_latest_by_symbol()` response.
+    pub fn from_symbol_quotes(quotes: &QLv2Symbol) -> Self {
+        Self::new(
+            quotes.data.values().flatten(),
+            quotes.status.timestamp.to_owned(),
+        )
+    }
+
+    /// Table of `quotes` taken at `timestamp`.
+    ///
+    /// Cryptocurrencies sharing a symbol are looked up by symbol as the one with the best rank;
+    /// the others stay reachable by id. A quote currency is valued with the best ranked
+    /// cryptocurrency quoted in both it and the base currency.
+    pub fn new<'a, I>(quotes: I, timestamp: Timestamp) -> Self
+    where
+        I: IntoIterator<Item = &'a CryptoCurrency>,
+    {
+        let mut quotes: Vec<_> = quotes.into_iter().collect();
+        quotes.sort_by_key(|crypto| (crypto.cmc_rank.unwrap_or(u64::MAX), crypto.id));
+
+        let base = if quotes.iter().any(|crypto| crypto.quote.contains_key("USD")) {
+            "USD".to_owned()
+        } else {
+            quotes
+                .iter()
+                .flat_map(|crypto| crypto.quote.keys())
+                .min()
+                .cloned()
+                .unwrap_or_else(|| "USD".to_owned())
+        };
+
+        let mut values = HashMap::new();
+        values.insert(Unit::from(base.as_str()), Amount::from(1));
+        for crypto in &quotes {
+            let Some(price) = crypto.quote.get(&base).and_then(|quote| quote.price) else {
+                continue;
+            };
+            values.insert(Unit::Id(CryptoId(crypto.id as u64)), price);
+            values
+                .entry(Unit::from(crypto.symbol.as_str()))
+                .or_insert(price);
+            for (currency, quote) in &crypto.quote {
+                match quote.price {
+                    Some(other) if other != Amount::default() => {
+                        values
+                            .entry(Unit::from(currency.as_str()))
+                            .or_insert(price / other);
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        Self {
+            base,
+            timestamp,
+            values,
+        }
+    }
+
+    /// Adds or replaces the value of one unit of `currency` in the base currency, e.g. a fiat
+    /// rate from another source.
+    pub fn with_rate<T: Into<Unit>>(mut self, currency: T, value: Amount) -> Self {
+        self.values.insert(currency.into(), value);
+        self
+    }
+
+    /// Currency all rates go through. Example: "USD"
+    pub fn base(&self) -> &str {
+        &self.base
+    }
+
+    /// Time of the snapshot.
+    pub fn timestamp(&self) -> &Timestamp {
+        &self.timestamp
+    }
+
+    /// Symbols and ids the table can convert.
+    pub fn currencies(&self) -> impl Iterator<Item = &Unit> {
+        self.values.keys()
+    }
+
+    /// Units of `to` one unit of `from` is worth. Currencies are symbols ("ETH", "EUR") or
+    /// ids of the cryptocurrencies in the snapshot (`CryptoId(1027)`).
+    pub fn rate<F: Into<Unit>, T: Into<Unit>>(&self, from: F, to: T) -> CmcResult<Amount> {
+        let to = self.value(to.into())?;
+        if to == Amount::default() {
+            return Err(CmcErrors::NullAnswer);
+        }
+        Ok(self.value(from.into())? / to)
+    }
+
+    /// Converts `amount` of `from` into `to`.
+    pub fn convert<F: Into<Unit>, T: Into<Unit>>(
+        &self,
+        amount: Amount,
+        from: F,
+        to: T,
+    ) -> CmcResult<Amount> {
+        Ok(amount * self.rate(from, to)?)
+    }
+
+    fn value(&self, currency: Unit) -> CmcResult<Amount> {
+        self.values
+            .get(&currency)
+            .copied()
+            .ok_or_else(|| CmcErrors::NotFound(currency.to_string()))
+    }
+}
@@ -0,0 +1,958 @@
+//! Per-call request builders.
+//!
+//! A builder carries all lookup and conversion options of one call, so a single client can mix
+//! ids, slugs and symbols without depending on the builder-wide [`Pass`][crate::Pass] or
+//! [`convert()`][crate::CmcBuilder::convert]. Options that are not set fall back to the client
+//! configuration. The blocking and the async client return the same builders, only `send()`
+//! differs.
+//!
+#![cfg_attr(feature = "blocking", doc = " ```rust")]
+#![cfg_attr(not(feature = "blocking"), doc = " ```rust,ignore")]
+//! use cmc::{Cmc, ExchangeId, Slug};
+//!
+//! let cmc = Cmc::new("<API KEY>");
+//!
+//! match cmc.quotes().by_slug("bitcoin").convert("EUR").send() {
+//!     Ok(quotes) => println!("{}", quotes.data.len()),
+//!     Err(err) => println!("{}", err),
+//! }
+//!
+//! // No `Pass::Slug` needed.
+//! match cmc.exchange_info().by_slug(Slug::new("binance")).send() {
+//!     Ok(metadata) => println!("{}", metadata.data["binance"].name),
+//!     Err(err) => println!("{}", err),
+//! }
+//! ```
+
+#[cfg(feature = "cryptocurrency")]
+use crate::api::cryptocurrency::{
+    Category, CmcCategories, CmcCategory, CmcIdMap, MDv2, MDv2Symbol, Metadata, QLv2Id, QLv2Slug,
+    QLv2Symbol,
+};
+#[cfg(feature = "exchange")]
+use crate::api::exchange::{CmcExchangeIdMap, ExchangeMetadata};
+#[cfg(feature = "fiat")]
+use crate::api::fiat::CmcFiatIdMap;
+#[cfg(feature = "global_metrics")]
+use crate::api::global_metrics::{CmcGlobalMetrics, GlobalMetrics};
+#[cfg(feature = "key")]
+use crate::api::key::{CmcKeyInfo, KeyInfo};
+#[cfg(feature = "tools")]
+use crate::api::tools::{ConversionResult, PCv2Id, PCv2Symbol};
+#[cfg(feature = "tools")]
+use crate::api::Amount;
+use crate::api::CmcResult;
+#[cfg(feature = "fiat")]
+use crate::api::SortFiat;
+#[cfg(feature = "cryptocurrency")]
+use crate::api::{ListingStatus, Sort};
+#[cfg(feature = "exchange")]
+use crate::api::{ListingStatusExchange, SortExchange};
+#[cfg(any(feature = "cryptocurrency", feature = "tools"))]
+use crate::errors::CmcErrors;
+#[cfg(any(feature = "cryptocurrency", feature = "tools"))]
+use crate::ids::AssetRef;
+#[cfg(feature = "exchange")]
+use crate::ids::ExchangeId;
+#[cfg(any(feature = "cryptocurrency", feature = "exchange"))]
+use crate::ids::Slug;
+#[cfg(feature = "cryptocurrency")]
+use crate::ids::{CategoryId, ContractAddress, CryptoId};
+use crate::ids::{CurrencyId, Identifiers, Symbol};
+#[cfg(any(feature = "cryptocurrency", feature = "tools"))]
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+#[cfg(feature = "cryptocurrency")]
+use std::collections::HashMap;
+#[cfg(any(feature = "cryptocurrency", feature = "tools"))]
+use std::marker::PhantomData;
+use std::time::Duration;
+
+/// Describes one endpoint: its path, query parameters and how the response is read.
+pub trait Endpoint {
+    type Output;
+
+    #[doc(hidden)]
+    const PATH: &'static str;
+
+    /// Responses are kept in the [`RefCache`][crate::RefCache], if one is configured.
+    #[doc(hidden)]
+    const CACHED: bool = false;
+
+    /// The endpoint takes a `convert` or `convert_id` parameter.
+    #[doc(hidden)]
+    const CONVERT: bool = false;
+
+    /// Rejects the call before it is sent.
+    #[doc(hidden)]
+    fn check(&self) -> CmcResult<()> {
+        Ok(())
+    }
+
+    #[doc(hidden)]
+    fn query(&self) -> Vec<(&'static str, String)>;
+
+    /// Reads the response body. `convert` is the target currency the request was sent with.
+    #[doc(hidden)]
+    fn parse(self, body: Value, convert: &str) -> CmcResult<Self::Output>;
+}
+
+/// Endpoints that accept a target currency.
+pub trait Convertible: Endpoint {}
+
+/// A call to one endpoint, sent with `send()`.
+#[must_use = "a call does nothing until it is sent"]
+#[derive(Clone, Debug)]
+#[cfg_attr(not(any(feature = "blocking", feature = "async")), allow(dead_code))]
+pub struct Call<'a, C, E> {
+    pub(crate) cmc: &'a C,
+    pub(crate) endpoint: E,
+    pub(crate) convert: Option<(&'static str, String)>,
+    pub(crate) timeout: Option<Duration>,
+}
+
+impl<'a, C, E> Call<'a, C, E> {
+    #[cfg(any(
+        feature = "cryptocurrency",
+        feature = "exchange",
+        feature = "fiat",
+        feature = "global_metrics",
+        feature = "key",
+        feature = "tools"
+    ))]
+    #[cfg_attr(not(any(feature = "blocking", feature = "async")), allow(dead_code))]
+    pub(crate) fn new(cmc: &'a C, endpoint: E) -> Self {
+        Call {
+            cmc,
+            endpoint,
+            convert: None,
+            timeout: None,
+        }
+    }
+
+    /// Time limit of this call, instead of the client default.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+}
+
+impl<C, E: Convertible> Call<'_, C, E> {
+    /// Fiat or cryptocurrency symbol(s) to convert to, instead of the client default. Example: "EUR"
+    pub fn convert<T: Identifiers<Symbol>>(mut self, currency: T) -> Self {
+        self.convert = Some(("convert", currency.to_query().to_uppercase()));
+        self
+    }
+
+    /// Fiat or cryptocurrency id(s) to convert to, instead of the client default. Example: `FiatId(2790)`
+    pub fn convert_id<T: Identifiers<CurrencyId>>(mut self, currency_id: T) -> Self {
+        self.convert = Some(("convert_id", currency_id.to_query()));
+        self
+    }
+}
+
+/// A point in time for historical parameters: an ISO 8601 string, a Unix timestamp in seconds,
+/// or with the `chrono` feature a `DateTime` or `NaiveDate`.
+pub trait TimeQuery {
+    #[doc(hidden)]
+    fn to_time(&self) -> String;
+}
+
+impl TimeQuery for str {
+    fn to_time(&self) -> String {
+        self.to_owned()
+    }
+}
+
+impl TimeQuery for String {
+    fn to_time(&self) -> String {
+        self.clone()
+    }
+}
+
+impl TimeQuery for i64 {
+    fn to_time(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl TimeQuery for u64 {
+    fn to_time(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl<T: TimeQuery + ?Sized> TimeQuery for &T {
+    fn to_time(&self) -> String {
+        (**self).to_time()
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl<Tz: chrono::TimeZone> TimeQuery for chrono::DateTime<Tz> {
+    fn to_time(&self) -> String {
+        self.with_timezone(&chrono::Utc)
+            .to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl TimeQuery for chrono::NaiveDate {
+    fn to_time(&self) -> String {
+        self.format("%Y-%m-%d").to_string()
+    }
+}
+
+/// An amount to convert: any primitive number, or with the `decimal` feature a `Decimal`.
+pub trait AmountQuery {
+    #[doc(hidden)]
+    fn to_amount(&self) -> String;
+}
+
+macro_rules! amount_query {
+    ($($ty:ty),*) => {
+        $(
+            impl AmountQuery for $ty {
+                fn to_amount(&self) -> String {
+                    self.to_string()
+                }
+            }
+        )*
+    };
+}
+
+amount_query!(f64, f32, i64, i32, u64, u32);
+
+#[cfg(feature = "decimal")]
+amount_query!(rust_decimal::Decimal);
+
+impl<T: AmountQuery + ?Sized> AmountQuery for &T {
+    fn to_amount(&self) -> String {
+        (**self).to_amount()
+    }
+}
+
+#[cfg(any(
+    feature = "cryptocurrency",
+    feature = "exchange",
+    feature = "fiat",
+    feature = "tools"
+))]
+fn push<T: ToString>(
+    query: &mut Vec<(&'static str, String)>,
+    name: &'static str,
+    value: &Option<T>,
+) {
+    if let Some(value) = value {
+        query.push((name, value.to_string()));
+    }
+}
+
+/// First currency of a comma-separated `convert` value, as used in the response keys.
+#[cfg(any(
+    feature = "tools",
+    all(
+        feature = "cryptocurrency",
+        any(feature = "blocking", feature = "async")
+    )
+))]
+pub(crate) fn first(convert: &str) -> String {
+    convert.split(',').next().unwrap_or_default().to_uppercase()
+}
+
+#[cfg(any(feature = "cryptocurrency", feature = "exchange", feature = "fiat"))]
+macro_rules! setters {
+    ($endpoint:ty { $($(#[$meta:meta])* $name:ident: $ty:ty),* $(,)? }) => {
+        impl<C> Call<'_, C, $endpoint> {
+            $(
+                $(#[$meta])*
+                pub fn $name(mut self, $name: $ty) -> Self {
+                    self.endpoint.$name = Some($name);
+                    self
+                }
+            )*
+        }
+    };
+}
+
+/// `v1/cryptocurrency/map`
+#[cfg(feature = "cryptocurrency")]
+#[derive(Clone, Debug, Default)]
+pub struct CryptoMap {
+    listing_status: Option<String>,
+    start: Option<usize>,
+    limit: Option<usize>,
+    sort: Option<Sort>,
+}
+
+#[cfg(feature = "cryptocurrency")]
+setters!(CryptoMap {
+    /// Offset the start (1-based index).
+    start: usize,
+    /// Number of results to return.
+    limit: usize,
+    /// What field to sort the list of cryptocurrencies by.
+    sort: Sort,
+});
+
+#[cfg(feature = "cryptocurrency")]
+impl<C> Call<'_, C, CryptoMap> {
+    /// Which cryptocurrencies to list, active ones by default.
+    /// Example: `[ListingStatus::Active, ListingStatus::Inactive]`
+    pub fn listing_status<T: AsRef<[ListingStatus]>>(mut self, statuses: T) -> Self {
+        let statuses: Vec<_> = statuses
+            .as_ref()
+            .iter()
+            .map(|status| match status {
+                ListingStatus::Active => "active",
+                ListingStatus::Inactive => "inactive",
+                ListingStatus::Untracked => "untracked",
+            })
+            .collect();
+        self.endpoint.listing_status = Some(statuses.join(","));
+        self
+    }
+}
+
+#[cfg(feature = "cryptocurrency")]
+impl Endpoint for CryptoMap {
+    type Output = CmcIdMap;
+    const PATH: &'static str = "v1/cryptocurrency/map";
+    const CACHED: bool = true;
+
+    fn query(&self) -> Vec<(&'static str, String)> {
+        let mut query = Vec::new();
+        push(&mut query, "listing_status", &self.listing_status);
+        push(&mut query, "start", &self.start);
+        push(&mut query, "limit", &self.limit);
+        let sort = self.sort.as_ref().map(|sort| match sort {
+            Sort::Id => "id",
+            Sort::CmcRank => "cmc_rank",
+        });
+        push(&mut query, "sort", &sort);
+        query
+    }
+
+    fn parse(self, body: Value, _convert: &str) -> CmcResult<CmcIdMap> {
+        Ok(serde_json::from_value(body)?)
+    }
+}
+
+/// `v1/fiat/map`
+#[cfg(feature = "fiat")]
+#[derive(Clone, Debug, Default)]
+pub struct FiatMap {
+    start: Option<usize>,
+    limit: Option<usize>,
+    sort: Option<SortFiat>,
+}
+
+#[cfg(feature = "fiat")]
+setters!(FiatMap {
+    /// Offset the start (1-based index).
+    start: usize,
+    /// Number of results to return.
+    limit: usize,
+    /// What field to sort the list of currencies by.
+    sort: SortFiat,
+});
+
+#[cfg(feature = "fiat")]
+impl Endpoint for FiatMap {
+    type Output = CmcFiatIdMap;
+    const PATH: &'static str = "v1/fiat/map";
+    const CACHED: bool = true;
+
+    fn query(&self) -> Vec<(&'static str, String)> {
+        let mut query = Vec::new();
+        push(&mut query, "start", &self.start);
+        push(&mut query, "limit", &self.limit);
+        let sort = self.sort.as_ref().map(|sort| match sort {
+            SortFiat::Id => "id",
+            SortFiat::Name => "name",
+        });
+        push(&mut query, "sort", &sort);
+        query
+    }
+
+    fn parse(self, body: Value, _convert: &str) -> CmcResult<CmcFiatIdMap> {
+        Ok(serde_json::from_value(body)?)
+    }
+}
+
+/// `v1/exchange/map`
+#[cfg(feature = "exchange")]
+#[derive(Clone, Debug, Default)]
+pub struct ExchangeMap {
+    listing_status: Option<ListingStatusExchange>,
+    start: Option<usize>,
+    limit: Option<usize>,
+    sort: Option<SortExchange>,
+    crypto_id: Option<String>,
+}
+
+#[cfg(feature = "exchange")]
+setters!(ExchangeMap {
+    /// Which exchanges to list, active ones by default.
+    listing_status: ListingStatusExchange,
+    /// Offset the start (1-based index).
+    start: usize,
+    /// Number of results to return.
+    limit: usize,
+    /// What field to sort the list of exchanges by.
+    sort: SortExchange,
+});
+
+#[cfg(feature = "exchange")]
+impl<C> Call<'_, C, ExchangeMap> {
+    /// Only list exchanges with market pairs for this fiat or cryptocurrency.
+    pub fn crypto_id<T: Identifiers<CurrencyId>>(mut self, crypto_id: T) -> Self {
+        self.endpoint.crypto_id = Some(crypto_id.to_query());
+        self
+    }
+}
+
+#[cfg(feature = "exchange")]
+impl Endpoint for ExchangeMap {
+    type Output = CmcExchangeIdMap;
+    const PATH: &'static str = "v1/exchange/map";
+    const CACHED: bool = true;
+
+    fn query(&self) -> Vec<(&'static str, String)> {
+        let mut query = Vec::new();
+        push(&mut query, "start", &self.start);
+        push(&mut query, "limit", &self.limit);
+        let listing_status = self.listing_status.as_ref().map(|status| match status {
+            ListingStatusExchange::Active => "active",
+            ListingStatusExchange::Inactive => "inactive",
+            ListingStatusExchange::Untracked => "untracked",
+        });
+        push(&mut query, "listing_status", &listing_status);
+        let sort = self.sort.as_ref().map(|sort| match sort {
+            SortExchange::Id => "id",
+            SortExchange::Volume24h => "volume_24h",
+        });
+        push(&mut query, "sort", &sort);
+        push(&mut query, "crypto_id", &self.crypto_id);
+        query
+    }
+
+    fn parse(self, body: Value, _convert: &str) -> CmcResult<CmcExchangeIdMap> {
+        Ok(serde_json::from_value(body)?)
+    }
+}
+
+/// Selects how `quotes()` identifies cryptocurrencies.
+#[cfg(feature = "cryptocurrency")]
+#[must_use = "select the cryptocurrencies with `by_id()`, `by_slug()` or `by_symbol()`"]
+pub struct QuotesBy<'a, C> {
+    pub(crate) cmc: &'a C,
+}
+
+#[cfg(feature = "cryptocurrency")]
+impl<'a, C> QuotesBy<'a, C> {
+    /// One or more CoinMarketCap ids. Example: `[CryptoId(1), CryptoId(1027)]`
+    pub fn by_id<T: Identifiers<CryptoId>>(self, ids: T) -> Call<'a, C, QuotesLatest<QLv2Id>> {
+        Call::new(self.cmc, QuotesLatest::new("id", ids.to_query()))
+    }
+
+    /// One or more slugs. Example: "bitcoin,ethereum"
+    pub fn by_slug<T: Identifiers<Slug>>(self, slugs: T) -> Call<'a, C, QuotesLatest<QLv2Slug>> {
+        Call::new(
+            self.cmc,
+            QuotesLatest::new("slug", slugs.to_query().to_lowercase()),
+        )
+    }
+
+    /// One or more symbols. Example: "BTC,ETH"
+    pub fn by_symbol<T: Identifiers<Symbol>>(
+        self,
+        symbols: T,
+    ) -> Call<'a, C, QuotesLatest<QLv2Symbol>> {
+        Call::new(
+            self.cmc,
+            QuotesLatest::new("symbol", symbols.to_query().to_uppercase()),
+        )
+    }
+}
+
+/// `v2/cryptocurrency/quotes/latest`
+#[cfg(feature = "cryptocurrency")]
+#[derive(Clone, Debug)]
+pub struct QuotesLatest<R> {
+    key: (&'static str, String),
+    response: PhantomData<R>,
+}
+
+#[cfg(feature = "cryptocurrency")]
+impl<R> QuotesLatest<R> {
+    fn new(name: &'static str, value: String) -> Self {
+        QuotesLatest {
+            key: (name, value),
+            response: PhantomData,
+        }
+    }
+}
+
+#[cfg(feature = "cryptocurrency")]
+impl<R: DeserializeOwned> Endpoint for QuotesLatest<R> {
+    type Output = R;
+    const PATH: &'static str = "v2/cryptocurrency/quotes/latest";
+    const CONVERT: bool = true;
+
+    fn query(&self) -> Vec<(&'static str, String)> {
+        vec![self.key.clone()]
+    }
+
+    fn parse(self, body: Value, _convert: &str) -> CmcResult<R> {
+        Ok(serde_json::from_value(body)?)
+    }
+}
+
+#[cfg(feature = "cryptocurrency")]
+impl<R: DeserializeOwned> Convertible for QuotesLatest<R> {}
+
+/// Selects the currency `conversion()` converts from.
+#[cfg(feature = "tools")]
+#[must_use = "select the source currency with `by_id()` or `by_symbol()`"]
+pub struct ConversionBy<'a, C> {
+    pub(crate) cmc: &'a C,
+    pub(crate) amount: String,
+}
+
+#[cfg(feature = "tools")]
+impl<'a, C> ConversionBy<'a, C> {
+    /// CoinMarketCap id of the fiat or cryptocurrency to convert from. Example: `CryptoId(1027)`
+    pub fn by_id<T: Identifiers<CurrencyId>>(self, id: T) -> Call<'a, C, PriceConversion<PCv2Id>> {
+        Call::new(
+            self.cmc,
+            PriceConversion::new(self.amount, "id", id.to_query()),
+        )
+    }
+
+    /// Symbol of the fiat or cryptocurrency to convert from. Example: "BTC"
+    pub fn by_symbol<T: Identifiers<Symbol>>(
+        self,
+        symbol: T,
+    ) -> Call<'a, C, PriceConversion<PCv2Symbol>> {
+        Call::new(
+            self.cmc,
+            PriceConversion::new(self.amount, "symbol", symbol.to_query().to_uppercase()),
+        )
+    }
+}
+
+/// Response of `v2/tools/price-conversion`.
+#[cfg(feature = "tools")]
+pub trait ConversionResponse: DeserializeOwned {
+    #[doc(hidden)]
+    fn price(&self, currency: &str) -> Option<Amount>;
+}
+
+#[cfg(feature = "tools")]
+impl ConversionResponse for PCv2Symbol {
+    fn price(&self, currency: &str) -> Option<Amount> {
+        self.data.first()?.quote.get(currency)?.price
+    }
+}
+
+#[cfg(feature = "tools")]
+impl ConversionResponse for PCv2Id {
+    fn price(&self, currency: &str) -> Option<Amount> {
+        self.data.quote.get(currency)?.price
+    }
+}
+
+/// `v2/tools/price-conversion`
+#[cfg(feature = "tools")]
+#[derive(Clone, Debug)]
+pub struct PriceConversion<R> {
+    amount: String,
+    key: (&'static str, String),
+    time: Option<String>,
+    response: PhantomData<R>,
+}
+
+#[cfg(feature = "tools")]
+impl<R> PriceConversion<R> {
+    fn new(amount: String, name: &'static str, value: String) -> Self {
+        PriceConversion {
+            amount,
+            key: (name, value),
+            time: None,
+            response: PhantomData,
+        }
+    }
+}
+
+#[cfg(feature = "tools")]
+impl<C, R> Call<'_, C, PriceConversion<R>> {
+    /// Timestamp (Unix or ISO 8601) to reference historical pricing during conversion.
+    pub fn time<T: TimeQuery>(mut self, time: T) -> Self {
+        self.endpoint.time = Some(time.to_time());
+        self
+    }
+}
+
+#[cfg(feature = "tools")]
+impl<R: ConversionResponse> Endpoint for PriceConversion<R> {
+    type Output = Amount;
+    const PATH: &'static str = "v2/tools/price-conversion";
+    const CONVERT: bool = true;
+
+    fn query(&self) -> Vec<(&'static str, String)> {
+        let mut query = vec![("amount", self.amount.clone()), self.key.clone()];
+        push(&mut query, "time", &self.time);
+        query
+    }
+
+    fn parse(self, body: Value, convert: &str) -> CmcResult<Amount> {
+        let root: R = serde_json::from_value(body)?;
+        root.price(&first(convert)).ok_or(CmcErrors::NullAnswer)
+    }
+}
+
+#[cfg(feature = "tools")]
+impl<R: ConversionResponse> Convertible for PriceConversion<R> {}
+
+/// `v2/tools/price-conversion` for one cryptocurrency, returning the full conversion record.
+#[cfg(feature = "tools")]
+#[derive(Clone, Debug)]
+pub struct Convert {
+    amount: String,
+    asset: AssetRef,
+    time: Option<String>,
+}
+
+#[cfg(all(feature = "tools", any(feature = "blocking", feature = "async")))]
+impl Convert {
+    pub(crate) fn new(amount: String, asset: AssetRef) -> Self {
+        Convert {
+            amount,
+            asset,
+            time: None,
+        }
+    }
+}
+
+#[cfg(feature = "tools")]
+impl<C> Call<'_, C, Convert> {
+    /// Point in time to reference historical pricing: an ISO 8601 string, a Unix timestamp or,
+    /// with the `chrono` feature, a `DateTime` or `NaiveDate`.
+    pub fn time<T: TimeQuery>(mut self, time: T) -> Self {
+        self.endpoint.time = Some(time.to_time());
+        self
+    }
+}
+
+#[cfg(feature = "tools")]
+impl Endpoint for Convert {
+    type Output = ConversionResult;
+    const PATH: &'static str = "v2/tools/price-conversion";
+    const CONVERT: bool = true;
+
+    fn check(&self) -> CmcResult<()> {
+        match self.asset {
+            AssetRef::Id(_) | AssetRef::Symbol(_) => Ok(()),
+            AssetRef::Slug(_) | AssetRef::Address(_) => Err(CmcErrors::PassIncompatible),
+        }
+    }
+
+    fn query(&self) -> Vec<(&'static str, String)> {
+        let mut query = vec![("amount", self.amount.clone()), self.asset.param()];
+        push(&mut query, "time", &self.time);
+        query
+    }
+
+    fn parse(self, body: Value, _convert: &str) -> CmcResult<ConversionResult> {
+        match self.asset {
+            AssetRef::Symbol(_) => serde_json::from_value::<PCv2Symbol>(body)?
+                .data
+                .into_iter()
+                .next()
+                .ok_or(CmcErrors::NullAnswer),
+            _ => Ok(serde_json::from_value::<PCv2Id>(body)?.data),
+        }
+    }
+}
+
+#[cfg(feature = "tools")]
+impl Convertible for Convert {}
+
+/// `v1/cryptocurrency/categories`
+#[cfg(feature = "cryptocurrency")]
+#[derive(Clone, Debug, Default)]
+pub struct CategoryList {
+    start: Option<usize>,
+    limit: Option<usize>,
+    key: Option<(&'static str, String)>,
+}
+
+#[cfg(feature = "cryptocurrency")]
+setters!(CategoryList {
+    /// Offset the start (1-based index).
+    start: usize,
+    /// Number of results to return.
+    limit: usize,
+});
+
+#[cfg(feature = "cryptocurrency")]
+impl<C> Call<'_, C, CategoryList> {
+    /// Only categories of these cryptocurrencies. Example: `CryptoId(1027)`
+    pub fn id<T: Identifiers<CryptoId>>(mut self, ids: T) -> Self {
+        self.endpoint.key = Some(("id", ids.to_query()));
+        self
+    }
+
+    /// Only categories of these cryptocurrencies. Example: "ethereum"
+    pub fn slug<T: Identifiers<Slug>>(mut self, slugs: T) -> Self {
+        self.endpoint.key = Some(("slug", slugs.to_query().to_lowercase()));
+        self
+    }
+
+    /// Only categories of these cryptocurrencies. Example: "ETH"
+    pub fn symbol<T: Identifiers<Symbol>>(mut self, symbols: T) -> Self {
+        self.endpoint.key = Some(("symbol", symbols.to_query().to_uppercase()));
+        self
+    }
+}
+
+#[cfg(feature = "cryptocurrency")]
+impl Endpoint for CategoryList {
+    type Output = CmcCategories;
+    const PATH: &'static str = "v1/cryptocurrency/categories";
+
+    fn query(&self) -> Vec<(&'static str, String)> {
+        let mut query = Vec::new();
+        push(&mut query, "start", &self.start);
+        push(&mut query, "limit", &self.limit);
+        query.extend(self.key.clone());
+        query
+    }
+
+    fn parse(self, body: Value, _convert: &str) -> CmcResult<CmcCategories> {
+        Ok(serde_json::from_value(body)?)
+    }
+}
+
+/// `v1/cryptocurrency/category`
+#[cfg(feature = "cryptocurrency")]
+#[derive(Clone, Debug)]
+pub struct CategoryInfo {
+    id: CategoryId,
+    start: Option<usize>,
+    limit: Option<usize>,
+}
+
+#[cfg(all(
+    feature = "cryptocurrency",
+    any(feature = "blocking", feature = "async")
+))]
+impl CategoryInfo {
+    pub(crate) fn new(id: CategoryId) -> Self {
+        CategoryInfo {
+            id,
+            start: None,
+            limit: None,
+        }
+    }
+}
+
+#[cfg(feature = "cryptocurrency")]
+setters!(CategoryInfo {
+    /// Offset the start (1-based index) of the list of coins.
+    start: usize,
+    /// Number of coins to return.
+    limit: usize,
+});
+
+#[cfg(feature = "cryptocurrency")]
+impl Endpoint for CategoryInfo {
+    type Output = Category;
+    const PATH: &'static str = "v1/cryptocurrency/category";
+    const CONVERT: bool = true;
+
+    fn query(&self) -> Vec<(&'static str, String)> {
+        let mut query = vec![("id", self.id.to_string())];
+        push(&mut query, "start", &self.start);
+        push(&mut query, "limit", &self.limit);
+        query
+    }
+
+    fn parse(self, body: Value, _convert: &str) -> CmcResult<Category> {
+        Ok(serde_json::from_value::<CmcCategory>(body)?.data)
+    }
+}
+
+#[cfg(feature = "cryptocurrency")]
+impl Convertible for CategoryInfo {}
+
+/// Selects how `info()` identifies cryptocurrencies.
+#[cfg(feature = "cryptocurrency")]
+#[must_use = "select the cryptocurrency with `by_id()`, `by_slug()`, `by_symbol()` or `by_address()`"]
+pub struct InfoBy<'a, C> {
+    pub(crate) cmc: &'a C,
+}
+
+#[cfg(feature = "cryptocurrency")]
+impl<'a, C> InfoBy<'a, C> {
+    /// Metadata of one cryptocurrency. Example: `CryptoId(1027)`
+    pub fn by_id<T: Into<CryptoId>>(self, id: T) -> Call<'a, C, CryptoInfo> {
+        Call::new(self.cmc, CryptoInfo(AssetRef::Id(id.into())))
+    }
+
+    /// Metadata of one cryptocurrency. Example: "ethereum"
+    pub fn by_slug<T: Into<Slug>>(self, slug: T) -> Call<'a, C, CryptoInfo> {
+        Call::new(self.cmc, CryptoInfo(AssetRef::Slug(slug.into())))
+    }
+
+    /// Metadata of one cryptocurrency. Example: "ETH"
+    pub fn by_symbol<T: Into<Symbol>>(self, symbol: T) -> Call<'a, C, CryptoInfo> {
+        Call::new(self.cmc, CryptoInfo(AssetRef::Symbol(symbol.into())))
+    }
+
+    /// Metadata of one token. Example: "0xc40af1e4fecfa05ce6bab79dcd8b373d2e436c4e"
+    pub fn by_address<T: Into<ContractAddress>>(self, address: T) -> Call<'a, C, CryptoInfo> {
+        Call::new(self.cmc, CryptoInfo(AssetRef::Address(address.into())))
+    }
+
+    /// Metadata of one or more cryptocurrencies, keyed by id. Example: "1,328,1027"
+    pub fn by_ids<T: Identifiers<CryptoId>>(self, ids: T) -> Call<'a, C, CryptoInfoMap> {
+        Call::new(self.cmc, CryptoInfoMap(ids.to_query()))
+    }
+}
+
+/// `v2/cryptocurrency/info` for one cryptocurrency.
+#[cfg(feature = "cryptocurrency")]
+#[derive(Clone, Debug)]
+pub struct CryptoInfo(pub(crate) AssetRef);
+
+#[cfg(feature = "cryptocurrency")]
+impl Endpoint for CryptoInfo {
+    type Output = Metadata;
+    const PATH: &'static str = "v2/cryptocurrency/info";
+
+    fn query(&self) -> Vec<(&'static str, String)> {
+        vec![self.0.param()]
+    }
+
+    fn parse(self, body: Value, _convert: &str) -> CmcResult<Metadata> {
+        let (_, query) = self.0.param();
+        let md = match self.0 {
+            AssetRef::Symbol(_) => serde_json::from_value::<MDv2Symbol>(body)?
+                .data
+                .remove(&query)
+                .and_then(|md_vec| md_vec.into_iter().next()),
+            AssetRef::Slug(_) | AssetRef::Address(_) => serde_json::from_value::<MDv2>(body)?
+                .data
+                .into_values()
+                .next(),
+            AssetRef::Id(_) => serde_json::from_value::<MDv2>(body)?.data.remove(&query),
+        };
+        md.ok_or(CmcErrors::NullAnswer)
+    }
+}
+
+/// `v2/cryptocurrency/info` for one or more cryptocurrencies.
+#[cfg(feature = "cryptocurrency")]
+#[derive(Clone, Debug)]
+pub struct CryptoInfoMap(String);
+
+#[cfg(feature = "cryptocurrency")]
+impl Endpoint for CryptoInfoMap {
+    type Output = HashMap<String, Metadata>;
+    const PATH: &'static str = "v2/cryptocurrency/info";
+    const CACHED: bool = true;
+
+    fn query(&self) -> Vec<(&'static str, String)> {
+        vec![("id", self.0.clone())]
+    }
+
+    fn parse(self, body: Value, _convert: &str) -> CmcResult<HashMap<String, Metadata>> {
+        Ok(serde_json::from_value::<MDv2>(body)?.data)
+    }
+}
+
+/// Selects how `exchange_info()` identifies exchanges.
+#[cfg(feature = "exchange")]
+#[must_use = "select the exchanges with `by_id()` or `by_slug()`"]
+pub struct ExchangeInfoBy<'a, C> {
+    pub(crate) cmc: &'a C,
+}
+
+#[cfg(feature = "exchange")]
+impl<'a, C> ExchangeInfoBy<'a, C> {
+    /// One or more exchange ids. Example: `[ExchangeId(270), ExchangeId(271)]`
+    pub fn by_id<T: Identifiers<ExchangeId>>(self, ids: T) -> Call<'a, C, ExchangeInfo> {
+        Call::new(self.cmc, ExchangeInfo(("id", ids.to_query())))
+    }
+
+    /// One or more exchange slugs. Example: "binance,gdax"
+    pub fn by_slug<T: Identifiers<Slug>>(self, slugs: T) -> Call<'a, C, ExchangeInfo> {
+        Call::new(
+            self.cmc,
+            ExchangeInfo(("slug", slugs.to_query().to_lowercase())),
+        )
+    }
+}
+
+/// `v1/exchange/info`
+#[cfg(feature = "exchange")]
+#[derive(Clone, Debug)]
+pub struct ExchangeInfo(pub(crate) (&'static str, String));
+
+#[cfg(feature = "exchange")]
+impl Endpoint for ExchangeInfo {
+    type Output = ExchangeMetadata;
+    const PATH: &'static str = "v1/exchange/info";
+
+    fn query(&self) -> Vec<(&'static str, String)> {
+        vec![self.0.clone()]
+    }
+
+    fn parse(self, body: Value, _convert: &str) -> CmcResult<ExchangeMetadata> {
+        Ok(serde_json::from_value(body)?)
+    }
+}
+
+/// `v1/global-metrics/quotes/latest`
+#[cfg(feature = "global_metrics")]
+#[derive(Clone, Debug, Default)]
+pub struct GlobalQuotes;
+
+#[cfg(feature = "global_metrics")]
+impl Endpoint for GlobalQuotes {
+    type Output = GlobalMetrics;
+    const PATH: &'static str = "v1/global-metrics/quotes/latest";
+    const CONVERT: bool = true;
+
+    fn query(&self) -> Vec<(&'static str, String)> {
+        Vec::new()
+    }
+
+    fn parse(self, body: Value, _convert: &str) -> CmcResult<GlobalMetrics> {
+        Ok(serde_json::from_value::<CmcGlobalMetrics>(body)?.data)
+    }
+}
+
+#[cfg(feature = "global_metrics")]
+impl Convertible for GlobalQuotes {}
+
+/// `v1/key/info`
+#[cfg(feature = "key")]
+#[derive(Clone, Debug, Default)]
+pub struct KeyUsage;
+
+#[cfg(feature = "key")]
+impl Endpoint for KeyUsage {
+    type Output = KeyInfo;
+    const PATH: &'static str = "v1/key/info";
+
+    fn query(&self) -> Vec<(&'static str, String)> {
+        Vec::new()
+    }
+
+    fn parse(self, body: Value, _convert: &str) -> CmcResult<KeyInfo> {
+        Ok(serde_json::from_value::<CmcKeyInfo>(body)?.data)
+    }
+}
@@ -0,0 +1,199 @@
+//! Local lookup of CoinMarketCap ids by symbol, slug or contract address.
+//!
+//! Symbols are not unique on CoinMarketCap, so a `Resolver` never guesses: a query that
+//! matches several cryptocurrencies fails with [`CmcErrors::Ambiguous`] unless one of the
+//! disambiguation rules narrows it down to a single id.
+//!
+#![cfg_attr(feature = "blocking", doc = " ```rust")]
+#![cfg_attr(not(feature = "blocking"), doc = " ```rust,ignore")]
+//! use cmc::Cmc;
+//!
+//! let cmc = Cmc::new("<API KEY>");
+//!
+//! match cmc.resolver() {
+//!     Ok(resolver) => {
+//!         let resolver = resolver.active_only(true).highest_rank(true);
+//!         match resolver.by_symbol("USDT") {
+//!             Ok(id) => println!("USDT id: {}", id),
+//!             Err(err) => println!("{}", err),
+//!         }
+//!     }
+//!     Err(err) => println!("{}", err),
+//! }
+//! ```
+
+use crate::api::cryptocurrency::coinmarketcap_id_map::Cryptocurrency;
+use crate::api::CmcResult;
+use crate::errors::CmcErrors;
+use crate::ids::CryptoId;
+use std::collections::HashMap;
+
+/// Maximum page size of the `/v1/cryptocurrency/map` endpoint.
+#[cfg(any(feature = "blocking", feature = "async"))]
+pub(crate) const ID_MAP_PAGE: usize = 5000;
+
+/// Resolves symbols, slugs and contract addresses into CoinMarketCap ids.
+#[derive(Clone, Debug, Default)]
+pub struct Resolver {
+    entries: Vec<Cryptocurrency>,
+    by_symbol: HashMap<String, Vec<usize>>,
+    by_slug: HashMap<String, Vec<usize>>,
+    by_address: HashMap<String, Vec<usize>>,
+    active_only: bool,
+    highest_rank: bool,
+    platforms: Vec<String>,
+}
+
+impl Resolver {
+    /// Builds a resolver from `id_map()` entries.
+    pub fn new<I: IntoIterator<Item = Cryptocurrency>>(entries: I) -> Self {
+        let mut resolver = Resolver::default();
+        for cc in entries {
+            let index = resolver.entries.len();
+            resolver
+                .by_symbol
+                .entry(cc.symbol.to_uppercase())
+                .or_default()
+                .push(index);
+            resolver
+                .by_slug
+                .entry(cc.slug.to_lowercase())
+                .or_default()
+                .push(index);
+            if let Some(address) = token_address(&cc) {
+                resolver
+                    .by_address
+                    .entry(normalize_address(address))
+                    .or_default()
+                    .push(index);
+            }
+            resolver.entries.push(cc);
+        }
+        resolver
+    }
+
+    /// Ignore cryptocurrencies that are no longer active.
+    pub fn active_only(mut self, active_only: bool) -> Self {
+        self.active_only = active_only;
+        self
+    }
+
+    /// When several candidates remain, pick the one with the best CoinMarketCap rank.
+    pub fn highest_rank(mut self, highest_rank: bool) -> Self {
+        self.highest_rank = highest_rank;
+        self
+    }
+
+    /// Prefer candidates that live on the given platform (slug, e.g. "ethereum").
+    ///
+    /// Can be called several times, earlier platforms win. A native coin counts as living on
+    /// its own platform, so `prefer_platform("ethereum")` prefers ETH itself and ERC-20 tokens.
+    pub fn prefer_platform<T: Into<String>>(mut self, platform: T) -> Self {
+        self.platforms.push(platform.into().to_lowercase());
+        self
+    }
+
+    /// Returns the id of the cryptocurrency with the given symbol. Example: "BTC"
+    pub fn by_symbol(&self, symbol: &str) -> CmcResult<CryptoId> {
+        self.resolve(symbol, self.by_symbol.get(&symbol.to_uppercase()))
+    }
+
+    /// Returns the id of the cryptocurrency with the given slug. Example: "bitcoin"
+    pub fn by_slug(&self, slug: &str) -> CmcResult<CryptoId> {
+        self.resolve(slug, self.by_slug.get(&slug.to_lowercase()))
+    }
+
+    /// Returns the id of the token with the given contract address.
+    /// Example: "0xdac17f958d2ee523a2206206994597c13d831ec7"
+    pub fn by_address(&self, address: &str) -> CmcResult<CryptoId> {
+        self.resolve(address, self.by_address.get(&normalize_address(address)))
+    }
+
+    /// All cryptocurrencies sharing `symbol`, before any rule is applied.
+    pub fn candidates(&self, symbol: &str) -> Vec<&Cryptocurrency> {
+        self.by_symbol
+            .get(&symbol.to_uppercase())
+            .map(|indexes| indexes.iter().map(|&i| &self.entries[i]).collect())
+            .unwrap_or_default()
+    }
+
+    /// Number of cryptocurrencies known to the resolver.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the resolver knows no cryptocurrencies.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn resolve(&self, query: &str, indexes: Option<&Vec<usize>>) -> CmcResult<CryptoId> {
+        let mut candidates: Vec<&Cryptocurrency> = indexes
+            .map(|indexes| indexes.iter().map(|&i| &self.entries[i]).collect())
+            .unwrap_or_default();
+
+        if self.active_only {
+            candidates.retain(|cc| cc.is_active == 1);
+        }
+
+        if candidates.len() > 1 {
+            for platform in &self.platforms {
+                if candidates
+                    .iter()
+                    .any(|cc| platform_slug(cc) == platform.as_str())
+                {
+                    candidates.retain(|cc| platform_slug(cc) == platform.as_str());
+                    break;
+                }
+            }
+        }
+
+        if candidates.len() > 1 && self.highest_rank {
+            let best = candidates.iter().map(|cc| rank(cc)).min().unwrap();
+            candidates.retain(|cc| rank(cc) == best);
+        }
+
+        match candidates.as_slice() {
+            [] => Err(CmcErrors::NotFound(query.to_owned())),
+            [cc] => Ok(id(cc)),
+            _ => Err(CmcErrors::Ambiguous(
+                query.to_owned(),
+                candidates.iter().map(|cc| id(cc)).collect(),
+            )),
+        }
+    }
+}
+
+fn id(cc: &Cryptocurrency) -> CryptoId {
+    CryptoId(cc.id as u64)
+}
+
+/// Unranked cryptocurrencies are reported with rank 0 and sort after ranked ones.
+fn rank(cc: &Cryptocurrency) -> i64 {
+    if cc.rank > 0 {
+        cc.rank
+    } else {
+        i64::MAX
+    }
+}
+
+fn platform_slug(cc: &Cryptocurrency) -> &str {
+    cc.platform
+        .as_ref()
+        .map_or(&cc.slug, |platform| &platform.slug)
+}
+
+fn token_address(cc: &Cryptocurrency) -> Option<&str> {
+    cc.platform
+        .as_ref()
+        .and_then(|platform| platform.token_address.as_deref())
+}
+
+/// Hex addresses are case-insensitive, other formats (e.g. base58) are kept as is.
+fn normalize_address(address: &str) -> String {
+    if address.starts_with("0x") || address.starts_with("0X") {
+        address.to_lowercase()
+    } else {
+        address.to_owned()
+    }
+}
@@ -0,0 +1,134 @@
+//! HTTP layer of the clients.
+//!
+//! Every API call goes through an [`HttpTransport`] (blocking client) or an
+//! [`AsyncHttpTransport`] (async client). Both are implemented for the matching
+//! `reqwest::Client`, which is what the builders use by default. Implement them to route
+//! requests through an instrumented HTTP stack, or to answer them from fixtures in tests.
+//!
+#![cfg_attr(feature = "blocking", doc = " ```rust")]
+#![cfg_attr(not(feature = "blocking"), doc = " ```rust,ignore")]
+//! use cmc::transport::{HttpRequest, HttpResponse, HttpTransport};
+//! use cmc::api::CmcResult;
+//! use cmc::CmcBuilder;
+//! use reqwest::StatusCode;
+//!
+//! #[derive(Debug)]
+//! struct Offline;
+//!
+//! impl HttpTransport for Offline {
+//!     fn send(&self, request: HttpRequest) -> CmcResult<HttpResponse> {
+//!         assert_eq!(request.url.path(), "/v1/key/info");
+//!         Ok(HttpResponse::new(StatusCode::UNAUTHORIZED, r#"{"status":{"timestamp":"","error_code":1001,"error_message":"This API Key is invalid.","elapsed":0,"credit_count":0}}"#))
+//!     }
+//! }
+//!
+//! let cmc = CmcBuilder::new("<API KEY>").transport(Offline).build().unwrap();
+//! assert!(cmc.key_info().is_err());
+//! ```
+
+use crate::api::CmcResult;
+use crate::errors::CmcErrors;
+use reqwest::{StatusCode, Url};
+use std::fmt::Debug;
+#[cfg(any(feature = "async", doc))]
+use std::future::Future;
+#[cfg(any(feature = "async", doc))]
+use std::pin::Pin;
+use std::time::Duration;
+
+/// GET request to the API.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HttpRequest {
+    /// Full url including the query.
+    pub url: Url,
+    /// Headers to send, including the API key.
+    pub headers: Vec<(String, String)>,
+    /// Time limit of the whole request, if any.
+    pub timeout: Option<Duration>,
+}
+
+impl HttpRequest {
+    /// Request for `url` with the query parameters appended.
+    pub fn new(url: &str, query: &[(&str, String)]) -> CmcResult<Self> {
+        let mut url = Url::parse(url).map_err(|err| CmcErrors::InvalidUrl(err.to_string()))?;
+        if !query.is_empty() {
+            url.query_pairs_mut().extend_pairs(query);
+        }
+        Ok(Self {
+            url,
+            headers: Vec::new(),
+            timeout: None,
+        })
+    }
+
+    /// Adds a header.
+    pub fn header<K: Into<String>, V: Into<String>>(mut self, name: K, value: V) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+}
+
+/// Status and raw body of a response.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HttpResponse {
+    pub status: StatusCode,
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    pub fn new<B: Into<Vec<u8>>>(status: StatusCode, body: B) -> Self {
+        Self {
+            status,
+            body: body.into(),
+        }
+    }
+}
+
+/// Sends requests of the blocking client.
+pub trait HttpTransport: Debug + Send + Sync {
+    fn send(&self, request: HttpRequest) -> CmcResult<HttpResponse>;
+}
+
+/// Future returned by [`AsyncHttpTransport::send`].
+#[cfg(any(feature = "async", doc))]
+pub type TransportFuture<'a> = Pin<Box<dyn Future<Output = CmcResult<HttpResponse>> + Send + 'a>>;
+
+/// Sends requests of the async client.
+#[cfg(any(feature = "async", doc))]
+pub trait AsyncHttpTransport: Debug + Send + Sync {
+    fn send(&self, request: HttpRequest) -> TransportFuture<'_>;
+}
+
+#[cfg(feature = "blocking")]
+impl HttpTransport for reqwest::blocking::Client {
+    fn send(&self, request: HttpRequest) -> CmcResult<HttpResponse> {
+        let mut rb = self.get(request.url);
+        for (name, value) in &request.headers {
+            rb = rb.header(name, value);
+        }
+        if let Some(timeout) = request.timeout {
+            rb = rb.timeout(timeout);
+        }
+        let resp = rb.send()?;
+        let status = resp.status();
+        Ok(HttpResponse::new(status, resp.bytes()?))
+    }
+}
+
+#[cfg(feature = "async")]
+impl AsyncHttpTransport for reqwest::Client {
+    fn send(&self, request: HttpRequest) -> TransportFuture<'_> {
+        Box::pin(async move {
+            let mut rb = self.get(request.url);
+            for (name, value) in &request.headers {
+                rb = rb.header(name, value);
+            }
+            if let Some(timeout) = request.timeout {
+                rb = rb.timeout(timeout);
+            }
+            let resp = rb.send().await?;
+            let status = resp.status();
+            Ok(HttpResponse::new(status, resp.bytes().await?))
+        })
+    }
+}
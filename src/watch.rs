@@ -0,0 +1,238 @@
+//! Polling quotes as they change.
+//!
+//! `watch_quotes(ids, interval)` polls `v2/cryptocurrency/quotes/latest` for `ids` and yields a
+//! [`QuoteUpdate`] only for the cryptocurrencies whose `last_updated` changed since the previous
+//! poll. The blocking client returns a [`QuoteWatch`] iterator, the async client a
+//! [`QuoteStream`].
+//!
+//! Polls are aligned to the clock: they happen on multiples of `interval` since the Unix epoch,
+//! shifted by a twelfth of it, so a 60 second interval polls 5 seconds into each minute, just
+//! after CoinMarketCap refreshes its quotes. The first poll happens right away.
+//!
+//! Every poll is an ordinary request, so it goes through the key pool and the credit ledger.
+//! Errors are yielded and polling goes on; after `RateLimited` the next poll waits for the
+//! cooldown. `BudgetExceeded` is yielded last and ends the watch.
+//!
+#![cfg_attr(feature = "blocking", doc = " ```rust,no_run")]
+#![cfg_attr(not(feature = "blocking"), doc = " ```rust,ignore")]
+//! use cmc::{CmcBuilder, CryptoId};
+//! use std::time::Duration;
+//!
+//! let cmc = CmcBuilder::new("<API KEY>").build().unwrap();
+//!
+//! for update in cmc.watch_quotes([CryptoId(1), CryptoId(1027)], Duration::from_secs(60)) {
+//!     match update {
+//!         Ok(update) => println!("{}: {:?}", update.quote.symbol, update.quote.quote["USD"].price),
+//!         Err(err) => println!("{}", err),
+//!     }
+//! }
+//! ```
+
+use crate::api::cryptocurrency::quotes_latest_v2::CryptoCurrency;
+use crate::api::Timestamp;
+use crate::ids::CryptoId;
+
+#[cfg(any(feature = "blocking", feature = "async"))]
+use crate::api::cryptocurrency::quotes_latest_v2::QLv2Id;
+#[cfg(any(feature = "blocking", feature = "async"))]
+use crate::api::CmcResult;
+#[cfg(any(feature = "blocking", feature = "async"))]
+use crate::errors::CmcErrors;
+#[cfg(any(feature = "blocking", feature = "async"))]
+use std::collections::HashMap;
+#[cfg(feature = "blocking")]
+use std::collections::VecDeque;
+#[cfg(feature = "async")]
+use std::pin::Pin;
+#[cfg(feature = "async")]
+use std::task::{Context, Poll};
+#[cfg(any(feature = "blocking", feature = "async"))]
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// A quote whose `last_updated` changed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct QuoteUpdate {
+    /// Id of the cryptocurrency.
+    pub id: CryptoId,
+    /// `last_updated` of the previous quote, `None` for the first one.
+    pub previous: Option<Timestamp>,
+    /// The new quote.
+    pub quote: CryptoCurrency,
+}
+
+/// Polling state shared by [`QuoteWatch`] and [`QuoteStream`].
+#[cfg(any(feature = "blocking", feature = "async"))]
+#[derive(Debug)]
+struct Watcher {
+    ids: String,
+    interval: Duration,
+    first: bool,
+    done: bool,
+    pause: Option<Duration>,
+    seen: HashMap<i64, Timestamp>,
+}
+
+#[cfg(any(feature = "blocking", feature = "async"))]
+impl Watcher {
+    fn new(ids: String, interval: Duration) -> Self {
+        Self {
+            ids,
+            interval,
+            first: true,
+            done: false,
+            pause: None,
+            seen: HashMap::new(),
+        }
+    }
+
+    /// Time to wait before the next poll.
+    fn wait(&mut self) -> Duration {
+        if std::mem::take(&mut self.first) {
+            return Duration::ZERO;
+        }
+        let interval = self.interval.as_millis().max(1);
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis())
+            .unwrap_or_default();
+        let offset = interval / 12;
+        let wait = interval - (now + interval - offset) % interval;
+        let wait = Duration::from_millis(wait as u64);
+        wait.max(self.pause.take().unwrap_or_default())
+    }
+
+    /// Updates of one poll, ordered by id.
+    fn updates(&mut self, result: CmcResult<QLv2Id>) -> Vec<CmcResult<QuoteUpdate>> {
+        let root = match result {
+            Ok(root) => root,
+            Err(err) => {
+                match &err {
+                    CmcErrors::RateLimited(retry) => self.pause = Some(*retry),
+                    CmcErrors::BudgetExceeded(..) => self.done = true,
+                    _ => {}
+                }
+                return vec![Err(err)];
+            }
+        };
+
+        let mut quotes: Vec<_> = root.data.into_values().collect();
+        quotes.sort_by_key(|quote| quote.id);
+        quotes
+            .into_iter()
+            .filter_map(|quote| {
+                let previous = self.seen.get(&quote.id);
+                if previous == Some(&quote.last_updated) {
+                    return None;
+                }
+                let previous = self.seen.insert(quote.id, quote.last_updated.to_owned());
+                Some(Ok(QuoteUpdate {
+                    id: CryptoId(quote.id as u64),
+                    previous,
+                    quote,
+                }))
+            })
+            .collect()
+    }
+}
+
+/// Blocking iterator over quote updates, see [`Cmc::watch_quotes()`](crate::Cmc::watch_quotes).
+/// It never ends on its own, unless the credit budget is exceeded.
+#[cfg(feature = "blocking")]
+#[derive(Debug)]
+pub struct QuoteWatch {
+    cmc: crate::api::Cmc,
+    watcher: Watcher,
+    pending: VecDeque<CmcResult<QuoteUpdate>>,
+}
+
+#[cfg(feature = "blocking")]
+impl QuoteWatch {
+    pub(crate) fn new(cmc: crate::api::Cmc, ids: String, interval: Duration) -> Self {
+        Self {
+            cmc,
+            watcher: Watcher::new(ids, interval),
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Calls `f` with every update until it returns `false`.
+    pub fn each<F: FnMut(CmcResult<QuoteUpdate>) -> bool>(self, mut f: F) {
+        for update in self {
+            if !f(update) {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl Iterator for QuoteWatch {
+    type Item = CmcResult<QuoteUpdate>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(update) = self.pending.pop_front() {
+                return Some(update);
+            }
+            if self.watcher.done {
+                return None;
+            }
+            std::thread::sleep(self.watcher.wait());
+            let result = self.cmc.quotes().by_id(&self.watcher.ids).send();
+            self.pending.extend(self.watcher.updates(result));
+        }
+    }
+}
+
+/// Stream of quote updates, see [`async_api::Cmc::watch_quotes()`](crate::async_api::Cmc::watch_quotes).
+/// Polling runs on a background task, which stops when the stream is dropped. Outside a Tokio
+/// runtime the stream yields `CmcErrors::NoRuntime` and ends.
+#[cfg(feature = "async")]
+#[derive(Debug)]
+pub struct QuoteStream {
+    updates: tokio::sync::mpsc::Receiver<CmcResult<QuoteUpdate>>,
+}
+
+#[cfg(feature = "async")]
+impl QuoteStream {
+    pub(crate) fn new(cmc: crate::async_api::Cmc, ids: String, interval: Duration) -> Self {
+        let (tx, updates) = tokio::sync::mpsc::channel(16);
+        let runtime = match tokio::runtime::Handle::try_current() {
+            Ok(runtime) => runtime,
+            Err(_) => {
+                let _ = tx.try_send(Err(CmcErrors::NoRuntime));
+                return Self { updates };
+            }
+        };
+        runtime.spawn(async move {
+            let mut watcher = Watcher::new(ids, interval);
+            while !watcher.done {
+                tokio::time::sleep(watcher.wait()).await;
+                if tx.is_closed() {
+                    return;
+                }
+                let result = cmc.quotes().by_id(&watcher.ids).send().await;
+                for update in watcher.updates(result) {
+                    if tx.send(update).await.is_err() {
+                        return;
+                    }
+                }
+            }
+        });
+        Self { updates }
+    }
+
+    /// Waits for the next update. Returns `None` once the stream has ended.
+    pub async fn recv(&mut self) -> Option<CmcResult<QuoteUpdate>> {
+        self.updates.recv().await
+    }
+}
+
+#[cfg(feature = "async")]
+impl futures_core::Stream for QuoteStream {
+    type Item = CmcResult<QuoteUpdate>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.updates.poll_recv(cx)
+    }
+}
@@ -1,6 +1,118 @@
+#![cfg(feature = "blocking")]
+
+#[cfg(test)]
+mod mock {
+    use cmc::api::CmcResult;
+    use cmc::transport::{HttpRequest, HttpResponse, HttpTransport};
+    use reqwest::StatusCode;
+    use serde_json::Value;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::mpsc::{self, Receiver};
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+
+    pub const PRICE: &str = r#"{"status":{"timestamp":"2022-06-16T07:21:13.621Z","error_code":0,"error_message":null,"elapsed":13,"credit_count":1,"notice":null},"data":{"1":{"id":1,"name":"Bitcoin","symbol":"BTC","slug":"bitcoin","num_market_pairs":9534,"date_added":null,"tags":[],"max_supply":21000000,"circulating_supply":19068831,"total_supply":19068831,"is_active":1,"platform":null,"cmc_rank":1,"is_fiat":0,"self_reported_circulating_supply":null,"self_reported_market_cap":null,"tvl_ratio":null,"last_updated":"2022-06-16T07:18:00.000Z","quote":{"USD":{"price":21864.74930690019,"volume_24h":null,"volume_change_24h":null,"percent_change_1h":null,"percent_change_24h":null,"percent_change_7d":null,"percent_change_30d":null,"percent_change_60d":null,"percent_change_90d":null,"market_cap":null,"market_cap_dominance":null,"fully_diluted_market_cap":null,"tvl":null,"last_updated":"2022-06-16T07:18:00.000Z"}}}}}"#;
+    pub const FIAT_MAP: &str = r#"{"status":{"timestamp":"2022-06-16T07:30:51.146Z","error_code":0,"error_message":null,"elapsed":1,"credit_count":1,"notice":null},"data":[{"id":2781,"name":"United States Dollar","sign":"$","symbol":"USD"},{"id":2790,"name":"Euro","sign":"€","symbol":"EUR"}]}"#;
+    pub const EXCHANGE_INFO: &str = r#"{"status":{"timestamp":"2022-06-16T07:30:51.146Z","error_code":0,"error_message":null,"elapsed":1,"credit_count":1,"notice":null},"data":{"binance":{"id":270,"name":"Binance","slug":"binance","description":null,"notice":"","logo":"","countries":[],"fiats":["EUR"],"urls":{"chat":[],"twitter":[],"blog":[],"fee":[],"website":[]},"tags":null,"type":"","date_launched":null,"is_hidden":0,"is_redistributable":null,"maker_fee":0.02,"taker_fee":0.04,"spot_volume_usd":null,"spot_volume_last_updated":null,"weekly_visits":null}}}"#;
+    pub const KEY_INFO: &str = r#"{"status":{"timestamp":"2024-03-12T10:00:00.000Z","error_code":0,"error_message":null,"elapsed":1,"credit_count":0,"notice":null},"data":{"plan":{"credit_limit_monthly":10000,"credit_limit_monthly_reset":"In 19 days","credit_limit_monthly_reset_timestamp":"2024-04-01T00:00:00.000Z","rate_limit_minute":30},"usage":{"current_minute":{"requests_made":0,"requests_left":30},"current_day":{"credits_used":40,"credits_left":null},"current_month":{"credits_used":9990,"credits_left":10}}}}"#;
+    pub const CONVERSION: &str = r#"{"status":{"timestamp":"2022-06-16T07:30:51.146Z","error_code":0,"error_message":null,"elapsed":1,"credit_count":1,"notice":null},"data":{"id":1027,"symbol":"ETH","name":"Ethereum","amount":1.6,"quote":{"2790":{"price":1712.5}}}}"#;
+
+    /// Serves `body` to every request and reports the request line of each one.
+    pub fn serve(body: &'static str) -> (String, Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let (tx, rx) = mpsc::channel();
+
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut buf = [0; 4096];
+                let n = stream.read(&mut buf).unwrap();
+                let request = String::from_utf8_lossy(&buf[..n]);
+                let _ = tx.send(request.lines().next().unwrap_or_default().to_owned());
+                let _ = write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+            }
+        });
+
+        (url, rx)
+    }
+
+    /// Answers each request with the body `respond` builds for it, and records the urls.
+    #[derive(Debug)]
+    pub struct Recorder {
+        pub urls: Arc<Mutex<Vec<String>>>,
+        respond: fn(&HttpRequest) -> String,
+    }
+
+    impl Recorder {
+        pub fn new(respond: fn(&HttpRequest) -> String) -> Self {
+            Self {
+                urls: Arc::default(),
+                respond,
+            }
+        }
+    }
+
+    impl HttpTransport for Recorder {
+        fn send(&self, request: HttpRequest) -> CmcResult<HttpResponse> {
+            self.urls.lock().unwrap().push(request.url.to_string());
+            Ok(HttpResponse::new(StatusCode::OK, (self.respond)(&request)))
+        }
+    }
+
+    /// `body` with the values at the given JSON pointers replaced.
+    pub fn patch(body: &str, values: &[(&str, Value)]) -> String {
+        let mut json: Value = serde_json::from_str(body).unwrap();
+        for (pointer, value) in values {
+            *json.pointer_mut(pointer).unwrap() = value.clone();
+        }
+        json.to_string()
+    }
+
+    /// `body` as answered to a lookup by symbol: a single `data` object becomes a list, `data`
+    /// keyed by id becomes keyed by symbol, with a list of candidates each.
+    pub fn by_symbol(body: &str) -> String {
+        let mut json: Value = serde_json::from_str(body).unwrap();
+        let data = json["data"].take();
+        json["data"] = if data.get("id").is_some() {
+            Value::Array(vec![data])
+        } else {
+            let mut by_symbol = serde_json::Map::new();
+            for (_, cc) in data.as_object().unwrap() {
+                let symbol = cc["symbol"].as_str().unwrap().to_owned();
+                let candidates = by_symbol.entry(symbol).or_insert(Value::Array(Vec::new()));
+                candidates.as_array_mut().unwrap().push(cc.clone());
+            }
+            Value::Object(by_symbol)
+        };
+        json.to_string()
+    }
+
+    /// Two cryptocurrencies sharing the symbol BTC, ids 1 and 2, as answered to a symbol lookup.
+    pub fn two_btc(_request: &HttpRequest) -> String {
+        let mut json: Value = serde_json::from_str(&by_symbol(PRICE)).unwrap();
+        let mut other = json["data"]["BTC"][0].clone();
+        other["id"] = 2.into();
+        json["data"]["BTC"].as_array_mut().unwrap().push(other);
+        json.to_string()
+    }
+}
+
 #[cfg(test)]
 mod deserialize_tests {
     use cmc::api::cryptocurrency::quotes_latest_v2::*;
+    use cmc::api::Amount;
+
+    /// Parses an expected amount, so comparisons hold with and without the `decimal` feature.
+    pub fn amount(s: &str) -> Amount {
+        s.parse().unwrap()
+    }
 
     #[test]
     fn deserialize_struct_id() {
@@ -17,7 +129,7 @@ mod deserialize_tests {
             .price
             .unwrap();
 
-        assert_eq!(price, 21864.74930690019);
+        assert_eq!(price, amount("21864.74930690019"));
     }
 
     #[test]
@@ -35,7 +147,7 @@ mod deserialize_tests {
             .price
             .unwrap();
 
-        assert_eq!(price, 21847.757042255787);
+        assert_eq!(price, amount("21847.757042255787"));
     }
 
     #[test]
@@ -49,19 +161,144 @@ mod deserialize_tests {
             .price
             .unwrap();
 
-        assert_eq!(price, 21871.88352845583);
+        assert_eq!(price, amount("21871.88352845583"));
+    }
+
+    #[cfg(all(feature = "decimal", feature = "tools"))]
+    #[test]
+    fn deserialize_decimal_exact() {
+        use cmc::api::tools::PCv2Id;
+
+        let raw = |amount: &str, price: &str| {
+            super::mock::CONVERSION
+                .replace("1.6", amount)
+                .replace("1712.5", price)
+        };
+        let root: PCv2Id = serde_json::from_str(&raw("12345678901234567890.5", "1.5e-27")).unwrap();
+        assert_eq!(root.data.amount.to_string(), "12345678901234567890.5");
+        assert_eq!(
+            root.data.quote["2790"].price.unwrap().to_string(),
+            "0.0000000000000000000000000015"
+        );
+
+        assert!(serde_json::from_str::<PCv2Id>(&raw("1.6", "1e-30")).is_err());
+        assert!(
+            serde_json::from_str::<PCv2Id>(&raw("1.6", "0.1234567890123456789012345678901"))
+                .is_err()
+        );
+    }
+}
+
+#[cfg(test)]
+mod resolver_tests {
+    use cmc::api::cryptocurrency::CmcIdMap;
+    use cmc::errors::CmcErrors;
+    use cmc::{CmcBuilder, CryptoId, Resolver};
+
+    const ID_MAP: &str = r#"{"status":{"timestamp":"2024-03-11T10:00:00.000Z","error_code":0,"error_message":null,"elapsed":10,"credit_count":1,"notice":null},"data":[{"id":1,"name":"Bitcoin","symbol":"BTC","slug":"bitcoin","rank":1,"is_active":1,"first_historical_data":"2013-04-28T18:47:21.000Z","last_historical_data":"2024-03-11T09:59:00.000Z","platform":null},{"id":1027,"name":"Ethereum","symbol":"ETH","slug":"ethereum","rank":2,"is_active":1,"first_historical_data":"2015-08-07T14:49:30.000Z","last_historical_data":"2024-03-11T09:59:00.000Z","platform":null},{"id":825,"name":"Tether USDt","symbol":"USDT","slug":"tether","rank":3,"is_active":1,"first_historical_data":"2015-02-25T13:34:26.000Z","last_historical_data":"2024-03-11T09:59:00.000Z","platform":{"id":1027,"name":"Ethereum","symbol":"ETH","slug":"ethereum","token_address":"0xdAC17F958D2ee523a2206206994597C13D831ec7"}},{"id":21763,"name":"Bridged Tether","symbol":"USDT","slug":"bridged-tether","rank":0,"is_active":1,"first_historical_data":"2022-09-01T00:00:00.000Z","last_historical_data":"2024-03-11T09:59:00.000Z","platform":{"id":1839,"name":"BNB","symbol":"BNB","slug":"bnb","token_address":"0x55d398326f99059ff775485246999027b3197955"}},{"id":9999,"name":"Old Tether","symbol":"USDT","slug":"old-tether","rank":0,"is_active":0,"first_historical_data":"2018-01-01T00:00:00.000Z","last_historical_data":"2019-01-01T00:00:00.000Z","platform":{"id":1027,"name":"Ethereum","symbol":"ETH","slug":"ethereum","token_address":"0x0000000000000000000000000000000000000001"}}]}"#;
+
+    fn resolver() -> Resolver {
+        let map: CmcIdMap = serde_json::from_str(ID_MAP).unwrap();
+        Resolver::new(map.data)
+    }
+
+    #[test]
+    fn resolve_unique() {
+        let resolver = resolver();
+        assert_eq!(resolver.len(), 5);
+        assert_eq!(resolver.by_symbol("btc").unwrap(), CryptoId(1));
+        assert_eq!(resolver.by_slug("Ethereum").unwrap(), CryptoId(1027));
+        assert_eq!(
+            resolver
+                .by_address("0xdac17f958d2ee523a2206206994597c13d831ec7")
+                .unwrap(),
+            CryptoId(825)
+        );
+        assert!(matches!(
+            resolver.by_symbol("XYZ"),
+            Err(CmcErrors::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn resolve_ambiguous() {
+        let resolver = resolver();
+        assert_eq!(resolver.candidates("USDT").len(), 3);
+
+        match resolver.by_symbol("USDT") {
+            Err(CmcErrors::Ambiguous(query, ids)) => {
+                assert_eq!(query, "USDT");
+                assert_eq!(ids, [CryptoId(825), CryptoId(21763), CryptoId(9999)]);
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        let resolver = resolver.active_only(true);
+        assert!(matches!(
+            resolver.by_symbol("USDT"),
+            Err(CmcErrors::Ambiguous(_, ids)) if ids == [CryptoId(825), CryptoId(21763)]
+        ));
+    }
+
+    #[test]
+    fn resolve_rules() {
+        assert_eq!(
+            resolver().highest_rank(true).by_symbol("USDT").unwrap(),
+            CryptoId(825)
+        );
+        assert_eq!(
+            resolver()
+                .active_only(true)
+                .prefer_platform("bnb")
+                .by_symbol("USDT")
+                .unwrap(),
+            CryptoId(21763)
+        );
+        // The inactive token is also on Ethereum, so only `active_only` disambiguates it.
+        assert!(resolver()
+            .prefer_platform("ethereum")
+            .by_symbol("USDT")
+            .is_err());
+        assert_eq!(
+            resolver()
+                .active_only(true)
+                .prefer_platform("solana")
+                .prefer_platform("ethereum")
+                .by_symbol("USDT")
+                .unwrap(),
+            CryptoId(825)
+        );
+    }
+
+    #[test]
+    fn resolve_lists_inactive() {
+        let (url, request) = super::mock::serve(ID_MAP);
+        let cmc = CmcBuilder::new("key").base_url(&url).build().unwrap();
+
+        let resolver = cmc.resolver().unwrap().active_only(true);
+        assert!(matches!(
+            resolver.by_symbol("USDT"),
+            Err(CmcErrors::Ambiguous(_, ids)) if ids.len() == 2
+        ));
+        let request = request.recv().unwrap();
+        assert!(
+            request.contains("listing_status=active%2Cinactive%2Cuntracked"),
+            "{}",
+            request
+        );
     }
 }
 
 #[cfg(test)]
 mod network_tests {
+    use super::deserialize_tests::amount;
     use cmc::{Cmc, CmcBuilder, ListingStatusExchange, Pass, Sort, SortExchange, SortFiat};
     const APIKEY: &str = env!("CMC_API");
 
     #[test]
     fn net_price() {
         let cmc = Cmc::new(APIKEY);
-        assert!(cmc.price("BTC").unwrap() > 0.1)
+        assert!(cmc.price("BTC").unwrap() > amount("0.1"))
     }
 
     #[test]
@@ -69,9 +306,10 @@ mod network_tests {
         let cmc = CmcBuilder::new(APIKEY)
             .pass(Pass::Id)
             .convert("EUR")
-            .build();
+            .build()
+            .unwrap();
 
-        assert!(cmc.price("1027").unwrap() > 0.1);
+        assert!(cmc.price("1027").unwrap() > amount("0.1"));
     }
 
     #[test]
@@ -79,9 +317,10 @@ mod network_tests {
         let cmc = CmcBuilder::new(APIKEY)
             .pass(Pass::Slug)
             .convert("EUR")
-            .build();
+            .build()
+            .unwrap();
 
-        assert!(cmc.price("bitcoin").unwrap() > 0.1);
+        assert!(cmc.price("bitcoin").unwrap() > amount("0.1"));
     }
 
     #[test]
@@ -109,55 +348,59 @@ mod network_tests {
     #[test]
     fn net_price_conversion() {
         let cmc = Cmc::new(APIKEY);
-        let price = cmc.price_conversion(2.5, "BTC", None, "usd").unwrap();
-        assert!(price > 0.1);
+        let price = cmc
+            .price_conversion(2.5, "BTC", None::<i64>, "usd")
+            .unwrap();
+        assert!(price > amount("0.1"));
     }
 
     #[test]
     fn net_price_conversion_id() {
         let cmc = Cmc::new(APIKEY);
-        let price = cmc.price_conversion_id(2.5, "1", None, "2781").unwrap();
-        assert!(price > 0.1);
+        let price = cmc
+            .price_conversion_id(2.5, "1", None::<i64>, "2781")
+            .unwrap();
+        assert!(price > amount("0.1"));
     }
 
     #[test]
     fn net_categories() {
-        let cmc = CmcBuilder::new(APIKEY).pass(Pass::Id).build();
+        let cmc = CmcBuilder::new(APIKEY).pass(Pass::Id).build().unwrap();
         let categories = cmc.categories(1, 10, "1027").unwrap();
         assert!(categories.data[0].market_cap > 0.1)
     }
 
     #[test]
     fn net_category() {
-        let cmc = CmcBuilder::new(APIKEY).convert_id("1027").build();
+        let cmc = CmcBuilder::new(APIKEY).convert_id("1027").build().unwrap();
         let category = cmc.category("605e2ce9d41eae1066535f7c", 1, 10).unwrap();
         assert!(category.volume > 0.1)
     }
 
     #[test]
     fn net_metadata_id() {
-        let cmc = CmcBuilder::new(APIKEY).pass(Pass::Id).build();
+        let cmc = CmcBuilder::new(APIKEY).pass(Pass::Id).build().unwrap();
         let name = cmc.metadata("1027").unwrap().name;
         assert_eq!("Ethereum", name);
     }
 
     #[test]
     fn net_metadata_symbol() {
-        let cmc = CmcBuilder::new(APIKEY).pass(Pass::Symbol).build();
+        let cmc = CmcBuilder::new(APIKEY).pass(Pass::Symbol).build().unwrap();
         let name = cmc.metadata("ETH").unwrap().name;
         assert_eq!("Ethereum", name);
     }
 
     #[test]
     fn net_metadata_slug() {
-        let cmc = CmcBuilder::new(APIKEY).pass(Pass::Slug).build();
+        let cmc = CmcBuilder::new(APIKEY).pass(Pass::Slug).build().unwrap();
         let name = cmc.metadata("ethereum").unwrap().name;
         assert_eq!("Ethereum", name);
     }
 
     #[test]
     fn net_metadata_address() {
-        let cmc = CmcBuilder::new(APIKEY).pass(Pass::Address).build();
+        let cmc = CmcBuilder::new(APIKEY).pass(Pass::Address).build().unwrap();
         let id = cmc
             // contract address
             .metadata("0xdAC17F958D2ee523a2206206994597C13D831ec7")
@@ -178,7 +421,7 @@ mod network_tests {
 
     #[test]
     fn net_global_metrics_convert() {
-        let cmc = CmcBuilder::new(APIKEY).convert("ETH").build();
+        let cmc = CmcBuilder::new(APIKEY).convert("ETH").build().unwrap();
 
         let active_cc = cmc.global_metrics().unwrap().active_cryptocurrencies;
 
@@ -187,7 +430,7 @@ mod network_tests {
 
     #[test]
     fn net_global_metrics_convert_id() {
-        let cmc = CmcBuilder::new(APIKEY).convert_id("1027").build();
+        let cmc = CmcBuilder::new(APIKEY).convert_id("1027").build().unwrap();
 
         let total = cmc
             .global_metrics()
@@ -197,12 +440,12 @@ mod network_tests {
             .unwrap()
             .total_market_cap;
 
-        assert!(total > 1.0);
+        assert!(total > amount("1.0"));
     }
 
     #[test]
     fn net_exchange_metadata_id() {
-        let cmc = CmcBuilder::new(APIKEY).pass(Pass::Id).build();
+        let cmc = CmcBuilder::new(APIKEY).pass(Pass::Id).build().unwrap();
 
         let metadata = cmc.exchange_metadata("270").unwrap().data;
 
@@ -213,7 +456,7 @@ mod network_tests {
 
     #[test]
     fn net_exchange_metadata_slug() {
-        let cmc = CmcBuilder::new(APIKEY).pass(Pass::Slug).build();
+        let cmc = CmcBuilder::new(APIKEY).pass(Pass::Slug).build().unwrap();
 
         let metadata = cmc.exchange_metadata("binance").unwrap().data;
 
@@ -237,7 +480,7 @@ mod network_tests {
             .price
             .unwrap();
 
-        assert!(price > 0.1);
+        assert!(price > amount("0.1"));
     }
 
     #[test]
@@ -255,7 +498,7 @@ mod network_tests {
             .price
             .unwrap();
 
-        assert!(price > 0.1);
+        assert!(price > amount("0.1"));
     }
 
     #[test]
@@ -273,7 +516,7 @@ mod network_tests {
             .price
             .unwrap();
 
-        assert!(price > 0.1);
+        assert!(price > amount("0.1"));
     }
 
     #[test]
@@ -288,3 +531,1678 @@ mod network_tests {
         assert_eq!(16, id);
     }
 }
+
+#[cfg(test)]
+mod cache_tests {
+    use super::mock::{serve, FIAT_MAP};
+    use cmc::{CmcBuilder, RefCache, SortFiat};
+    use std::time::Duration;
+
+    fn cache_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("cmc-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn cache_warm_start() {
+        let (url, requests) = serve(FIAT_MAP);
+        let dir = cache_dir("warm-start");
+
+        let cmc = CmcBuilder::new("key")
+            .base_url(&url)
+            .cache(RefCache::new(&dir))
+            .build()
+            .unwrap();
+        assert_eq!(
+            cmc.fiat_id_map(1, 10, SortFiat::Id).unwrap().data[1].symbol,
+            "EUR"
+        );
+        assert_eq!(cmc.fiat_id_map(1, 10, SortFiat::Id).unwrap().data.len(), 2);
+        assert_eq!(requests.try_iter().count(), 1);
+
+        // A new client reuses the entry written by the previous one.
+        let cmc = CmcBuilder::new("key")
+            .base_url(&url)
+            .cache(RefCache::new(&dir))
+            .build()
+            .unwrap();
+        assert_eq!(
+            cmc.fiat_id_map(1, 10, SortFiat::Id).unwrap().data[0].symbol,
+            "USD"
+        );
+        assert_eq!(requests.try_iter().count(), 0);
+
+        // Different parameters are cached separately.
+        cmc.fiat_id_map(1, 10, SortFiat::Name).unwrap();
+        assert_eq!(requests.try_iter().count(), 1);
+
+        RefCache::new(&dir).clear().unwrap();
+    }
+
+    #[test]
+    fn cache_stale_refresh() {
+        let (url, requests) = serve(FIAT_MAP);
+        let dir = cache_dir("stale-refresh");
+
+        let cmc = CmcBuilder::new("key")
+            .base_url(&url)
+            .cache(RefCache::new(&dir).max_age(Duration::ZERO))
+            .build()
+            .unwrap();
+        cmc.fiat_id_map(1, 10, SortFiat::Id).unwrap();
+        assert_eq!(requests.try_iter().count(), 1);
+
+        // The stale entry is served right away and refreshed in the background.
+        assert_eq!(cmc.fiat_id_map(1, 10, SortFiat::Id).unwrap().data.len(), 2);
+        assert!(requests.recv_timeout(Duration::from_secs(1)).is_ok());
+
+        RefCache::new(&dir).clear().unwrap();
+    }
+}
+
+#[cfg(test)]
+mod ids_tests {
+    use super::deserialize_tests::amount;
+    use super::mock::{serve, PRICE};
+    use cmc::errors::CmcErrors;
+    use cmc::ids::Identifiers;
+    use cmc::{AssetRef, CmcBuilder, CryptoId, CurrencyId, FiatId, Pass, Slug, Symbol};
+
+    #[test]
+    fn ids_normalize() {
+        assert_eq!(Slug::new("Bitcoin").as_str(), "bitcoin");
+        assert_eq!(Symbol::new("btc").as_str(), "BTC");
+        assert_eq!("1027".parse::<CryptoId>().unwrap(), CryptoId(1027));
+        assert_eq!(AssetRef::from(CryptoId(1)).to_string(), "1");
+    }
+
+    #[test]
+    fn ids_join() {
+        assert_eq!(
+            Identifiers::<CryptoId>::to_query(&[CryptoId(1), CryptoId(1027)]),
+            "1,1027"
+        );
+        assert_eq!(
+            Identifiers::<Symbol>::to_query(&vec![Symbol::new("btc"), Symbol::new("eth")]),
+            "BTC,ETH"
+        );
+        assert_eq!(
+            Identifiers::<CurrencyId>::to_query(&[
+                CurrencyId::from(CryptoId(1)),
+                CurrencyId::from(FiatId(2781))
+            ]),
+            "1,2781"
+        );
+    }
+
+    #[test]
+    fn ids_override_pass() {
+        let (url, request) = serve(PRICE);
+        let cmc = CmcBuilder::new("key")
+            .base_url(&url)
+            .pass(Pass::Symbol)
+            .build()
+            .unwrap();
+
+        assert_eq!(cmc.price(CryptoId(1)).unwrap(), amount("21864.74930690019"));
+        let request = request.recv().unwrap();
+        assert!(request.contains("id=1"), "{}", request);
+        assert!(!request.contains("symbol="), "{}", request);
+    }
+
+    #[test]
+    fn ids_reject_list() {
+        let cmc = CmcBuilder::new("key").build().unwrap();
+        assert!(matches!(
+            cmc.price("BTC,ETH"),
+            Err(CmcErrors::IncorrectQuery)
+        ));
+    }
+}
+
+#[cfg(test)]
+mod request_tests {
+    use super::deserialize_tests::amount;
+    use super::mock::{serve, two_btc, Recorder, CONVERSION, EXCHANGE_INFO, PRICE};
+    use cmc::errors::CmcErrors;
+    use cmc::{CmcBuilder, CryptoId, FiatId, Pass};
+
+    #[test]
+    fn request_price_ambiguous_symbol() {
+        let cmc = CmcBuilder::new("key")
+            .transport(Recorder::new(two_btc))
+            .build()
+            .unwrap();
+        match cmc.price("btc") {
+            Err(CmcErrors::Ambiguous(symbol, ids)) => {
+                assert_eq!(symbol, "BTC");
+                assert_eq!(ids, vec![CryptoId(1), CryptoId(2)]);
+            }
+            other => panic!("expected an ambiguous symbol, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn request_exchange_by_slug() {
+        let (url, request) = serve(EXCHANGE_INFO);
+        let cmc = CmcBuilder::new("key").base_url(&url).build().unwrap();
+
+        // The default `Pass::Symbol` does not apply to per-call requests.
+        let metadata = cmc.exchange_info().by_slug("Binance").send().unwrap();
+        assert_eq!(metadata.data["binance"].id, 270);
+        let request = request.recv().unwrap();
+        assert!(
+            request.contains("v1/exchange/info?slug=binance "),
+            "{}",
+            request
+        );
+    }
+
+    #[test]
+    fn request_convert_per_call() {
+        let (url, request) = serve(CONVERSION);
+        let cmc = CmcBuilder::new("key")
+            .base_url(&url)
+            .pass(Pass::Symbol)
+            .convert("USD")
+            .build()
+            .unwrap();
+
+        let price = cmc
+            .conversion(1.6)
+            .by_id(CryptoId(1027))
+            .convert_id(FiatId(2790))
+            .time("2022-06-01")
+            .send()
+            .unwrap();
+        assert_eq!(price, amount("1712.5"));
+        let request = request.recv().unwrap();
+        assert!(request.contains("id=1027"), "{}", request);
+        assert!(request.contains("time=2022-06-01"), "{}", request);
+        assert!(request.contains("convert_id=2790"), "{}", request);
+        assert!(!request.contains("convert=USD"), "{}", request);
+    }
+
+    #[test]
+    fn request_convert_default() {
+        let (url, request) = serve(PRICE);
+        let cmc = CmcBuilder::new("key").base_url(&url).build().unwrap();
+
+        let quotes = cmc.quotes().by_id(CryptoId(1)).send().unwrap();
+        assert_eq!(quotes.data["1"].symbol, "BTC");
+        let request = request.recv().unwrap();
+        assert!(request.contains("id=1&convert=USD"), "{}", request);
+    }
+
+    #[test]
+    fn request_with_status() {
+        let (url, _request) = serve(CONVERSION);
+        let cmc = CmcBuilder::new("key").base_url(&url).build().unwrap();
+        let response = cmc
+            .conversion(1.6)
+            .by_id(CryptoId(1027))
+            .convert_id(FiatId(2790))
+            .send_with_status()
+            .unwrap();
+        assert_eq!(response.data, amount("1712.5"));
+        assert_eq!(response.status.credit_count, 1);
+        assert_eq!(response.status.notice, None);
+    }
+
+    #[test]
+    fn request_conversion_time() {
+        let (url, request) = serve(CONVERSION);
+        let cmc = CmcBuilder::new("key").base_url(&url).build().unwrap();
+
+        let price = cmc
+            .price_conversion_id(1.6, CryptoId(1027), Some(1654041600_i64), FiatId(2790))
+            .unwrap();
+        assert_eq!(price, amount("1712.5"));
+        let request = request.recv().unwrap();
+        assert!(request.contains("time=1654041600"), "{}", request);
+    }
+}
+
+#[cfg(test)]
+mod fixture_tests {
+    use super::deserialize_tests::amount;
+    use cmc::api::cryptocurrency::{CmcIdMap, MDv2, QLv2Id};
+    use cmc::api::exchange::ExchangeMetadata;
+
+    #[test]
+    fn fixture_quotes_latest() {
+        let root: QLv2Id =
+            serde_json::from_str(include_str!("fixtures/quotes_latest_v2.json")).unwrap();
+
+        let btc = &root.data["1"];
+        assert_eq!(btc.max_supply, Some(21000000.0));
+        assert_eq!(btc.cmc_rank, Some(1));
+        assert_eq!(btc.platform, None);
+        assert_eq!(btc.tags[0].slug, "mineable");
+        assert_eq!(btc.quote["USD"].market_cap_dominance, Some(52.2539));
+        assert_eq!(btc.quote["USD"].tvl, None);
+
+        // Numbers encoded as strings, whole floats, empty strings and missing fields.
+        let usdt = &root.data["825"];
+        assert_eq!(usdt.num_market_pairs, Some(84012));
+        assert_eq!(usdt.max_supply, None);
+        assert_eq!(usdt.circulating_supply, Some(103218446371.85));
+        assert_eq!(usdt.cmc_rank, Some(3));
+        assert_eq!(usdt.self_reported_circulating_supply, None);
+        assert!(usdt.tags.is_empty());
+        let platform = usdt.platform.as_ref().unwrap();
+        assert_eq!(platform.id, Some(1027));
+        assert_eq!(
+            platform.token_address.as_deref(),
+            Some("0xdac17f958d2ee523a2206206994597c13d831ec7")
+        );
+        let quote = &usdt.quote["USD"];
+        assert_eq!(quote.price, Some(amount("1.0003")));
+        assert_eq!(quote.percent_change_1h, None);
+        assert_eq!(quote.percent_change_24h, Some(0.0123));
+        assert_eq!(quote.volume_change_24h, None);
+    }
+
+    #[test]
+    fn fixture_metadata() {
+        let root: MDv2 = serde_json::from_str(include_str!("fixtures/metadata_v2.json")).unwrap();
+
+        let usdt = &root.data["825"];
+        assert_eq!(usdt.date_launched, None);
+        assert_eq!(usdt.self_reported_market_cap, Some(0.0));
+        assert!(usdt.self_reported_tags.is_empty());
+        assert_eq!(usdt.urls.website, ["https://tether.to"]);
+        assert!(usdt.urls.message_board.is_empty());
+        assert!(usdt.urls.chat.is_empty());
+        assert_eq!(usdt.urls.explorer.len(), 2);
+        assert_eq!(usdt.contract_address.len(), 2);
+        assert_eq!(usdt.contract_address[0].platform.coin.id, Some(1027));
+        assert_eq!(usdt.contract_address[1].platform.coin.id, Some(1958));
+        assert_eq!(
+            usdt.contract_address[1].contract_address,
+            "TR7NHqjeKQxGTCi8q8ZY4pL8otSzgjLj6t"
+        );
+    }
+
+    #[test]
+    fn fixture_exchange_info() {
+        let root: ExchangeMetadata =
+            serde_json::from_str(include_str!("fixtures/exchange_info.json")).unwrap();
+
+        let binance = &root.data["270"];
+        assert!(binance
+            .description
+            .as_deref()
+            .unwrap()
+            .starts_with("Launched"));
+        assert!(binance.tags.is_empty());
+        assert_eq!(binance.is_redistributable, Some(true));
+        assert_eq!(binance.spot_volume_usd, Some(12771898281.9346));
+        assert_eq!(binance.weekly_visits, Some(16352342));
+        assert_eq!(binance.urls.fee, ["https://www.binance.com/fees.html"]);
+
+        let coinbase = &root.data["89"];
+        assert_eq!(coinbase.description, None);
+        assert!(coinbase.countries.is_empty());
+        assert_eq!(coinbase.tags, ["regulated"]);
+        assert_eq!(coinbase.date_launched, None);
+        assert_eq!(coinbase.is_redistributable, Some(false));
+        assert_eq!(coinbase.spot_volume_usd, None);
+        assert!(coinbase.urls.twitter.is_empty());
+        assert!(coinbase.urls.blog.is_empty());
+    }
+
+    #[test]
+    fn fixture_id_map() {
+        let root: CmcIdMap = serde_json::from_str(include_str!("fixtures/id_map.json")).unwrap();
+
+        assert_eq!(root.data[0].platform, None);
+        let platform = root.data[1].platform.as_ref().unwrap();
+        assert_eq!(platform.slug, "ethereum");
+        assert_eq!(platform.id, Some(1027));
+    }
+
+    #[test]
+    #[cfg(feature = "chrono")]
+    fn fixture_timestamps() {
+        use chrono::{TimeZone, Utc};
+
+        let root: QLv2Id =
+            serde_json::from_str(include_str!("fixtures/quotes_latest_v2.json")).unwrap();
+        let btc = &root.data["1"];
+        assert_eq!(
+            btc.last_updated,
+            Utc.with_ymd_and_hms(2024, 3, 11, 9, 59, 0).unwrap()
+        );
+        assert_eq!(
+            btc.date_added,
+            Some(Utc.with_ymd_and_hms(2010, 7, 13, 0, 0, 0).unwrap())
+        );
+
+        let root: ExchangeMetadata =
+            serde_json::from_str(include_str!("fixtures/exchange_info.json")).unwrap();
+        assert_eq!(root.data["89"].date_launched, None);
+    }
+
+    #[test]
+    #[cfg(not(feature = "chrono"))]
+    fn fixture_timestamps() {
+        let root: QLv2Id =
+            serde_json::from_str(include_str!("fixtures/quotes_latest_v2.json")).unwrap();
+        let btc = &root.data["1"];
+        assert_eq!(btc.last_updated, "2024-03-11T09:59:00.000Z");
+        assert_eq!(btc.date_added.as_deref(), Some("2010-07-13T00:00:00.000Z"));
+    }
+
+    #[test]
+    #[cfg(feature = "decimal")]
+    fn fixture_decimal() {
+        use cmc::api::tools::PCv2Id;
+
+        let root: QLv2Id =
+            serde_json::from_str(include_str!("fixtures/quotes_latest_v2.json")).unwrap();
+        let quote = &root.data["1"].quote["USD"];
+        assert_eq!(quote.price.unwrap().to_string(), "71474.89112364418");
+        assert_eq!(quote.market_cap.unwrap().to_string(), "1404652961098.2183");
+        assert_eq!(root.data["825"].quote["USD"].price, Some(amount("1.0003")));
+
+        let raw = r#"{"status":{"timestamp":"2022-06-16T07:30:51.146Z","error_code":0,"error_message":null,"elapsed":1,"credit_count":1,"notice":null},"data":{"id":1,"symbol":"BTC","name":"Bitcoin","amount":0.1,"quote":{"USD":{"price":0.2,"last_updated":"2022-06-16T07:30:00.000Z"},"SATS":{"price":1.5e-8,"last_updated":"2022-06-16T07:30:00.000Z"}}}}"#;
+        let root: PCv2Id = serde_json::from_str(raw).unwrap();
+        let usd = root.data.quote["USD"].price.unwrap();
+        assert_eq!(root.data.amount + usd, amount("0.3"));
+        assert_eq!(root.data.quote["SATS"].price, Some(amount("0.000000015")));
+    }
+
+    #[test]
+    fn fixture_status() {
+        use cmc::api::response::Response;
+
+        let raw = r#"{"status":{"timestamp":"2022-06-16T07:30:51.146Z","error_code":0,"error_message":"","elapsed":12,"credit_count":2,"notice":"This endpoint is deprecated."},"data":[]}"#;
+        let root: Response<Vec<u64>> = serde_json::from_str(raw).unwrap();
+        assert_eq!(root.status.error_message, None);
+        assert_eq!(root.status.elapsed, 12);
+        assert_eq!(root.status.credit_count, 2);
+        assert_eq!(
+            root.status.notice.as_deref(),
+            Some("This endpoint is deprecated.")
+        );
+
+        let root = root.map(|data| data.len());
+        assert_eq!(root.data, 0);
+    }
+
+    #[test]
+    fn fixture_roundtrip() {
+        let root: QLv2Id =
+            serde_json::from_str(include_str!("fixtures/quotes_latest_v2.json")).unwrap();
+        let again: QLv2Id = serde_json::from_str(&serde_json::to_string(&root).unwrap()).unwrap();
+        assert_eq!(root, again);
+    }
+}
+
+#[cfg(test)]
+mod ledger_tests {
+    use super::mock::{serve, KEY_INFO, PRICE};
+    use cmc::api::key::KeyInfo;
+    use cmc::errors::CmcErrors;
+    use cmc::ledger::Day;
+    use cmc::{CmcBuilder, CryptoId, Ledger};
+    use std::thread;
+
+    #[test]
+    fn ledger_counts_credits() {
+        let (url, _request) = serve(PRICE);
+        let cmc = CmcBuilder::new("key").base_url(&url).build().unwrap();
+
+        cmc.price(CryptoId(1)).unwrap();
+        let ledger = cmc.ledger();
+        assert_eq!(ledger.total(), 1);
+        assert_eq!(ledger.per_endpoint()["v2/cryptocurrency/quotes/latest"], 1);
+        assert_eq!(ledger.per_day()[&Day::today()], 1);
+        assert_eq!(ledger.spent_today(), 1);
+        assert_eq!(ledger.remaining(), None);
+    }
+
+    #[test]
+    fn ledger_budget_fails_fast() {
+        let (url, request) = serve(PRICE);
+        let ledger = Ledger::new().daily_budget(1);
+        let cmc = CmcBuilder::new("key")
+            .base_url(&url)
+            .ledger(ledger.clone())
+            .build()
+            .unwrap();
+
+        cmc.price(CryptoId(1)).unwrap();
+        assert_eq!(ledger.remaining(), Some(0));
+        match cmc.price(CryptoId(1)) {
+            Err(CmcErrors::BudgetExceeded(1, 1)) => {}
+            other => panic!("{:?}", other),
+        }
+        assert_eq!(request.try_iter().count(), 1);
+    }
+
+    #[test]
+    fn ledger_budget_concurrent() {
+        let (url, request) = serve(PRICE);
+        let ledger = Ledger::new().daily_budget(1);
+        let cmc = CmcBuilder::new("key")
+            .base_url(&url)
+            .ledger(ledger.clone())
+            .build()
+            .unwrap();
+
+        // Calls in flight hold their credit, so only one of them gets through.
+        let sent = thread::scope(|scope| {
+            let calls: Vec<_> = (0..4)
+                .map(|_| scope.spawn(|| cmc.price(CryptoId(1)).is_ok()))
+                .collect();
+            calls
+                .into_iter()
+                .map(|call| call.join().unwrap())
+                .filter(|sent| *sent)
+                .count()
+        });
+        assert_eq!(sent, 1);
+        assert_eq!(request.try_iter().count(), 1);
+        assert_eq!(ledger.total(), 1);
+    }
+
+    #[test]
+    fn ledger_seed() {
+        let raw = r#"{"plan":{"credit_limit_monthly":10000,"credit_limit_monthly_reset":"In 19 days","credit_limit_monthly_reset_timestamp":"2024-04-01T00:00:00.000Z","rate_limit_minute":30},"usage":{"current_minute":{"requests_made":0,"requests_left":30},"current_day":{"credits_used":40,"credits_left":null},"current_month":{"credits_used":9990,"credits_left":10}}}"#;
+        let info: KeyInfo = serde_json::from_str(raw).unwrap();
+        let ledger = Ledger::new().daily_budget(100);
+        ledger.seed(&info);
+
+        assert_eq!(ledger.total(), 0);
+        assert_eq!(ledger.spent_today(), 40);
+        assert_eq!(ledger.spent_this_month(), 9990);
+        assert_eq!(ledger.remaining(), Some(10));
+    }
+
+    #[test]
+    fn ledger_budget_spent_key_info() {
+        let (url, request) = serve(KEY_INFO);
+        let ledger = Ledger::new().daily_budget(1);
+        let cmc = CmcBuilder::new("key")
+            .base_url(&url)
+            .ledger(ledger.clone())
+            .build()
+            .unwrap();
+
+        // 40 credits used today, far above the budget: only free calls still go through.
+        ledger.seed(&cmc.key_info().unwrap());
+        assert_eq!(ledger.remaining(), Some(0));
+        assert!(cmc.key_info().is_ok());
+        cmc.refresh_key_credits().unwrap();
+        assert!(matches!(
+            cmc.price(CryptoId(1)),
+            Err(CmcErrors::BudgetExceeded(40, 1))
+        ));
+        assert_eq!(request.try_iter().count(), 3);
+    }
+}
+
+#[cfg(test)]
+mod transport_tests {
+    use super::mock::{serve, PRICE};
+    use cmc::api::CmcResult;
+    use cmc::errors::CmcErrors;
+    use cmc::transport::{HttpRequest, HttpResponse, HttpTransport};
+    use cmc::{CmcBuilder, CryptoId};
+    use reqwest::StatusCode;
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+    use std::time::Duration;
+
+    #[derive(Debug, Default)]
+    struct Fake {
+        requests: Arc<Mutex<Vec<HttpRequest>>>,
+    }
+
+    impl HttpTransport for Fake {
+        fn send(&self, request: HttpRequest) -> CmcResult<HttpResponse> {
+            self.requests.lock().unwrap().push(request);
+            Ok(HttpResponse::new(StatusCode::OK, PRICE))
+        }
+    }
+
+    #[test]
+    fn transport_custom() {
+        let fake = Fake::default();
+        let requests = fake.requests.clone();
+        let cmc = CmcBuilder::new("key").transport(fake).build().unwrap();
+
+        let quotes = cmc.quotes().by_id(CryptoId(1)).send().unwrap();
+        assert_eq!(quotes.data["1"].symbol, "BTC");
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(
+            requests[0].url.as_str(),
+            "https://pro-api.coinmarketcap.com/v2/cryptocurrency/quotes/latest?id=1&convert=USD"
+        );
+        assert!(requests[0]
+            .headers
+            .contains(&("X-CMC_PRO_API_KEY".to_owned(), "key".to_owned())));
+    }
+
+    #[test]
+    fn transport_reqwest_client() {
+        let (url, request) = serve(PRICE);
+        let client = reqwest::blocking::Client::builder()
+            .user_agent("cmc-test")
+            .build()
+            .unwrap();
+        let cmc = CmcBuilder::new("key")
+            .base_url(&url)
+            .client(client)
+            .build()
+            .unwrap();
+
+        let quotes = cmc.quotes().by_id(CryptoId(1)).send().unwrap();
+        assert_eq!(quotes.data["1"].symbol, "BTC");
+        let request = request.recv().unwrap();
+        assert!(request.contains("id=1&convert=USD"), "{}", request);
+    }
+
+    #[test]
+    fn transport_headers_and_timeout() {
+        let fake = Fake::default();
+        let requests = fake.requests.clone();
+        let cmc = CmcBuilder::new("key")
+            .transport(fake)
+            .user_agent("cmc-test")
+            .header("X-Trace", "1")
+            .timeout(Duration::from_secs(10))
+            .build()
+            .unwrap();
+
+        cmc.quotes().by_id(CryptoId(1)).send().unwrap();
+        cmc.quotes()
+            .by_id(CryptoId(1))
+            .timeout(Duration::from_secs(1))
+            .send()
+            .unwrap();
+
+        let requests = requests.lock().unwrap();
+        let headers = &requests[0].headers;
+        assert!(headers.contains(&("User-Agent".to_owned(), "cmc-test".to_owned())));
+        assert!(headers.contains(&("X-Trace".to_owned(), "1".to_owned())));
+        assert_eq!(requests[0].timeout, Some(Duration::from_secs(10)));
+        assert_eq!(requests[1].timeout, Some(Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn transport_timeout() {
+        // Accepts connections, but never answers.
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        thread::spawn(move || {
+            let _streams: Vec<_> = listener.incoming().collect();
+        });
+
+        let cmc = CmcBuilder::new("key")
+            .base_url(&url)
+            .timeout(Duration::from_secs(30))
+            .build()
+            .unwrap();
+        let result = cmc
+            .quotes()
+            .by_id(CryptoId(1))
+            .timeout(Duration::from_millis(200))
+            .send();
+        assert!(matches!(result, Err(CmcErrors::Timeout)), "{:?}", result);
+    }
+}
+
+#[cfg(test)]
+mod config_tests {
+    use super::mock::{serve, PRICE};
+    use cmc::errors::{CmcErrors, ConfigError};
+    use cmc::{Cmc, CmcBuilder, CryptoId, Environment};
+
+    fn config_error(builder: CmcBuilder) -> ConfigError {
+        match builder.build() {
+            Err(CmcErrors::ConfigError(err)) => err,
+            other => panic!(
+                "expected a configuration error, got {:?}",
+                other.map(|_| ())
+            ),
+        }
+    }
+
+    #[test]
+    fn config_valid() {
+        assert!(CmcBuilder::new("key")
+            .convert("eur,btc")
+            .base_url("http://localhost:8080/")
+            .build()
+            .is_ok());
+        assert!(CmcBuilder::new("key").convert_id("2790,1").build().is_ok());
+    }
+
+    #[test]
+    fn config_invalid() {
+        assert_eq!(config_error(CmcBuilder::new(" ")), ConfigError::EmptyApiKey);
+        assert!(matches!(
+            config_error(CmcBuilder::new("key").base_url("localhost/")),
+            ConfigError::InvalidBaseUrl(..)
+        ));
+        assert_eq!(
+            config_error(CmcBuilder::new("key").base_url("http://localhost")),
+            ConfigError::BaseUrlWithoutSlash("http://localhost".into())
+        );
+        assert_eq!(
+            config_error(CmcBuilder::new("key").convert("US D")),
+            ConfigError::InvalidCurrency("US D".into())
+        );
+        assert_eq!(
+            config_error(CmcBuilder::new("key").convert_id("eur")),
+            ConfigError::InvalidCurrency("eur".into())
+        );
+        assert_eq!(
+            config_error(CmcBuilder::new("key").convert("EUR").convert_id("2790")),
+            ConfigError::ConvertConflict
+        );
+    }
+
+    #[test]
+    fn config_new_empty_key() {
+        let cmc = Cmc::new(" ");
+        assert!(matches!(
+            cmc.quotes().by_id(CryptoId(1)).send(),
+            Err(CmcErrors::ConfigError(ConfigError::EmptyApiKey))
+        ));
+    }
+
+    #[test]
+    fn config_environment() {
+        assert_eq!(
+            Environment::Sandbox.base_url(),
+            "https://sandbox-api.coinmarketcap.com/"
+        );
+
+        let (url, request) = serve(PRICE);
+        let cmc = CmcBuilder::new("key")
+            .environment(Environment::Custom(url))
+            .build()
+            .unwrap();
+        cmc.quotes().by_id(CryptoId(1)).send().unwrap();
+        assert!(request
+            .recv()
+            .unwrap()
+            .contains("v2/cryptocurrency/quotes/latest"));
+    }
+
+    #[test]
+    fn config_from_env() {
+        let (url, request) = serve(PRICE);
+        std::env::set_var("CMC_API_KEY", "secret-key");
+        std::env::set_var("CMC_BASE_URL", &url);
+        std::env::set_var("CMC_CONVERT", "eur");
+        let cmc = CmcBuilder::from_env().build().unwrap();
+        std::env::remove_var("CMC_API_KEY");
+        std::env::remove_var("CMC_BASE_URL");
+        std::env::remove_var("CMC_CONVERT");
+
+        assert!(!format!("{:?}", cmc).contains("secret-key"));
+        let _ = cmc.quotes().by_id(CryptoId(1)).send();
+        assert!(request.recv().unwrap().contains("convert=EUR"));
+        assert!(matches!(
+            CmcBuilder::from_env().build(),
+            Err(CmcErrors::ConfigError(ConfigError::EmptyApiKey))
+        ));
+    }
+}
+
+#[cfg(test)]
+mod keys_tests {
+    use super::mock::PRICE;
+    use cmc::api::CmcResult;
+    use cmc::errors::CmcErrors;
+    use cmc::transport::{HttpRequest, HttpResponse, HttpTransport};
+    use cmc::{CmcBuilder, CryptoId, KeyPool, Strategy};
+    use reqwest::StatusCode;
+    use std::sync::{Arc, Mutex};
+
+    const RATE_LIMITED: &str = r#"{"status":{"timestamp":"2022-06-16T07:30:51.146Z","error_code":1008,"error_message":"You've exceeded your API Key's HTTP request rate limit.","elapsed":0,"credit_count":0}}"#;
+
+    /// Rate limits the key "limited", answers all others. Records the keys used.
+    #[derive(Debug, Default)]
+    struct Keyed {
+        used: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl HttpTransport for Keyed {
+        fn send(&self, request: HttpRequest) -> CmcResult<HttpResponse> {
+            let key = request
+                .headers
+                .iter()
+                .find(|(name, _)| name == "X-CMC_PRO_API_KEY")
+                .map(|(_, key)| key.clone())
+                .unwrap();
+            self.used.lock().unwrap().push(key.clone());
+            Ok(match key.as_str() {
+                "limited" => HttpResponse::new(StatusCode::TOO_MANY_REQUESTS, RATE_LIMITED),
+                "proxied" => HttpResponse::new(
+                    StatusCode::TOO_MANY_REQUESTS,
+                    "<html><body>Too Many Requests</body></html>",
+                ),
+                _ => HttpResponse::new(StatusCode::OK, PRICE),
+            })
+        }
+    }
+
+    fn send(pool: &KeyPool, transport: Keyed) -> CmcResult<()> {
+        let cmc = CmcBuilder::new(pool.clone())
+            .transport(transport)
+            .build()
+            .unwrap();
+        cmc.quotes().by_id(CryptoId(1)).send().map(|_| ())
+    }
+
+    #[test]
+    fn keys_round_robin() {
+        let pool = KeyPool::new(["key-a", "key-b"]);
+        let transport = Keyed::default();
+        let used = transport.used.clone();
+        let cmc = CmcBuilder::new(pool.clone())
+            .transport(transport)
+            .build()
+            .unwrap();
+        for _ in 0..3 {
+            cmc.quotes().by_id(CryptoId(1)).send().unwrap();
+        }
+
+        assert_eq!(*used.lock().unwrap(), ["key-a", "key-b", "key-a"]);
+        let usage = pool.usage();
+        assert_eq!(usage[0].key, "***-a");
+        assert_eq!((usage[0].requests, usage[0].credits), (2, 2));
+        assert_eq!((usage[1].requests, usage[1].credits), (1, 1));
+        assert!(!format!("{:?}", pool).contains("key-a"));
+        assert_eq!(KeyPool::new(["abc"]).usage()[0].key, "**c");
+        assert_eq!(KeyPool::new(["0123456789"]).usage()[0].key, "******6789");
+    }
+
+    #[test]
+    fn keys_failover() {
+        let pool = KeyPool::new(["limited", "spare"]);
+        let transport = Keyed::default();
+        let used = transport.used.clone();
+        send(&pool, transport).unwrap();
+
+        assert_eq!(*used.lock().unwrap(), ["limited", "spare"]);
+        let usage = pool.usage();
+        assert_eq!(usage[0].rate_limited, 1);
+        assert!(usage[0].cooldown.is_some());
+        assert_eq!(usage[1].cooldown, None);
+
+        // The limited key rests, so the next request goes to the spare key directly.
+        let transport = Keyed::default();
+        let used = transport.used.clone();
+        send(&pool, transport).unwrap();
+        assert_eq!(*used.lock().unwrap(), ["spare"]);
+    }
+
+    #[test]
+    fn keys_failover_without_json() {
+        let pool = KeyPool::new(["proxied", "spare"]);
+        let transport = Keyed::default();
+        let used = transport.used.clone();
+        send(&pool, transport).unwrap();
+
+        assert_eq!(*used.lock().unwrap(), ["proxied", "spare"]);
+        assert_eq!(pool.usage()[0].rate_limited, 1);
+    }
+
+    #[test]
+    fn keys_all_rate_limited() {
+        let pool = KeyPool::new(["limited"]);
+        let result = send(&pool, Keyed::default());
+        assert!(
+            matches!(result, Err(CmcErrors::RateLimited(_))),
+            "{:?}",
+            result
+        );
+        let result = send(&pool, Keyed::default());
+        assert!(
+            matches!(result, Err(CmcErrors::RateLimited(_))),
+            "{:?}",
+            result
+        );
+        assert_eq!(pool.usage()[0].requests, 1);
+    }
+
+    #[test]
+    fn keys_most_credits() {
+        let info = |left: i64| -> cmc::api::key::KeyInfo {
+            serde_json::from_value(serde_json::json!({
+                "plan": {"credit_limit_monthly": 10000, "credit_limit_monthly_reset": "", "credit_limit_monthly_reset_timestamp": "2022-07-01T00:00:00.000Z", "rate_limit_minute": 30},
+                "usage": {
+                    "current_minute": {"requests_made": 0, "requests_left": 30},
+                    "current_day": {"credits_used": 0, "credits_left": null},
+                    "current_month": {"credits_used": 0, "credits_left": left}
+                }
+            }))
+            .unwrap()
+        };
+        let pool = KeyPool::new(["key-a", "key-b"]).strategy(Strategy::MostCredits);
+        pool.seed("key-a", &info(10));
+        pool.seed("key-b", &info(500));
+
+        let transport = Keyed::default();
+        let used = transport.used.clone();
+        send(&pool, transport).unwrap();
+        assert_eq!(*used.lock().unwrap(), ["key-b"]);
+        assert_eq!(pool.usage()[1].credits_left, Some(499));
+    }
+
+    #[test]
+    fn keys_empty() {
+        assert!(CmcBuilder::new(Vec::<String>::new()).build().is_err());
+        assert!(CmcBuilder::new(["key", ""]).build().is_err());
+    }
+}
+
+#[cfg(all(test, feature = "async"))]
+mod parity_tests {
+    use super::mock::{serve, CONVERSION, EXCHANGE_INFO, FIAT_MAP, PRICE};
+    use cmc::{CryptoId, FiatId, SortFiat};
+
+    /// Runs the same call through the blocking and the async client and compares the
+    /// request line and the result.
+    macro_rules! parity {
+        ($body:expr, |$cmc:ident| $call:expr) => {{
+            let (url, request) = serve($body);
+            let $cmc = cmc::CmcBuilder::new("key").base_url(&url).build().unwrap();
+            let blocking = format!("{:?}", $call);
+            let blocking_request = request.recv().unwrap();
+
+            let (url, request) = serve($body);
+            let $cmc = cmc::async_api::CmcBuilder::new("key")
+                .base_url(&url)
+                .build()
+                .unwrap();
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .unwrap();
+            let future = runtime.block_on(async { format!("{:?}", $call.await) });
+            let async_request = request.recv().unwrap();
+
+            assert_eq!(blocking_request, async_request);
+            assert_eq!(blocking, future);
+            assert!(blocking.starts_with("Ok("), "{}", blocking);
+        }};
+    }
+
+    #[test]
+    fn parity_price() {
+        parity!(PRICE, |cmc| cmc.price(CryptoId(1)));
+    }
+
+    #[test]
+    fn parity_quotes() {
+        parity!(PRICE, |cmc| cmc.quotes().by_id(CryptoId(1)).send());
+    }
+
+    #[test]
+    fn parity_conversion() {
+        parity!(CONVERSION, |cmc| cmc
+            .conversion(1.6)
+            .by_id(CryptoId(1027))
+            .convert_id(FiatId(2790))
+            .time("2022-06-01")
+            .send_with_status());
+    }
+
+    #[test]
+    fn parity_exchange_info() {
+        parity!(EXCHANGE_INFO, |cmc| cmc
+            .exchange_info()
+            .by_slug("binance")
+            .send());
+    }
+
+    #[test]
+    fn parity_fiat_id_map() {
+        parity!(FIAT_MAP, |cmc| cmc.fiat_id_map(1, 10, SortFiat::Id));
+    }
+}
+
+#[cfg(all(test, feature = "async"))]
+mod coalesce_tests {
+    use super::mock::PRICE;
+    use cmc::async_api::CmcBuilder;
+    use cmc::transport::{AsyncHttpTransport, HttpRequest, HttpResponse, TransportFuture};
+    use cmc::CryptoId;
+    use reqwest::StatusCode;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    /// Answers after yielding a few times, so concurrent calls overlap.
+    #[derive(Debug, Default)]
+    struct Slow {
+        hits: Arc<AtomicUsize>,
+    }
+
+    impl AsyncHttpTransport for Slow {
+        fn send(&self, _request: HttpRequest) -> TransportFuture<'_> {
+            Box::pin(async move {
+                self.hits.fetch_add(1, Ordering::SeqCst);
+                for _ in 0..10 {
+                    tokio::task::yield_now().await;
+                }
+                Ok(HttpResponse::new(StatusCode::OK, PRICE))
+            })
+        }
+    }
+
+    fn concurrent_calls(coalesce: bool) -> usize {
+        let transport = Slow::default();
+        let hits = transport.hits.clone();
+        let cmc = CmcBuilder::new("key")
+            .transport(transport)
+            .coalesce(coalesce)
+            .build()
+            .unwrap();
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        runtime.block_on(async {
+            let tasks: Vec<_> = (0..5)
+                .map(|_| {
+                    let cmc = cmc.clone();
+                    tokio::spawn(async move { cmc.price(CryptoId(1)).await })
+                })
+                .collect();
+            for task in tasks {
+                assert!(task.await.unwrap().is_ok());
+            }
+        });
+        hits.load(Ordering::SeqCst)
+    }
+
+    #[test]
+    fn coalesce_concurrent_calls() {
+        assert_eq!(concurrent_calls(false), 5);
+        assert_eq!(concurrent_calls(true), 1);
+    }
+}
+
+#[cfg(all(test, feature = "async"))]
+mod batch_tests {
+    use super::deserialize_tests::amount;
+    use super::mock::{two_btc, PRICE};
+    use cmc::api::Amount;
+    use cmc::async_api::CmcBuilder;
+    use cmc::errors::CmcErrors;
+    use cmc::transport::{AsyncHttpTransport, HttpRequest, HttpResponse, TransportFuture};
+    use cmc::CryptoId;
+    use reqwest::StatusCode;
+    use std::future::Future;
+    use std::pin::pin;
+    use std::sync::{Arc, Mutex};
+    use std::task::{Context, Poll, Wake, Waker};
+    use std::time::Duration;
+
+    /// Answers every requested id with a price of the id times 10, and records the queries.
+    #[derive(Debug, Default)]
+    struct Echo {
+        queries: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl AsyncHttpTransport for Echo {
+        fn send(&self, request: HttpRequest) -> TransportFuture<'_> {
+            Box::pin(async move {
+                let ids = request
+                    .url
+                    .query_pairs()
+                    .find(|(name, _)| name == "id")
+                    .map(|(_, ids)| ids.into_owned())
+                    .unwrap();
+                self.queries.lock().unwrap().push(ids.clone());
+
+                let (head, rest) = PRICE.split_once(r#""data":{"#).unwrap();
+                let entry = rest.strip_suffix('}').unwrap().strip_suffix('}').unwrap();
+                let entries: Vec<_> = ids
+                    .split(',')
+                    .map(|id| {
+                        entry
+                            .replace(r#""1":{"id":1,"#, &format!(r#""{id}":{{"id":{id},"#))
+                            .replace("21864.74930690019", &format!("{}0", id))
+                    })
+                    .collect();
+                let body = format!(r#"{}"data":{{{}}}}}"#, head, entries.join(","));
+                Ok(HttpResponse::new(StatusCode::OK, body))
+            })
+        }
+    }
+
+    fn batched(ids: &[u64], max_size: usize) -> (Vec<Amount>, Vec<String>) {
+        let transport = Echo::default();
+        let queries = transport.queries.clone();
+        let cmc = CmcBuilder::new("key")
+            .transport(transport)
+            .batch(Duration::from_millis(20), max_size)
+            .build()
+            .unwrap();
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .unwrap();
+        let prices = runtime.block_on(async {
+            let tasks: Vec<_> = ids
+                .iter()
+                .map(|&id| {
+                    let cmc = cmc.clone();
+                    tokio::spawn(async move { cmc.price(CryptoId(id)).await })
+                })
+                .collect();
+            let mut prices = Vec::new();
+            for task in tasks {
+                prices.push(task.await.unwrap().unwrap());
+            }
+            prices
+        });
+        let queries = queries.lock().unwrap().clone();
+        (prices, queries)
+    }
+
+    #[test]
+    fn batch_window() {
+        let (prices, queries) = batched(&[1, 1027, 825, 1027], 100);
+        let expected: Vec<_> = ["10", "10270", "8250", "10270"].map(amount).into();
+        assert_eq!(prices, expected);
+        assert_eq!(queries, vec!["1,1027,825"]);
+    }
+
+    #[test]
+    fn batch_max_size() {
+        let (prices, queries) = batched(&[1, 2, 3, 4, 5], 2);
+        let expected: Vec<_> = ["10", "20", "30", "40", "50"].map(amount).into();
+        assert_eq!(prices, expected);
+        assert_eq!(queries, vec!["1,2", "3,4", "5"]);
+    }
+
+    /// Polls a future once outside of any Tokio runtime.
+    pub fn poll_once<F: Future>(future: F) -> Poll<F::Output> {
+        struct Noop;
+        impl Wake for Noop {
+            fn wake(self: Arc<Self>) {}
+        }
+        let waker = Waker::from(Arc::new(Noop));
+        pin!(future).poll(&mut Context::from_waker(&waker))
+    }
+
+    /// Answers symbol lookups with two cryptocurrencies sharing BTC.
+    #[derive(Debug)]
+    struct TwoBtc;
+
+    impl AsyncHttpTransport for TwoBtc {
+        fn send(&self, request: HttpRequest) -> TransportFuture<'_> {
+            let body = two_btc(&request);
+            Box::pin(async move { Ok(HttpResponse::new(StatusCode::OK, body)) })
+        }
+    }
+
+    #[test]
+    fn batch_ambiguous_symbol() {
+        let cmc = CmcBuilder::new("key")
+            .transport(TwoBtc)
+            .batch(Duration::from_millis(20), 100)
+            .build()
+            .unwrap();
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .unwrap();
+        match runtime.block_on(cmc.price("BTC")) {
+            Err(CmcErrors::Ambiguous(symbol, ids)) => {
+                assert_eq!(symbol, "BTC");
+                assert_eq!(ids, vec![CryptoId(1), CryptoId(2)]);
+            }
+            other => panic!("expected an ambiguous symbol, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn batch_without_runtime() {
+        let cmc = CmcBuilder::new("key")
+            .transport(Echo::default())
+            .batch(Duration::from_millis(20), 100)
+            .build()
+            .unwrap();
+        assert!(matches!(
+            poll_once(cmc.price(CryptoId(1))),
+            Poll::Ready(Err(CmcErrors::NoRuntime))
+        ));
+    }
+}
+
+#[cfg(test)]
+mod watch_tests {
+    use super::mock::PRICE;
+    use cmc::api::CmcResult;
+    use cmc::transport::{HttpRequest, HttpResponse, HttpTransport};
+    use cmc::{CmcBuilder, CryptoId};
+    use reqwest::StatusCode;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    /// Quote of BTC whose `last_updated` changes from the third poll on.
+    fn poll(hit: usize) -> HttpResponse {
+        let body = if hit < 2 {
+            PRICE.to_owned()
+        } else {
+            PRICE.replace("2022-06-16T07:18:00.000Z", "2022-06-16T07:19:00.000Z")
+        };
+        HttpResponse::new(StatusCode::OK, body)
+    }
+
+    #[derive(Debug, Default)]
+    struct Ticker {
+        hits: Arc<AtomicUsize>,
+    }
+
+    impl HttpTransport for Ticker {
+        fn send(&self, _request: HttpRequest) -> CmcResult<HttpResponse> {
+            Ok(poll(self.hits.fetch_add(1, Ordering::SeqCst)))
+        }
+    }
+
+    #[test]
+    fn watch_changed_only() {
+        let ticker = Ticker::default();
+        let hits = ticker.hits.clone();
+        let cmc = CmcBuilder::new("key").transport(ticker).build().unwrap();
+
+        let updates: Vec<_> = cmc
+            .watch_quotes(CryptoId(1), Duration::from_millis(10))
+            .take(2)
+            .map(|update| update.unwrap())
+            .collect();
+
+        assert_eq!(hits.load(Ordering::SeqCst), 3);
+        assert_eq!(updates[0].id, CryptoId(1));
+        assert!(updates[0].previous.is_none());
+        assert_eq!(
+            updates[1].previous.as_ref(),
+            Some(&updates[0].quote.last_updated)
+        );
+        assert_ne!(updates[1].quote.last_updated, updates[0].quote.last_updated);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn watch_stream() {
+        use cmc::transport::{AsyncHttpTransport, TransportFuture};
+
+        #[derive(Debug, Default)]
+        struct AsyncTicker {
+            hits: Arc<AtomicUsize>,
+        }
+
+        impl AsyncHttpTransport for AsyncTicker {
+            fn send(&self, _request: HttpRequest) -> TransportFuture<'_> {
+                Box::pin(async move { Ok(poll(self.hits.fetch_add(1, Ordering::SeqCst))) })
+            }
+        }
+
+        let ticker = AsyncTicker::default();
+        let hits = ticker.hits.clone();
+        let cmc = cmc::async_api::CmcBuilder::new("key")
+            .transport(ticker)
+            .build()
+            .unwrap();
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .unwrap();
+        runtime.block_on(async {
+            let mut updates = cmc.watch_quotes(CryptoId(1), Duration::from_millis(10));
+            let first = updates.recv().await.unwrap().unwrap();
+            let second = updates.recv().await.unwrap().unwrap();
+            assert!(first.previous.is_none());
+            assert_eq!(second.previous, Some(first.quote.last_updated));
+        });
+        assert_eq!(hits.load(Ordering::SeqCst), 3);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn watch_stream_without_runtime() {
+        use super::batch_tests::poll_once;
+        use cmc::errors::CmcErrors;
+        use std::task::Poll;
+
+        let cmc = cmc::async_api::Cmc::new("key");
+        let mut updates = cmc.watch_quotes(CryptoId(1), Duration::from_millis(10));
+        assert!(matches!(
+            poll_once(updates.recv()),
+            Poll::Ready(Some(Err(CmcErrors::NoRuntime)))
+        ));
+        assert!(matches!(poll_once(updates.recv()), Poll::Ready(None)));
+    }
+}
+
+#[cfg(test)]
+mod alerts_tests {
+    use super::deserialize_tests::amount;
+    use super::mock::PRICE;
+    use cmc::alerts::{AlertKind, Alerts, Condition, Rule};
+    use cmc::api::cryptocurrency::quotes_latest_v2::QLv2Id;
+    use cmc::CryptoId;
+    use std::sync::mpsc;
+    use std::time::{Duration, Instant};
+
+    fn quotes(price: &str, rank: u64) -> QLv2Id {
+        let json = PRICE
+            .replace("21864.74930690019", price)
+            .replace(r#""cmc_rank":1"#, &format!(r#""cmc_rank":{}"#, rank));
+        serde_json::from_str(&json).unwrap()
+    }
+
+    fn minutes(start: Instant, minutes: u64) -> Instant {
+        start + Duration::from_secs(minutes * 60)
+    }
+
+    #[test]
+    fn alerts_threshold_dedup() {
+        let rule = Rule::new("high", CryptoId(1), Condition::Above(amount("25000")));
+        let mut alerts = Alerts::new().rule(rule);
+        let fired: Vec<_> = ["21864", "26000", "27000", "20000", "26000"]
+            .iter()
+            .map(|price| alerts.evaluate(&quotes(price, 1)).len())
+            .collect();
+        assert_eq!(fired, vec![0, 1, 0, 0, 1]);
+    }
+
+    #[test]
+    fn alerts_currency_case() {
+        let rule = Rule::new("high", CryptoId(1), Condition::Above(amount("25000")));
+        let mut alerts = Alerts::new().currency("usd").rule(rule);
+        assert_eq!(alerts.evaluate(&quotes("26000", 1)).len(), 1);
+    }
+
+    #[test]
+    fn alerts_cooldown() {
+        let rule = Rule::new("low", CryptoId(1), Condition::Below(amount("20000")))
+            .cooldown(Duration::from_secs(3600));
+        let mut alerts = Alerts::new().rule(rule);
+        let start = Instant::now();
+        assert_eq!(alerts.evaluate_at(&quotes("19000", 1), start).len(), 1);
+        assert_eq!(
+            alerts
+                .evaluate_at(&quotes("21000", 1), minutes(start, 1))
+                .len(),
+            0
+        );
+        assert_eq!(
+            alerts
+                .evaluate_at(&quotes("19000", 1), minutes(start, 2))
+                .len(),
+            0
+        );
+        assert_eq!(
+            alerts
+                .evaluate_at(&quotes("19000", 1), minutes(start, 61))
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn alerts_percent_change() {
+        let condition = Condition::PercentChange {
+            percent: amount("5"),
+            minutes: 10,
+        };
+        let mut alerts = Alerts::new().rule(Rule::new("move", CryptoId(1), condition));
+        let start = Instant::now();
+        assert!(alerts.evaluate_at(&quotes("100", 1), start).is_empty());
+        assert!(alerts
+            .evaluate_at(&quotes("120", 1), minutes(start, 5))
+            .is_empty());
+        let events = alerts.evaluate_at(&quotes("110", 1), minutes(start, 10));
+        assert_eq!(
+            events[0].kind,
+            AlertKind::PercentChange {
+                from: amount("100"),
+                to: amount("110"),
+                percent: amount("10"),
+                minutes: 10,
+            }
+        );
+    }
+
+    #[test]
+    fn alerts_rank_change_cooldown() {
+        let rule = Rule::new("rank", CryptoId(1), Condition::RankChange)
+            .cooldown(Duration::from_secs(3600));
+        let mut alerts = Alerts::new().rule(rule);
+        let start = Instant::now();
+        let kinds: Vec<_> = [(0, 1), (1, 2), (2, 3), (61, 3), (62, 4)]
+            .iter()
+            .flat_map(|&(at, rank)| alerts.evaluate_at(&quotes("21864", rank), minutes(start, at)))
+            .map(|event| event.kind)
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                AlertKind::RankChange { from: 1, to: 2 },
+                AlertKind::RankChange { from: 3, to: 4 },
+            ]
+        );
+    }
+
+    #[test]
+    fn alerts_rank_change_channel() {
+        let (tx, rx) = mpsc::channel();
+        let mut alerts = Alerts::new()
+            .rule(Rule::new("rank", CryptoId(1), Condition::RankChange))
+            .channel(tx);
+        alerts.evaluate(&quotes("21864", 1));
+        alerts.evaluate(&quotes("21864", 2));
+        alerts.evaluate(&quotes("21864", 2));
+
+        let events: Vec<_> = rx.try_iter().collect();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].kind, AlertKind::RankChange { from: 1, to: 2 });
+        assert_eq!(events[0].to_string(), "[rank] BTC rank 1 -> 2");
+        assert_eq!(alerts.ids(), vec![CryptoId(1)]);
+    }
+}
+
+#[cfg(test)]
+mod portfolio_tests {
+    use super::deserialize_tests::amount;
+    use super::mock::{by_symbol, patch, two_btc, Recorder, PRICE};
+    use cmc::errors::CmcErrors;
+    use cmc::portfolio::Portfolio;
+    use cmc::transport::HttpRequest;
+    use cmc::{CmcBuilder, CryptoId, Slug, Symbol};
+    use serde_json::json;
+
+    const CONVERSION: &str = r#"{"status":{"timestamp":"2022-06-16T07:30:51.146Z","error_code":0,"error_message":null,"elapsed":1,"credit_count":1,"notice":null},"data":{"id":1,"symbol":"BTC","name":"Bitcoin","amount":1,"quote":{"EUR":{"price":15000}}}}"#;
+
+    /// BTC at 20000 USD, up 25% in 24 hours, by id or by symbol.
+    fn quotes(request: &HttpRequest) -> String {
+        let btc = patch(
+            PRICE,
+            &[
+                ("/data/1/quote/USD/price", json!(20000)),
+                ("/data/1/quote/USD/percent_change_24h", json!(25)),
+            ],
+        );
+        match request.url.path() {
+            "/v2/tools/price-conversion" => CONVERSION.to_owned(),
+            _ if request.url.query().unwrap_or_default().contains("symbol=") => by_symbol(&btc),
+            _ => btc,
+        }
+    }
+
+    #[test]
+    fn portfolio_value() {
+        let transport = Recorder::new(quotes);
+        let urls = transport.urls.clone();
+        let cmc = CmcBuilder::new("key").transport(transport).build().unwrap();
+        let portfolio = Portfolio::new()
+            .hold(CryptoId(1), amount("0.25"))
+            .hold(Symbol::new("btc"), amount("0.5"))
+            .hold(CryptoId(1), amount("0.25"));
+
+        let valuation = cmc.value(&portfolio).unwrap();
+        assert_eq!(urls.lock().unwrap().len(), 2);
+        assert_eq!(valuation.currency, "USD");
+        assert_eq!(valuation.total, amount("20000"));
+        assert_eq!(valuation.assets[0].value, amount("10000"));
+        assert_eq!(valuation.assets[1].allocation, amount("50"));
+        assert_eq!(valuation.assets[1].symbol, "BTC");
+        assert_eq!(valuation.pnl_24h, Some(amount("4000")));
+        assert_eq!(valuation.pnl_1h, None);
+    }
+
+    #[cfg(feature = "tools")]
+    #[test]
+    fn portfolio_value_at() {
+        let transport = Recorder::new(quotes);
+        let urls = transport.urls.clone();
+        let cmc = CmcBuilder::new("key").transport(transport).build().unwrap();
+        let portfolio = Portfolio::new()
+            .hold(CryptoId(1), amount("2"))
+            .convert("eur");
+
+        let valuation = cmc.value_at(&portfolio, "2022-01-01").unwrap();
+        assert_eq!(valuation.total, amount("30000"));
+        assert_eq!(valuation.assets[0].price, amount("15000"));
+        assert_eq!(valuation.pnl_24h, None);
+
+        let urls = urls.lock().unwrap();
+        assert_eq!(urls.len(), 2);
+        assert!(urls[1].contains("time=2022-01-01"));
+        assert!(urls[1].contains("convert=EUR"));
+    }
+
+    #[cfg(feature = "tools")]
+    #[test]
+    fn portfolio_value_at_once_per_crypto() {
+        let transport = Recorder::new(quotes);
+        let urls = transport.urls.clone();
+        let cmc = CmcBuilder::new("key").transport(transport).build().unwrap();
+        let portfolio = Portfolio::new()
+            .hold(CryptoId(1), amount("2"))
+            .hold(Slug::new("bitcoin"), amount("1"))
+            .convert("eur");
+
+        let valuation = cmc.value_at(&portfolio, "2022-01-01").unwrap();
+        assert_eq!(valuation.total, amount("45000"));
+        assert_eq!(valuation.assets[1].id, CryptoId(1));
+        assert_eq!(valuation.assets[1].value, amount("15000"));
+
+        let urls = urls.lock().unwrap();
+        let conversions = urls.iter().filter(|url| url.contains("price-conversion"));
+        assert_eq!(conversions.count(), 1);
+        assert_eq!(urls.len(), 3);
+    }
+
+    #[test]
+    fn portfolio_ambiguous_symbol() {
+        let cmc = CmcBuilder::new("key")
+            .transport(Recorder::new(two_btc))
+            .build()
+            .unwrap();
+        let portfolio = Portfolio::new().hold(Symbol::new("BTC"), amount("1"));
+
+        match cmc.value(&portfolio) {
+            Err(CmcErrors::Ambiguous(symbol, ids)) => {
+                assert_eq!(symbol, "BTC");
+                assert_eq!(ids, vec![CryptoId(1), CryptoId(2)]);
+            }
+            other => panic!("expected an ambiguous symbol, got {:?}", other),
+        }
+    }
+}
+
+#[cfg(test)]
+mod rates_tests {
+    use super::deserialize_tests::amount;
+    use super::mock::PRICE;
+    use cmc::api::cryptocurrency::quotes_latest_v2::QLv2Id;
+    use cmc::errors::CmcErrors;
+    use cmc::rates::{RateTable, Unit};
+    use cmc::CryptoId;
+
+    /// BTC at 20000 USD or 16000 EUR, ETH at 1000 USD, and a second "ETH" ranked lower.
+    fn snapshot() -> QLv2Id {
+        let (head, rest) = PRICE.split_once(r#""data":{"#).unwrap();
+        let btc = rest.strip_suffix("}}}}").unwrap();
+        let entry = |id: &str, symbol: &str, rank: &str, price: &str, other: &str| {
+            let entry = btc
+                .replace(r#""1":{"id":1,"#, &format!(r#""{id}":{{"id":{id},"#))
+                .replace(r#""symbol":"BTC""#, &format!(r#""symbol":"{symbol}""#))
+                .replace(r#""cmc_rank":1"#, &format!(r#""cmc_rank":{rank}"#))
+                .replace("21864.74930690019", price);
+            format!("{}{}}}}}", entry, other)
+        };
+        let eur = r#","EUR":{"price":16000,"last_updated":"2022-06-16T07:18:00.000Z"}"#;
+        let data = [
+            entry("1", "BTC", "1", "20000", eur),
+            entry("1027", "ETH", "2", "1000", ""),
+            entry("9999", "ETH", "900", "5", ""),
+        ];
+        let json = format!(r#"{}"data":{{{}}}}}"#, head, data.join(","));
+        serde_json::from_str(&json).unwrap()
+    }
+
+    #[test]
+    fn rates_cross() {
+        let table = RateTable::from_quotes(&snapshot());
+        assert_eq!(table.base(), "USD");
+        assert_eq!(table.timestamp(), &snapshot().status.timestamp);
+        assert_eq!(table.rate("ETH", "BTC").unwrap(), amount("0.05"));
+        assert_eq!(table.rate(CryptoId(9999), "usd").unwrap(), amount("5"));
+        assert_eq!(
+            table.convert(amount("2"), "btc", "EUR").unwrap(),
+            amount("32000")
+        );
+        assert_eq!(
+            table.convert(amount("800"), "EUR", CryptoId(1027)).unwrap(),
+            amount("1")
+        );
+    }
+
+    #[test]
+    fn rates_manual_and_missing() {
+        let table = RateTable::from_quotes(&snapshot()).with_rate("GBP", amount("1.25"));
+        assert_eq!(
+            table.convert(amount("100"), "GBP", "USD").unwrap(),
+            amount("125")
+        );
+        assert!(matches!(
+            table.rate("XMR", "USD"),
+            Err(CmcErrors::NotFound(_))
+        ));
+        assert!(matches!(
+            table.rate("1027", "USD"),
+            Err(CmcErrors::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn rates_numeric_symbol() {
+        // A token whose symbol is "1" must not replace BTC, CoinMarketCap id 1.
+        let mut snapshot = snapshot();
+        snapshot.data.get_mut("9999").unwrap().symbol = "1".to_owned();
+        let table = RateTable::from_quotes(&snapshot);
+        assert_eq!(table.rate(CryptoId(1), "USD").unwrap(), amount("20000"));
+        assert_eq!(table.rate("1", "USD").unwrap(), amount("5"));
+        assert!(table
+            .currencies()
+            .any(|unit| *unit == Unit::Id(CryptoId(9999))));
+        assert!(table
+            .currencies()
+            .any(|unit| *unit == Unit::Symbol("1".to_owned())));
+    }
+}
+
+#[cfg(all(test, feature = "tools"))]
+mod convert_tests {
+    use super::deserialize_tests::amount;
+    use super::mock::{by_symbol, Recorder};
+    use cmc::errors::CmcErrors;
+    use cmc::transport::HttpRequest;
+    use cmc::{AssetRef, CmcBuilder, CryptoId, Slug, Symbol};
+
+    const BY_ID: &str = r#"{"status":{"timestamp":"2024-01-01T00:00:10.000Z","error_code":0,"error_message":null,"elapsed":1,"credit_count":2,"notice":null},"data":{"id":1027,"symbol":"ETH","name":"Ethereum","amount":2.5,"last_updated":"2024-01-01T00:00:00.000Z","quote":{"EUR":{"price":5000,"last_updated":"2024-01-01T00:00:00.000Z"},"BTC":{"price":0.125,"last_updated":"2024-01-01T00:00:00.000Z"}}}}"#;
+
+    /// Answers every conversion with ETH, wrapped in a list for symbol lookups.
+    fn conversions(request: &HttpRequest) -> String {
+        if request.url.query().unwrap_or_default().contains("symbol=") {
+            by_symbol(BY_ID)
+        } else {
+            BY_ID.to_owned()
+        }
+    }
+
+    #[test]
+    fn convert_record() {
+        let transport = Recorder::new(conversions);
+        let urls = transport.urls.clone();
+        let cmc = CmcBuilder::new("key").transport(transport).build().unwrap();
+
+        let conversion = cmc
+            .convert(2.5, CryptoId(1027))
+            .convert("eur,btc")
+            .time(1704067200_i64)
+            .send()
+            .unwrap();
+        let url = urls.lock().unwrap().pop().unwrap();
+        assert!(url.contains("amount=2.5&id=1027&time=1704067200&convert=EUR%2CBTC"));
+        assert_eq!(conversion.id, 1027);
+        assert_eq!(conversion.name, "Ethereum");
+        assert!(conversion.last_updated.is_some());
+        assert_eq!(conversion.quote["EUR"].price, Some(amount("5000")));
+        assert_eq!(conversion.quote["BTC"].price, Some(amount("0.125")));
+    }
+
+    #[test]
+    fn convert_by_symbol() {
+        let transport = Recorder::new(conversions);
+        let urls = transport.urls.clone();
+        let cmc = CmcBuilder::new("key").transport(transport).build().unwrap();
+
+        let conversion = cmc.convert(1, Symbol::new("eth")).send().unwrap();
+        assert!(urls.lock().unwrap()[0].contains("symbol=ETH"));
+        assert_eq!(conversion.symbol, "ETH");
+    }
+
+    #[test]
+    fn convert_rejects_slug() {
+        let transport = Recorder::new(conversions);
+        let urls = transport.urls.clone();
+        let cmc = CmcBuilder::new("key").transport(transport).build().unwrap();
+
+        let result = cmc.convert(1, Slug::new("ethereum")).send();
+        assert!(matches!(result, Err(CmcErrors::PassIncompatible)));
+        assert!(urls.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn value_lots() {
+        let transport = Recorder::new(conversions);
+        let urls = transport.urls.clone();
+        let cmc = CmcBuilder::new("key")
+            .transport(transport)
+            .convert("EUR")
+            .build()
+            .unwrap();
+
+        let eth = AssetRef::from(CryptoId(1027));
+        let lots = vec![
+            (amount("2"), eth.clone(), "2024-01-01"),
+            (
+                amount("0.5"),
+                AssetRef::from(Symbol::new("ETH")),
+                "2024-01-01",
+            ),
+            (amount("1"), eth.clone(), "2024-01-01"),
+            (amount("3"), eth, "2024-02-01"),
+        ];
+
+        let values = cmc.value_lots(lots).unwrap();
+        assert_eq!(urls.lock().unwrap().len(), 3);
+        assert_eq!(values.len(), 4);
+        assert_eq!(values[0].value, amount("10000"));
+        assert_eq!(values[1].currency, "EUR");
+        assert_eq!(values[2].price, amount("5000"));
+        assert_eq!(values[3].time, "2024-02-01");
+    }
+}